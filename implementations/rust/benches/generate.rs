@@ -0,0 +1,79 @@
+// File: benches/generate.rs
+//
+// Dependency-free throughput benchmark for the generation hot path.
+// Run with `cargo bench --bench generate`.
+//
+// `reseed_per_call` models the previous design, where every ID seeded a fresh
+// PRNG from the system clock. The other cases use the crate's thread-local
+// Xoshiro256** state, which is seeded once per thread and then only advanced.
+
+use std::hint::black_box;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use microshard_uuid::{MicroShardGenerator, MicroShardUUID};
+
+const ITERATIONS: u32 = 2_000_000;
+const MICROS: u64 = 1_765_503_300_123_456;
+
+fn bench(name: &str, f: impl FnMut() -> u128) {
+    bench_per_call(name, 1, f);
+}
+
+/// Times `f`, which mints `ids_per_call` IDs per invocation.
+fn bench_per_call(name: &str, ids_per_call: u32, mut f: impl FnMut() -> u128) {
+    let calls = ITERATIONS / ids_per_call;
+    // Warm up (and seed the thread-local RNG outside the timed loop).
+    for _ in 0..calls.min(10_000) {
+        black_box(f());
+    }
+    let start = Instant::now();
+    for _ in 0..calls {
+        black_box(f());
+    }
+    let elapsed = start.elapsed();
+    let ids = (calls * ids_per_call) as f64;
+    println!(
+        "{name:<18} {:>8.1} ns/id {:>10.2} M ids/s",
+        elapsed.as_nanos() as f64 / ids,
+        ids / elapsed.as_secs_f64() / 1e6
+    );
+}
+
+/// One SplitMix64 step: the cheapest possible "fresh RNG per call" baseline.
+fn reseeded_random() -> u64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+    let mut z = nanos.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (z ^ (z >> 31)) & ((1 << 36) - 1)
+}
+
+fn main() {
+    bench("reseed_per_call", || {
+        let id = MicroShardUUID::from_micros(black_box(MICROS), 7).unwrap();
+        id.as_u128() ^ reseeded_random() as u128
+    });
+    bench("from_micros", || {
+        MicroShardUUID::from_micros(black_box(MICROS), 7)
+            .unwrap()
+            .as_u128()
+    });
+    bench("generate", || {
+        MicroShardUUID::generate(7).unwrap().as_u128()
+    });
+
+    let mut ids = MicroShardGenerator::new(7).unwrap();
+    bench("generator.next_at", || {
+        ids.next_at(black_box(MICROS)).unwrap().as_u128()
+    });
+    bench("generator.next", || ids.next().unwrap().as_u128());
+
+    let mut batch = vec![MicroShardUUID::from_micros(MICROS, 7).unwrap(); 4096];
+    bench_per_call("fill_batch(4096)", 4096, || {
+        ids.fill_batch(&mut batch).unwrap();
+        batch[4095].as_u128()
+    });
+}
@@ -0,0 +1,67 @@
+// File: benches/hex.rs
+//
+// Dependency-free throughput benchmark for hex formatting and parsing.
+// Run with `cargo bench --bench hex`.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use microshard_uuid::MicroShardUUID;
+
+const ITERATIONS: u32 = 2_000_000;
+
+fn bench(name: &str, mut f: impl FnMut(u32) -> usize) {
+    for i in 0..10_000 {
+        black_box(f(i));
+    }
+    let start = Instant::now();
+    for i in 0..ITERATIONS {
+        black_box(f(i));
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{name:<18} {:>8.1} ns/id {:>10.2} M ids/s",
+        elapsed.as_nanos() as f64 / ITERATIONS as f64,
+        ITERATIONS as f64 / elapsed.as_secs_f64() / 1e6
+    );
+}
+
+fn main() {
+    let ids: Vec<MicroShardUUID> = (0..1024u64)
+        .map(|i| MicroShardUUID::from_parts(i * 1_000_003, i as u32 * 7_919, i * 104_729).unwrap())
+        .collect();
+    let texts: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+    let simple: Vec<String> = ids.iter().map(|id| id.to_simple_string()).collect();
+
+    let mut buf = [0u8; 36];
+    bench("encode_hyphenated", |i| {
+        ids[i as usize % 1024].encode_hyphenated(&mut buf).len()
+    });
+    let mut buf = [0u8; 32];
+    bench("encode_simple", |i| {
+        ids[i as usize % 1024].encode_simple(&mut buf).len()
+    });
+    bench("to_string", |i| ids[i as usize % 1024].to_string().len());
+    bench("parse", |i| {
+        MicroShardUUID::parse(black_box(&texts[i as usize % 1024]))
+            .unwrap()
+            .shard_id() as usize
+    });
+    bench("parse_simple", |i| {
+        MicroShardUUID::parse_simple(black_box(&simple[i as usize % 1024]))
+            .unwrap()
+            .shard_id() as usize
+    });
+    // FromStr takes the SIMD kernels; `parse` above is the const, scalar path
+    bench("from_str", |i| {
+        black_box(&texts[i as usize % 1024])
+            .parse::<MicroShardUUID>()
+            .unwrap()
+            .shard_id() as usize
+    });
+    bench("lenient_simple", |i| {
+        MicroShardUUID::parse_lenient(black_box(&simple[i as usize % 1024]))
+            .unwrap()
+            .shard_id() as usize
+    });
+}
@@ -0,0 +1,68 @@
+//! SQLite loadable extension for minting and inspecting MicroShard IDs in
+//! SQL, including triggers, on top of the `microshard-uuid` crate.
+//!
+//! | Function | Arguments | Return | Description |
+//! | :--- | :--- | :--- | :--- |
+//! | `microshard_generate` | `shard` | `BLOB` | A new ID from the system clock (`TEXT` with the `text` feature). |
+//! | `microshard_ts` | `id` | `INT` | Creation time as Unix microseconds. |
+//! | `microshard_shard` | `id` | `INT` | The 32-bit Shard ID. |
+//!
+//! The `microshard_time`, `microshard_range_start`, `microshard_range_end`
+//! and `microshard_range` functions of
+//! `microshard_uuid::sqlite::register_functions` are installed as well.
+//! IDs may be 16-byte `BLOB`s or canonical `TEXT`; the decoders return `NULL`
+//! for `NULL` and raise an error for anything that is not a MicroShard ID.
+//!
+//! ```text
+//! sqlite> .load ./target/release/libmicroshard_sqlite
+//! sqlite> CREATE TABLE events (id BLOB PRIMARY KEY, shard INT);
+//! sqlite> CREATE TRIGGER events_id AFTER INSERT ON events WHEN NEW.id IS NULL
+//!    ...> BEGIN UPDATE events SET id = microshard_generate(NEW.shard) WHERE rowid = NEW.rowid; END;
+//! ```
+
+use std::os::raw::{c_char, c_int};
+
+use microshard_uuid::{sqlite, MicroShardError, MicroShardUUID};
+use rusqlite::ffi;
+use rusqlite::functions::FunctionFlags;
+use rusqlite::{Connection, Error, Result};
+
+/// Entry point called by `sqlite3_load_extension` (the default name, so no
+/// entry point argument is needed).
+///
+/// # Safety
+/// Must only be called by SQLite with valid `db` and `p_api` pointers.
+#[no_mangle]
+pub unsafe extern "C" fn sqlite3_extension_init(
+    db: *mut ffi::sqlite3,
+    pz_err_msg: *mut *mut c_char,
+    p_api: *mut ffi::sqlite3_api_routines,
+) -> c_int {
+    Connection::extension_init2(db, pz_err_msg, p_api, extension_init)
+}
+
+fn extension_init(conn: Connection) -> Result<bool> {
+    sqlite::register_functions(&conn)?;
+
+    // Not deterministic: every call mints a new ID
+    let volatile = FunctionFlags::SQLITE_UTF8;
+    conn.create_scalar_function("microshard_generate", 1, volatile, |ctx| {
+        let shard: i64 = ctx.get(0)?;
+        let shard = u32::try_from(shard)
+            .map_err(|_| user_error(MicroShardError::InvalidShardId(u32::MAX)))?;
+        MicroShardUUID::generate(shard).map_err(user_error)
+    })?;
+
+    let flags = FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC;
+    conn.create_scalar_function("microshard_ts", 1, flags, |ctx| {
+        let id: Option<MicroShardUUID> = ctx.get(0)?;
+        Ok(id.map(|id| id.timestamp_micros() as i64))
+    })?;
+
+    // Registered per connection, not persistently
+    Ok(false)
+}
+
+fn user_error(e: MicroShardError) -> Error {
+    Error::UserFunctionError(Box::new(e))
+}
@@ -0,0 +1,133 @@
+import os
+import platform
+import sqlite3
+import sys
+import time
+import unittest
+
+# ------------------------------------------------------------------
+# Configuration & Setup
+# ------------------------------------------------------------------
+
+PROFILE = os.environ.get("PROFILE", "release")
+
+system = platform.system()
+if system == "Windows":
+    EXT_FILENAME = "microshard_sqlite.dll"
+elif system == "Darwin":
+    EXT_FILENAME = "libmicroshard_sqlite.dylib"
+else:
+    EXT_FILENAME = "libmicroshard_sqlite.so"
+
+# SQLite often requires absolute paths for security
+EXT_PATH = os.path.abspath(os.path.join("target", PROFILE, EXT_FILENAME))
+
+# Reference ID: 2024-05-01T10:00:00.123456Z, shard 4242, random 0
+REF_MICROS = 1_714_557_600_123_456
+REF_SHARD = 4242
+
+
+def ref_blob(micros=REF_MICROS):
+    """Packs the reference ID by hand, independently of the extension"""
+    shard = REF_SHARD
+    high = ((micros >> 6) << 16) | (8 << 12) | ((micros & 63) << 6) | (shard >> 26)
+    low = (2 << 62) | ((shard & 0x3FFFFFF) << 36)
+    return ((high << 64) | low).to_bytes(16, "big")
+
+
+class TestMicroShardSQLiteExtension(unittest.TestCase):
+    @classmethod
+    def setUpClass(cls):
+        """Check the extension was built before running tests"""
+        if not os.path.exists(EXT_PATH):
+            print(f"\n❌ Extension binary not found at: {EXT_PATH}")
+            print("\nPLEASE BUILD FIRST:\n  cargo build --release")
+            sys.exit(1)
+
+    def setUp(self):
+        """Create a fresh in-memory database for every test"""
+        self.conn = sqlite3.connect(":memory:")
+        self.conn.enable_load_extension(True)
+        self.conn.load_extension(EXT_PATH)
+
+    def tearDown(self):
+        self.conn.close()
+
+    def scalar(self, sql, *args):
+        return self.conn.execute(sql, args).fetchone()[0]
+
+    def test_generate(self):
+        before = time.time_ns() // 1000
+        blob = self.scalar("SELECT microshard_generate(7)")
+        after = time.time_ns() // 1000
+
+        self.assertIsInstance(blob, bytes)
+        self.assertEqual(len(blob), 16)
+        self.assertEqual(blob[6] >> 4, 8)  # Version 8
+        self.assertEqual(blob[8] >> 6, 2)  # Variant 2
+        self.assertEqual(self.scalar("SELECT microshard_shard(?)", blob), 7)
+        self.assertTrue(before <= self.scalar("SELECT microshard_ts(?)", blob) <= after)
+
+        # Largest shard, and every call mints a new ID
+        self.assertEqual(
+            self.scalar("SELECT microshard_shard(microshard_generate(4294967295))"),
+            4294967295,
+        )
+        ids = self.conn.execute(
+            "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 1000) "
+            "SELECT microshard_generate(1) FROM n"
+        ).fetchall()
+        self.assertEqual(len(set(ids)), 1000)
+
+    def test_generate_rejects_bad_shard(self):
+        for shard in (-1, 4294967296):
+            with self.assertRaises(sqlite3.OperationalError):
+                self.scalar("SELECT microshard_generate(?)", shard)
+
+    def test_decode_reference(self):
+        blob = ref_blob()
+        self.assertEqual(self.scalar("SELECT microshard_ts(?)", blob), REF_MICROS)
+        self.assertEqual(self.scalar("SELECT microshard_time(?)", blob), REF_MICROS)
+        self.assertEqual(self.scalar("SELECT microshard_shard(?)", blob), REF_SHARD)
+
+        # Canonical TEXT is accepted too
+        h = blob.hex()
+        text = f"{h[:8]}-{h[8:12]}-{h[12:16]}-{h[16:20]}-{h[20:]}"
+        self.assertEqual(self.scalar("SELECT microshard_shard(?)", text), REF_SHARD)
+
+        self.assertIsNone(self.scalar("SELECT microshard_ts(NULL)"))
+        for bad in (b"\x01\x02", bytes(16)):
+            with self.assertRaises(sqlite3.OperationalError):
+                self.scalar("SELECT microshard_ts(?)", bad)
+
+    def test_range_bounds(self):
+        self.conn.execute("CREATE TABLE events (id BLOB PRIMARY KEY)")
+        for micros in (REF_MICROS - 1, REF_MICROS, REF_MICROS + 1):
+            self.conn.execute("INSERT INTO events VALUES (?)", (ref_blob(micros),))
+        window = (
+            "FROM events WHERE id >= microshard_range_start('2024-05-01T10:00:00.123456Z') "
+            "AND id < microshard_range_end('2024-05-01T10:00:00.123457Z')"
+        )
+        self.assertEqual(self.scalar("SELECT COUNT(*) " + window), 1)
+        plan = self.conn.execute("EXPLAIN QUERY PLAN SELECT * " + window).fetchone()[3]
+        self.assertTrue(plan.startswith("SEARCH"), plan)
+
+    def test_trigger(self):
+        self.conn.executescript(
+            """
+            CREATE TABLE events (id BLOB, shard INT);
+            CREATE TRIGGER events_id AFTER INSERT ON events WHEN NEW.id IS NULL
+            BEGIN
+                UPDATE events SET id = microshard_generate(NEW.shard) WHERE rowid = NEW.rowid;
+            END;
+            INSERT INTO events (shard) VALUES (3), (9);
+            """
+        )
+        rows = self.conn.execute(
+            "SELECT shard, microshard_shard(id) FROM events ORDER BY rowid"
+        ).fetchall()
+        self.assertEqual(rows, [(3, 3), (9, 9)])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,120 @@
+//! Connection-affinity routing for database pools.
+//!
+//! Pool wrappers (bb8, deadpool, ...) can route statements touching an ID to
+//! the same connection every time, keyed by the ID's shard, without bespoke
+//! glue in each service.
+//!
+//! [`MicroShardUUID::partition_for`] instead spreads IDs over a fixed
+//! number of partitions (Kafka topics, hash-sharded tables) using all 128
+//! bits, so even IDs from a single shard are spread evenly.
+
+use crate::{splitmix64, MicroShardUUID};
+
+/// A value that can be routed to a pool member by a stable 32-bit key.
+pub trait ShardAffinity {
+    /// The routing key. For IDs this is the embedded shard ID.
+    fn affinity_key(&self) -> u32;
+
+    /// Picks a member index in `0..pool_size` (`key % pool_size`).
+    ///
+    /// Returns `None` for an empty pool.
+    fn pool_index(&self, pool_size: usize) -> Option<usize> {
+        (pool_size > 0).then(|| self.affinity_key() as usize % pool_size)
+    }
+}
+
+impl ShardAffinity for MicroShardUUID {
+    #[inline(always)]
+    fn affinity_key(&self) -> u32 {
+        self.shard_id()
+    }
+}
+
+/// Raw shard IDs route identically to the IDs that embed them.
+impl ShardAffinity for u32 {
+    #[inline(always)]
+    fn affinity_key(&self) -> u32 {
+        *self
+    }
+}
+
+impl<T: ShardAffinity + ?Sized> ShardAffinity for &T {
+    #[inline(always)]
+    fn affinity_key(&self) -> u32 {
+        (**self).affinity_key()
+    }
+}
+
+/// Returns the pool member that `key` is pinned to, or `None` if `pool` is empty.
+pub fn pick<'a, T, K: ShardAffinity>(pool: &'a [T], key: &K) -> Option<&'a T> {
+    key.pool_index(pool.len()).map(|i| &pool[i])
+}
+
+impl MicroShardUUID {
+    /// The partition in `0..num_partitions` this ID belongs to.
+    ///
+    /// The scheme is fixed so every language port routes an ID identically.
+    /// With `H` and `L` the high and low 64 bits as unsigned integers, and
+    /// all arithmetic wrapping modulo 2^64:
+    ///
+    /// ```text
+    /// mix(x) = x += 0x9E3779B97F4A7C15
+    ///          x = (x ^ (x >> 30)) * 0xBF58476D1CE4E5B9
+    ///          x = (x ^ (x >> 27)) * 0x94D049BB133111EB
+    ///          x ^ (x >> 31)                             (SplitMix64)
+    /// hash  = mix(L ^ mix(H))
+    /// partition = hash % num_partitions
+    /// ```
+    ///
+    /// ```
+    /// use microshard_uuid::MicroShardUUID;
+    ///
+    /// let id: MicroShardUUID = "01234567-89ab-8def-8123-456789abcdef".parse().unwrap();
+    /// assert_eq!(id.partition_for(1000), 225);
+    /// ```
+    ///
+    /// # Panics
+    /// If `num_partitions` is zero.
+    pub fn partition_for(&self, num_partitions: u32) -> u32 {
+        assert!(num_partitions > 0, "num_partitions must be non-zero");
+        (self.route_hash() % num_partitions as u64) as u32
+    }
+
+    /// The bucket in `0..buckets` this ID maps to under jump consistent hash
+    /// (Lamping & Veach, 2014), keyed by the same 128-bit hash as
+    /// [`partition_for`](Self::partition_for).
+    ///
+    /// Growing from `n` to `n + 1` buckets moves only ~1/(n+1) of IDs, all
+    /// into the new bucket; shrinking moves only the last bucket's IDs.
+    /// Buckets must be numbered, so this suits resizable clusters where
+    /// nodes are only added or removed at the end.
+    ///
+    /// ```
+    /// use microshard_uuid::MicroShardUUID;
+    ///
+    /// let id: MicroShardUUID = "01234567-89ab-8def-8123-456789abcdef".parse().unwrap();
+    /// assert_eq!(id.jump_hash(1000), 15);
+    /// ```
+    ///
+    /// # Panics
+    /// If `buckets` is zero.
+    pub fn jump_hash(&self, buckets: u32) -> u32 {
+        assert!(buckets > 0, "buckets must be non-zero");
+        let mut key = self.route_hash();
+        let (mut b, mut j) = (0i64, 0i64);
+        while j < buckets as i64 {
+            b = j;
+            key = key.wrapping_mul(2862933555777941757).wrapping_add(1);
+            j = ((b + 1) as f64 * ((1u64 << 31) as f64 / ((key >> 33) + 1) as f64)) as i64;
+        }
+        b as u32
+    }
+
+    /// Internal: the 64-bit routing hash `mix(L ^ mix(H))`.
+    #[inline(always)]
+    fn route_hash(&self) -> u64 {
+        let mut hi = self.high();
+        let mut lo = self.low() ^ splitmix64(&mut hi);
+        splitmix64(&mut lo)
+    }
+}
@@ -0,0 +1,510 @@
+//! Cluster-wide shard assignment through a shared lease store.
+//!
+//! [`ShardLease`](crate::shard::ShardLease) keeps processes on one host
+//! apart; fleets of ephemeral pods need a store every pod can reach. A
+//! [`ShardAllocator`] claims a shard in such a store with a time-to-live,
+//! and a [`ShardAllocation`] holds the claim: it renews it from a heartbeat
+//! thread and surrenders it when dropped. A pod that dies without
+//! surrendering loses its shard once the TTL runs out, so shards are never
+//! stranded for longer than that. Enabled with the `shard-allocator` feature.
+//!
+//! [`RedisAllocator`] is the bundled backend (`SET NX PX` plus
+//! compare-and-set scripts, spoken over a plain TCP connection). Other
+//! stores plug in through the trait; with etcd, for example, `try_claim` is
+//! a transaction putting the key only if absent, bound to a lease granted
+//! for the TTL, and `renew` is a lease keep-alive.
+//!
+//! **Safety margin:** a claim can expire while its holder is paused (GC,
+//! SIGSTOP, network partition), letting another pod take the shard. Check
+//! [`ShardAllocation::is_lost`] before generating, and pick a TTL well above
+//! the pauses you expect.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::ShardId;
+
+/// Default claim lifetime; renewed every third of it.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(15);
+
+/// A store that can hand out exclusive, expiring claims on shard IDs.
+///
+/// Each allocator instance acts as one claimant: `renew` and `surrender`
+/// only affect claims made by the same instance.
+pub trait ShardAllocator: Send + 'static {
+    /// Claims `shard` for `ttl` if no one holds it.
+    ///
+    /// Returns `Ok(false)` if another claimant holds it.
+    fn try_claim(&mut self, shard: u32, ttl: Duration) -> io::Result<bool>;
+
+    /// Extends this claimant's claim on `shard` to `ttl` from now.
+    ///
+    /// Returns `Ok(false)` if the claim has expired or been taken over.
+    fn renew(&mut self, shard: u32, ttl: Duration) -> io::Result<bool>;
+
+    /// Releases this claimant's claim on `shard`, if it still holds it.
+    fn surrender(&mut self, shard: u32) -> io::Result<()>;
+
+    /// The longest a single `try_claim` or `renew` call may block.
+    ///
+    /// A claim is treated as lost this long before its TTL runs out, since a
+    /// renewal still in flight may land too late. Defaults to zero.
+    fn timeout(&self) -> Duration {
+        Duration::ZERO
+    }
+}
+
+/// A shard claimed through a [`ShardAllocator`], kept alive by a heartbeat
+/// thread until dropped.
+///
+/// ```no_run
+/// use microshard_uuid::allocator::{RedisAllocator, ShardAllocation, DEFAULT_TTL};
+/// use microshard_uuid::MicroShardGenerator;
+///
+/// let redis = RedisAllocator::connect("redis.internal:6379")?;
+/// let allocation = ShardAllocation::acquire(redis, 0..=1023, DEFAULT_TTL)?;
+/// let mut ids = MicroShardGenerator::new(allocation.shard_id()).unwrap();
+/// // ... generate while `!allocation.is_lost()`; dropping it frees the shard.
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct ShardAllocation {
+    shard: ShardId,
+    shared: Arc<Heartbeat>,
+    thread: Option<JoinHandle<io::Result<()>>>,
+}
+
+/// Internal: state shared with the heartbeat thread.
+#[derive(Debug)]
+struct Heartbeat {
+    /// Reference point for `valid_until`.
+    started: Instant,
+    /// Nanoseconds after `started` when the claim can no longer be trusted.
+    valid_until: AtomicU64,
+    lost: AtomicBool,
+    stop: Mutex<bool>,
+    wake: Condvar,
+}
+
+impl Heartbeat {
+    /// Internal: trusts the claim until `until`.
+    fn set_valid_until(&self, until: Instant) {
+        let nanos = until.saturating_duration_since(self.started).as_nanos();
+        self.valid_until
+            .store(nanos.min(u64::MAX as u128) as u64, Ordering::Release);
+    }
+
+    fn valid_until(&self) -> Instant {
+        self.started + Duration::from_nanos(self.valid_until.load(Ordering::Acquire))
+    }
+
+    fn is_lost(&self) -> bool {
+        self.lost.load(Ordering::Acquire) || Instant::now() >= self.valid_until()
+    }
+}
+
+/// Internal: marks the claim lost when the heartbeat thread exits, however
+/// it exits (including by panicking).
+struct LostOnExit<'a>(&'a Heartbeat);
+
+impl Drop for LostOnExit<'_> {
+    fn drop(&mut self) {
+        self.0.lost.store(true, Ordering::Release);
+    }
+}
+
+impl ShardAllocation {
+    /// Claims the lowest free shard in `range` for `ttl` and starts renewing
+    /// it every `ttl / 3`.
+    ///
+    /// # Errors
+    /// - `ErrorKind::InvalidInput` for an empty range, or a TTL no longer
+    ///   than the allocator's [`timeout`](ShardAllocator::timeout).
+    /// - `ErrorKind::ResourceBusy` if every shard in the range is claimed.
+    /// - Any error from the allocator.
+    pub fn acquire<A: ShardAllocator>(
+        mut allocator: A,
+        range: RangeInclusive<u32>,
+        ttl: Duration,
+    ) -> io::Result<Self> {
+        if range.is_empty() || ttl.is_zero() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "empty shard range or zero TTL",
+            ));
+        }
+        if ttl <= allocator.timeout() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "TTL must exceed the allocator's timeout",
+            ));
+        }
+        for id in range.clone() {
+            let shard =
+                ShardId::new(id).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            // Measured from before the request: the TTL may start any time after
+            let sent = Instant::now();
+            if !allocator.try_claim(id, ttl)? {
+                continue;
+            }
+            let shared = Arc::new(Heartbeat {
+                started: sent,
+                valid_until: AtomicU64::new(0),
+                lost: AtomicBool::new(false),
+                stop: Mutex::new(false),
+                wake: Condvar::new(),
+            });
+            shared.set_valid_until(sent + ttl.saturating_sub(allocator.timeout()));
+            let beat = Arc::clone(&shared);
+            // If the thread can't start, the claim simply expires after `ttl`.
+            let thread = thread::Builder::new()
+                .name(format!("microshard-lease-{}", id))
+                .spawn(move || heartbeat(allocator, id, ttl, &beat))?;
+            return Ok(Self {
+                shard,
+                shared,
+                thread: Some(thread),
+            });
+        }
+        Err(io::Error::new(
+            io::ErrorKind::ResourceBusy,
+            format!("all shards {}..={} are claimed", range.start(), range.end()),
+        ))
+    }
+
+    /// The claimed shard ID.
+    pub fn shard_id(&self) -> u32 {
+        self.shard.get()
+    }
+
+    /// The claimed shard as a [`ShardId`].
+    pub fn shard(&self) -> ShardId {
+        self.shard
+    }
+
+    /// Whether the claim has been lost: a renewal found it expired or taken
+    /// over, or no renewal has succeeded within the TTL (less the
+    /// allocator's [`timeout`](ShardAllocator::timeout)) of being sent. Once
+    /// lost, another claimant may be using the shard; stop generating with it.
+    ///
+    /// The deadline is checked on every call, so this turns true on time even
+    /// if the heartbeat thread is stalled.
+    pub fn is_lost(&self) -> bool {
+        self.shared.is_lost()
+    }
+
+    /// Stops renewing and releases the claim, reporting any error from the
+    /// release (dropping the allocation does the same, silently).
+    ///
+    /// # Errors
+    /// Any error from [`ShardAllocator::surrender`].
+    pub fn surrender(mut self) -> io::Result<()> {
+        self.stop()
+    }
+
+    /// Internal: signals the heartbeat thread and waits for its surrender.
+    fn stop(&mut self) -> io::Result<()> {
+        let Some(thread) = self.thread.take() else {
+            return Ok(());
+        };
+        *self.shared.stop.lock().unwrap_or_else(|e| e.into_inner()) = true;
+        self.shared.wake.notify_one();
+        thread
+            .join()
+            .unwrap_or_else(|_| Err(io::Error::other("shard heartbeat thread panicked")))
+    }
+}
+
+impl Drop for ShardAllocation {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+/// Internal: the heartbeat loop. Renews every `ttl / 3` until stopped, then
+/// surrenders; gives up (marking the claim lost) if a renewal is refused, and
+/// stops renewing if the claim's deadline passes first.
+///
+/// Each successful renewal trusts the claim until `ttl - timeout` after it
+/// was *sent*, since the server may have applied it at any point after that.
+fn heartbeat<A: ShardAllocator>(
+    mut allocator: A,
+    shard: u32,
+    ttl: Duration,
+    shared: &Heartbeat,
+) -> io::Result<()> {
+    let _exit = LostOnExit(shared);
+    let interval = ttl / 3;
+    let valid_for = ttl.saturating_sub(allocator.timeout());
+    let mut stop = shared.stop.lock().unwrap_or_else(|e| e.into_inner());
+    loop {
+        // Wake for the next renewal, or at the deadline if that comes first
+        let expires = shared.valid_until();
+        let deadline = expires.min(Instant::now() + interval);
+        while !*stop {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                break;
+            }
+            stop = shared
+                .wake
+                .wait_timeout(stop, left)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+        if *stop {
+            return allocator.surrender(shard);
+        }
+        if Instant::now() >= expires {
+            break;
+        }
+        let sent = Instant::now();
+        match allocator.renew(shard, ttl) {
+            Ok(true) => shared.set_valid_until(sent + valid_for),
+            Ok(false) => return Ok(()),
+            // Transient failure: retry next tick while the claim may still be live.
+            Err(_) => {}
+        }
+    }
+
+    // The claim may still be ours on the server: release it when stopped
+    // (the release is token-guarded, so it never frees a new holder's shard).
+    shared.lost.store(true, Ordering::Release);
+    while !*stop {
+        stop = shared.wake.wait(stop).unwrap_or_else(|e| e.into_inner());
+    }
+    allocator.surrender(shard)
+}
+
+/// Renews a claim only if it still holds this claimant's token.
+const RENEW_SCRIPT: &str = "if redis.call('get', KEYS[1]) == ARGV[1] then \
+     return redis.call('pexpire', KEYS[1], ARGV[2]) else return 0 end";
+/// Deletes a claim only if it still holds this claimant's token.
+const SURRENDER_SCRIPT: &str = "if redis.call('get', KEYS[1]) == ARGV[1] then \
+     return redis.call('del', KEYS[1]) else return 0 end";
+
+/// Longest bulk reply accepted; the commands used here only return `nil`
+/// bulks, so anything big is a protocol error, not data to buffer.
+const MAX_BULK_LEN: usize = 512;
+
+/// A [`ShardAllocator`] backed by Redis (or any server speaking its protocol).
+///
+/// Shard `n` is the key `<prefix><n>` (prefix `microshard:shard:` by
+/// default), holding a token unique to this allocator:
+///
+/// - **Claim:** `SET key token NX PX ttl`.
+/// - **Renew / surrender:** `EVAL` scripts that `PEXPIRE` / `DEL` the key
+///   only if it still holds the token, so a claimant can never extend or
+///   release a shard someone else has taken over.
+///
+/// A broken connection is re-established on the next command. Only plain
+/// TCP is supported (no TLS or `AUTH`).
+#[derive(Debug)]
+pub struct RedisAllocator {
+    addr: SocketAddr,
+    conn: Option<BufReader<TcpStream>>,
+    prefix: String,
+    token: String,
+    timeout: Duration,
+}
+
+/// Internal: a RESP reply, minus arrays (no command used here returns one).
+#[derive(Debug, PartialEq)]
+enum Reply {
+    Status(String),
+    Integer(i64),
+    Bulk(Option<Vec<u8>>),
+}
+
+impl RedisAllocator {
+    /// Connects to the Redis server at `addr`.
+    ///
+    /// # Errors
+    /// Any error from resolving or connecting to `addr`.
+    pub fn connect<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        let addr = addr.to_socket_addrs()?.next().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "address resolved to nothing")
+        })?;
+        let mut allocator = Self {
+            addr,
+            conn: None,
+            prefix: "microshard:shard:".to_owned(),
+            token: String::new(),
+            timeout: Duration::from_secs(5),
+        };
+        let local = allocator.connection()?.get_ref().local_addr()?;
+        let nanos = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        allocator.token = format!("{}/{}/{}", local, std::process::id(), nanos);
+        Ok(allocator)
+    }
+
+    /// Sets the key prefix (default `microshard:shard:`), to run several
+    /// independent shard spaces on one server.
+    pub fn with_key_prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Sets the read/write timeout for each command (default 5s).
+    ///
+    /// Keep it well under the TTL so a hung server is noticed in time.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        if let Some(conn) = &self.conn {
+            let _ = conn.get_ref().set_read_timeout(Some(timeout));
+            let _ = conn.get_ref().set_write_timeout(Some(timeout));
+        }
+        self
+    }
+
+    /// The token stored under every key this allocator claims; it
+    /// identifies the holder when inspecting the store.
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Internal: the live connection, (re)connecting if needed.
+    fn connection(&mut self) -> io::Result<&mut BufReader<TcpStream>> {
+        if self.conn.is_none() {
+            let stream = TcpStream::connect_timeout(&self.addr, self.timeout)?;
+            stream.set_read_timeout(Some(self.timeout))?;
+            stream.set_write_timeout(Some(self.timeout))?;
+            stream.set_nodelay(true)?;
+            self.conn = Some(BufReader::new(stream));
+        }
+        Ok(self.conn.as_mut().expect("connection just established"))
+    }
+
+    /// Internal: sends one command and reads its reply, dropping the
+    /// connection on I/O errors so the next command reconnects.
+    fn command(&mut self, args: &[&[u8]]) -> io::Result<Reply> {
+        let mut request = Vec::with_capacity(64);
+        write!(request, "*{}\r\n", args.len())?;
+        for arg in args {
+            write!(request, "${}\r\n", arg.len())?;
+            request.extend_from_slice(arg);
+            request.extend_from_slice(b"\r\n");
+        }
+        let conn = self.connection()?;
+        let reply = conn
+            .get_mut()
+            .write_all(&request)
+            .and_then(|()| read_reply(conn));
+        if reply.is_err() {
+            self.conn = None;
+        }
+        reply?
+    }
+
+    /// Internal: runs a token-guarded script against shard's key.
+    fn eval(&mut self, script: &str, shard: u32, extra: Option<&[u8]>) -> io::Result<bool> {
+        let key = format!("{}{}", self.prefix, shard);
+        let token = self.token.clone();
+        let mut args: Vec<&[u8]> = vec![
+            b"EVAL",
+            script.as_bytes(),
+            b"1",
+            key.as_bytes(),
+            token.as_bytes(),
+        ];
+        args.extend(extra);
+        match self.command(&args)? {
+            Reply::Integer(n) => Ok(n != 0),
+            other => Err(unexpected(&other)),
+        }
+    }
+}
+
+impl ShardAllocator for RedisAllocator {
+    fn try_claim(&mut self, shard: u32, ttl: Duration) -> io::Result<bool> {
+        let key = format!("{}{}", self.prefix, shard);
+        let millis = ttl_millis(ttl);
+        let token = self.token.clone();
+        match self.command(&[
+            b"SET",
+            key.as_bytes(),
+            token.as_bytes(),
+            b"NX",
+            b"PX",
+            millis.as_bytes(),
+        ])? {
+            Reply::Status(s) if s == "OK" => Ok(true),
+            Reply::Bulk(None) => Ok(false),
+            other => Err(unexpected(&other)),
+        }
+    }
+
+    fn renew(&mut self, shard: u32, ttl: Duration) -> io::Result<bool> {
+        let millis = ttl_millis(ttl);
+        self.eval(RENEW_SCRIPT, shard, Some(millis.as_bytes()))
+    }
+
+    fn surrender(&mut self, shard: u32) -> io::Result<()> {
+        self.eval(SURRENDER_SCRIPT, shard, None).map(|_| ())
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+/// Internal: a TTL as whole milliseconds (at least 1), the unit of `PX`.
+fn ttl_millis(ttl: Duration) -> String {
+    ttl.as_millis().clamp(1, i64::MAX as u128).to_string()
+}
+
+/// Internal: the error for a reply of the wrong type.
+fn unexpected(reply: &Reply) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("unexpected Redis reply: {:?}", reply),
+    )
+}
+
+/// Internal: reads one RESP reply. Server errors (`-ERR ...`) become
+/// `ErrorKind::Other` errors carrying the message.
+fn read_reply<R: BufRead>(conn: &mut R) -> io::Result<io::Result<Reply>> {
+    let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidData, msg.to_owned());
+    let mut line = String::new();
+    if conn.read_line(&mut line)? == 0 {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    let line = line
+        .strip_suffix("\r\n")
+        .ok_or_else(|| invalid("unterminated Redis reply"))?;
+    let kind = line.as_bytes().first().copied();
+    // Every known type byte is ASCII, so the body starts on a char boundary
+    let body = || &line[1..];
+    let number = || {
+        body()
+            .parse::<i64>()
+            .map_err(|_| invalid("bad Redis integer"))
+    };
+    Ok(Ok(match kind {
+        Some(b'+') => Reply::Status(body().to_owned()),
+        Some(b'-') => return Ok(Err(io::Error::other(format!("Redis: {}", body())))),
+        Some(b':') => Reply::Integer(number()?),
+        Some(b'$') => match number()? {
+            -1 => Reply::Bulk(None),
+            len => {
+                let len = usize::try_from(len)
+                    .ok()
+                    .filter(|&len| len <= MAX_BULK_LEN)
+                    .ok_or_else(|| invalid("bad Redis length"))?;
+                let mut data = vec![0; len + 2];
+                conn.read_exact(&mut data)?;
+                data.truncate(len);
+                Reply::Bulk(Some(data))
+            }
+        },
+        _ => return Err(invalid("unsupported Redis reply")),
+    }))
+}
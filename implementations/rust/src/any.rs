@@ -0,0 +1,135 @@
+//! A container for UUIDs of any version.
+//!
+//! Systems migrating to MicroShard usually hold a mix of historical v4/v7
+//! IDs and new v8 IDs in the same column. [`AnyUuid`] classifies a raw
+//! 128-bit value once, so MicroShard payloads get typed access while legacy
+//! values round-trip untouched.
+
+use core::cmp::Ordering;
+use core::fmt;
+#[cfg(feature = "parse")]
+use core::str::FromStr;
+
+#[cfg(feature = "parse")]
+use crate::parse_hyphenated;
+use crate::{write_hyphenated, MicroShardError, MicroShardUUID};
+
+/// A UUID of any version, classified by its version and variant bits.
+///
+/// **Ordering:** values compare by their 128-bit big-endian value, exactly
+/// like PostgreSQL `uuid` and `BINARY(16)` columns, so in-memory order always
+/// matches database order. Within a single variant this keeps v7 and
+/// MicroShard IDs chronological.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum AnyUuid {
+    /// RFC 9562 version 4 (random).
+    V4(u128),
+    /// RFC 9562 version 7 (Unix milliseconds + random).
+    V7(u128),
+    /// RFC 9562 version 8 carrying the MicroShard layout.
+    V8MicroShard(MicroShardUUID),
+    /// Anything else: other versions, non-RFC variants, nil and max.
+    Other(u128),
+}
+
+impl AnyUuid {
+    /// Classifies a raw `u128`. Never fails.
+    pub fn from_u128(v: u128) -> Self {
+        let version = ((v >> 76) & 0xF) as u8;
+        let rfc_variant = (v >> 62) & 0x3 == 0b10;
+        match (version, rfc_variant) {
+            (4, true) => Self::V4(v),
+            (7, true) => Self::V7(v),
+            (8, true) => Self::V8MicroShard(MicroShardUUID(v)),
+            _ => Self::Other(v),
+        }
+    }
+
+    /// Classifies a 16-byte array (Big Endian).
+    pub fn from_bytes(bytes: [u8; 16]) -> Self {
+        Self::from_u128(u128::from_be_bytes(bytes))
+    }
+
+    /// Parses the canonical hyphenated form of any UUID.
+    #[cfg(feature = "parse")]
+    pub fn parse(s: &str) -> Result<Self, MicroShardError> {
+        parse_hyphenated(s).map(Self::from_u128)
+    }
+
+    /// Returns the raw `u128` value.
+    pub fn as_u128(&self) -> u128 {
+        match self {
+            Self::V4(v) | Self::V7(v) | Self::Other(v) => *v,
+            Self::V8MicroShard(id) => id.as_u128(),
+        }
+    }
+
+    /// Returns the UUID as a 16-byte array (Big Endian).
+    pub fn as_bytes(&self) -> [u8; 16] {
+        self.as_u128().to_be_bytes()
+    }
+
+    /// Returns the 4-bit version field.
+    pub fn version(&self) -> u8 {
+        ((self.as_u128() >> 76) & 0xF) as u8
+    }
+
+    /// Returns the MicroShard ID for v8 values, `None` otherwise.
+    pub fn as_microshard(&self) -> Option<&MicroShardUUID> {
+        match self {
+            Self::V8MicroShard(id) => Some(id),
+            _ => None,
+        }
+    }
+
+    /// Returns the embedded creation time in Unix microseconds for
+    /// time-bearing versions (v7 at millisecond precision, MicroShard v8).
+    pub fn timestamp_micros(&self) -> Option<u64> {
+        match self {
+            Self::V7(v) => Some(((v >> 80) as u64) * 1000),
+            Self::V8MicroShard(id) => Some(id.timestamp_micros()),
+            Self::V4(_) | Self::Other(_) => None,
+        }
+    }
+}
+
+impl Ord for AnyUuid {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_u128().cmp(&other.as_u128())
+    }
+}
+
+impl PartialOrd for AnyUuid {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<MicroShardUUID> for AnyUuid {
+    fn from(id: MicroShardUUID) -> Self {
+        Self::V8MicroShard(id)
+    }
+}
+
+impl TryFrom<AnyUuid> for MicroShardUUID {
+    type Error = MicroShardError;
+
+    fn try_from(any: AnyUuid) -> Result<Self, Self::Error> {
+        Self::from_u128(any.as_u128())
+    }
+}
+
+impl fmt::Display for AnyUuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hyphenated(self.as_u128(), f)
+    }
+}
+
+#[cfg(feature = "parse")]
+impl FromStr for AnyUuid {
+    type Err = MicroShardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
@@ -0,0 +1,96 @@
+//! Vectorized kernels over Apache Arrow arrays (enabled with the `arrow` feature).
+//!
+//! IDs are stored as `FixedSizeBinary(16)` columns holding the big-endian
+//! bytes from [`MicroShardUUID::as_bytes`], so they sort and compare exactly
+//! like the IDs themselves. [`to_fixed_size_binary`] and
+//! [`from_fixed_size_binary`] convert between slices and columns for hand-off
+//! to DataFusion or Parquet writers; each kernel decodes a whole column in one
+//! pass and propagates nulls.
+
+use arrow_array::types::{ArrowPrimitiveType, TimestampMicrosecondType, UInt32Type};
+use arrow_array::{Array, FixedSizeBinaryArray, PrimitiveArray};
+use arrow_array::{TimestampMicrosecondArray, UInt32Array};
+use arrow_buffer::NullBuffer;
+use arrow_schema::ArrowError;
+
+use crate::MicroShardUUID;
+
+/// Byte width of an ID column.
+pub const ID_WIDTH: i32 = 16;
+
+/// Builds a non-null `FixedSizeBinary(16)` column from `ids`.
+pub fn to_fixed_size_binary(ids: &[MicroShardUUID]) -> FixedSizeBinaryArray {
+    let mut bytes = Vec::with_capacity(ids.len() * ID_WIDTH as usize);
+    for id in ids {
+        bytes.extend_from_slice(&id.as_bytes());
+    }
+    FixedSizeBinaryArray::new(ID_WIDTH, bytes.into(), None)
+}
+
+/// Builds a nullable `FixedSizeBinary(16)` column; `None` becomes null.
+pub fn to_nullable_fixed_size_binary(ids: &[Option<MicroShardUUID>]) -> FixedSizeBinaryArray {
+    let mut bytes = Vec::with_capacity(ids.len() * ID_WIDTH as usize);
+    for id in ids {
+        // Null slots still occupy 16 (zeroed) bytes
+        bytes.extend_from_slice(&id.map_or([0; 16], |id| id.as_bytes()));
+    }
+    let nulls = NullBuffer::from_iter(ids.iter().map(Option::is_some));
+    FixedSizeBinaryArray::new(ID_WIDTH, bytes.into(), Some(nulls))
+}
+
+/// Decodes a `FixedSizeBinary(16)` column back into IDs, with `None` for nulls.
+///
+/// Fails on the same inputs as the extraction kernels.
+pub fn from_fixed_size_binary(
+    ids: &FixedSizeBinaryArray,
+) -> Result<Vec<Option<MicroShardUUID>>, ArrowError> {
+    decode_column_with(ids, |id| id)
+}
+
+/// Extracts creation times as a UTC `Timestamp(Microsecond)` column.
+pub fn timestamps(ids: &FixedSizeBinaryArray) -> Result<TimestampMicrosecondArray, ArrowError> {
+    decode_column::<TimestampMicrosecondType>(ids, |id| id.timestamp_micros() as i64)
+        .map(|a| a.with_timezone_utc())
+}
+
+/// Extracts the 32-bit Shard IDs as a `UInt32` column.
+pub fn shard_ids(ids: &FixedSizeBinaryArray) -> Result<UInt32Array, ArrowError> {
+    decode_column::<UInt32Type>(ids, |id| id.shard_id())
+}
+
+/// Internal: maps each non-null ID through `f` into a primitive column.
+fn decode_column<T: ArrowPrimitiveType>(
+    ids: &FixedSizeBinaryArray,
+    f: impl Fn(MicroShardUUID) -> T::Native,
+) -> Result<PrimitiveArray<T>, ArrowError> {
+    decode_column_with(ids, f).map(|values| values.into_iter().collect())
+}
+
+/// Internal: validates each non-null value and maps it through `f`.
+///
+/// Fails on a column that is not 16 bytes wide or holds a non-MicroShard value.
+fn decode_column_with<T>(
+    ids: &FixedSizeBinaryArray,
+    f: impl Fn(MicroShardUUID) -> T,
+) -> Result<Vec<Option<T>>, ArrowError> {
+    if ids.value_length() != ID_WIDTH {
+        return Err(ArrowError::InvalidArgumentError(format!(
+            "expected FixedSizeBinary({}), got FixedSizeBinary({})",
+            ID_WIDTH,
+            ids.value_length()
+        )));
+    }
+
+    let mut values = Vec::with_capacity(ids.len());
+    for i in 0..ids.len() {
+        if ids.is_null(i) {
+            values.push(None);
+            continue;
+        }
+        let bytes: [u8; 16] = ids.value(i).try_into().unwrap();
+        let id = MicroShardUUID::from_bytes(bytes)
+            .map_err(|e| ArrowError::InvalidArgumentError(format!("row {}: {}", i, e)))?;
+        values.push(Some(f(id)));
+    }
+    Ok(values)
+}
@@ -0,0 +1,88 @@
+//! Crockford Base32 text form (26 characters, ULID-style).
+//!
+//! The alphabet is in ascending ASCII order and every ID encodes to exactly
+//! 26 characters, so comparing encoded strings byte-wise gives the same order
+//! as comparing the underlying `u128` values (i.e. chronological order).
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+use crate::{MicroShardError, MicroShardUUID};
+
+/// Length of the encoded form: 26 × 5 bits = 130 bits (top 2 always zero).
+pub(crate) const BASE32_LEN: usize = 26;
+
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+impl MicroShardUUID {
+    /// Encodes the ID as 26 uppercase Crockford Base32 characters.
+    ///
+    /// Lexicographic order of the output matches the numeric (and therefore
+    /// chronological) order of the IDs.
+    #[cfg(feature = "alloc")]
+    pub fn to_base32(&self) -> String {
+        let digits = base32_digits(self.as_u128());
+        // The alphabet is ASCII, so every byte is a complete character.
+        digits.iter().map(|&b| b as char).collect()
+    }
+
+    /// Decodes a 26-character Crockford Base32 string.
+    ///
+    /// Decoding is case-insensitive and accepts Crockford's aliases (`I`/`L`
+    /// for `1`, `O` for `0`). The decoded value must be a valid v8 ID.
+    ///
+    /// # Errors
+    /// - [`MicroShardError::InvalidEncoding`] for a wrong length, a character
+    ///   outside the alphabet, or a value above 128 bits (first digit > `7`).
+    /// - [`MicroShardError::InvalidVersion`] / [`MicroShardError::InvalidVariant`]
+    ///   if the decoded value is not a MicroShard UUID.
+    pub fn from_base32(s: &str) -> Result<Self, MicroShardError> {
+        Self::from_u128(decode_base32(s)?)
+    }
+}
+
+/// Internal: decodes 26 Crockford digits into any 128-bit value.
+pub(crate) fn decode_base32(s: &str) -> Result<u128, MicroShardError> {
+    let bytes = s.as_bytes();
+    if bytes.len() != BASE32_LEN {
+        return Err(MicroShardError::InvalidEncoding);
+    }
+
+    let mut v: u128 = 0;
+    for (i, &c) in bytes.iter().enumerate() {
+        let digit = decode_digit(c).ok_or(MicroShardError::InvalidEncoding)?;
+        // The first digit carries only the top 3 bits.
+        if i == 0 && digit > 7 {
+            return Err(MicroShardError::InvalidEncoding);
+        }
+        v = (v << 5) | digit as u128;
+    }
+    Ok(v)
+}
+
+/// Internal: the 26 ASCII digits of `v`, most significant first.
+pub(crate) fn base32_digits(mut v: u128) -> [u8; BASE32_LEN] {
+    let mut out = [0u8; BASE32_LEN];
+    for slot in out.iter_mut().rev() {
+        *slot = ALPHABET[(v & 0x1F) as usize];
+        v >>= 5;
+    }
+    out
+}
+
+/// Internal: Crockford digit value, case-insensitive, with I/L/O aliases.
+fn decode_digit(c: u8) -> Option<u8> {
+    let digit = match c.to_ascii_uppercase() {
+        c @ b'0'..=b'9' => c - b'0',
+        b'O' => 0,
+        b'I' | b'L' => 1,
+        b'U' => return None,
+        c @ b'A'..=b'Z' => {
+            // Letters after the skipped I, L, O (and U) shift down.
+            let skipped = (c > b'I') as u8 + (c > b'L') as u8 + (c > b'O') as u8 + (c > b'U') as u8;
+            c - b'A' + 10 - skipped
+        }
+        _ => return None,
+    };
+    Some(digit)
+}
@@ -0,0 +1,125 @@
+//! Unpadded base64url text form (22 characters, RFC 4648 §5).
+//!
+//! The shortest URL- and header-safe form of an ID, e.g. for short links or
+//! JWT claims. Unlike [`to_base32`](MicroShardUUID::to_base32), the base64url
+//! alphabet is not in ASCII order, so encoded strings do **not** sort
+//! chronologically.
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+use crate::{MicroShardError, MicroShardUUID};
+
+/// Length of the encoded form: 21 full 6-bit digits + 1 digit carrying 2 bits.
+const BASE64_LEN: usize = 22;
+
+#[cfg(feature = "alloc")]
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+impl MicroShardUUID {
+    /// Encodes the 16 big-endian bytes as 22 unpadded base64url characters.
+    #[cfg(feature = "alloc")]
+    pub fn to_base64url(&self) -> String {
+        let digits = base64url_digits(self.as_u128());
+        // The alphabet is ASCII, so every byte is a complete character.
+        digits.iter().map(|&b| b as char).collect()
+    }
+
+    /// Decodes a 22-character unpadded base64url string.
+    ///
+    /// Decoding is strict: padding (`=`), the standard `+`/`/` alphabet and
+    /// non-zero trailing bits in the last character are rejected, so every ID
+    /// has exactly one accepted encoding.
+    ///
+    /// # Errors
+    /// - [`MicroShardError::InvalidEncoding`] for malformed input.
+    /// - [`MicroShardError::InvalidVersion`] / [`MicroShardError::InvalidVariant`]
+    ///   if the decoded value is not a MicroShard UUID.
+    pub fn from_base64url(s: &str) -> Result<Self, MicroShardError> {
+        let bytes = s.as_bytes();
+        if bytes.len() != BASE64_LEN {
+            return Err(MicroShardError::InvalidEncoding);
+        }
+
+        let mut v: u128 = 0;
+        for &c in &bytes[..BASE64_LEN - 1] {
+            let digit = decode_digit(c).ok_or(MicroShardError::InvalidEncoding)?;
+            v = (v << 6) | digit as u128;
+        }
+        // The last digit holds the final 2 bits followed by 4 zero bits.
+        let last = decode_digit(bytes[BASE64_LEN - 1]).ok_or(MicroShardError::InvalidEncoding)?;
+        if last & 0xF != 0 {
+            return Err(MicroShardError::InvalidEncoding);
+        }
+        v = (v << 2) | (last >> 4) as u128;
+
+        Self::from_u128(v)
+    }
+}
+
+/// Internal: the 22 ASCII digits of `v`, most significant first.
+#[cfg(feature = "alloc")]
+fn base64url_digits(v: u128) -> [u8; BASE64_LEN] {
+    let mut out = [0u8; BASE64_LEN];
+    for (i, slot) in out[..BASE64_LEN - 1].iter_mut().enumerate() {
+        *slot = ALPHABET[((v >> (122 - 6 * i)) & 0x3F) as usize];
+    }
+    out[BASE64_LEN - 1] = ALPHABET[((v & 0x3) << 4) as usize];
+    out
+}
+
+/// Internal: base64url digit value.
+fn decode_digit(c: u8) -> Option<u8> {
+    match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'-' => Some(62),
+        b'_' => Some(63),
+        _ => None,
+    }
+}
+
+/// Internal: unpadded base64url encoding of arbitrary bytes.
+#[cfg(feature = "alloc")]
+pub(crate) fn encode_bytes(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity((bytes.len() * 4).div_ceil(3));
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        // 1, 2 or 3 bytes carry 2, 3 or 4 digits
+        for i in 0..=chunk.len() {
+            out.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3F) as usize] as char);
+        }
+    }
+    out
+}
+
+/// Internal: strict inverse of [`encode_bytes`] into `out`, returning the
+/// number of bytes written; `None` for malformed input or if `out` is too
+/// small.
+#[cfg(feature = "alloc")]
+pub(crate) fn decode_bytes(s: &str, out: &mut [u8]) -> Option<usize> {
+    let mut len = 0;
+    for chunk in s.as_bytes().chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+        let mut n = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            n |= (decode_digit(c)? as u32) << (18 - 6 * i);
+        }
+        let bytes = n.to_be_bytes();
+        let count = chunk.len() - 1;
+        // Bits below the last whole byte must be zero (one encoding per input)
+        if n & (0xFF_FFFF >> (8 * count)) != 0 {
+            return None;
+        }
+        out.get_mut(len..len + count)?
+            .copy_from_slice(&bytes[1..=count]);
+        len += count;
+    }
+    Some(len)
+}
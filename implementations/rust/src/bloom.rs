@@ -0,0 +1,177 @@
+//! Bloom filter tuned for 128-bit MicroShard IDs.
+//!
+//! Replication endpoints can exchange a compact "which IDs do you already
+//! have" summary instead of full ID lists. Hashing uses Kirsch–Mitzenmacher
+//! double hashing: two 64-bit hashes are derived from the ID with SplitMix64
+//! and combined as `h1 + i * h2` for each of the `k` probes.
+
+use crate::{splitmix64, MicroShardError, MicroShardUUID};
+
+/// Magic prefix of the serialized form.
+const MAGIC: &[u8; 4] = b"MSBF";
+/// Serialization format version.
+const FORMAT_VERSION: u8 = 1;
+/// Header: magic (4) + version (1) + k (1) + num_bits (8) + items (8).
+const HEADER_LEN: usize = 22;
+
+/// A Bloom filter over `MicroShardUUID` values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UuidBloom {
+    words: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u8,
+    items: u64,
+}
+
+impl UuidBloom {
+    /// Creates a filter sized for `expected_items` at false-positive rate `fpr`.
+    ///
+    /// `fpr` is clamped to `[1e-12, 0.5]`; `expected_items` of 0 is treated as 1.
+    pub fn new(expected_items: usize, fpr: f64) -> Self {
+        let n = expected_items.max(1) as f64;
+        let p = if fpr.is_nan() {
+            0.01
+        } else {
+            fpr.clamp(1e-12, 0.5)
+        };
+        let ln2 = std::f64::consts::LN_2;
+
+        // Optimal size and probe count: m = -n ln p / (ln 2)^2, k = (m / n) ln 2
+        let m = (-n * p.ln() / (ln2 * ln2)).ceil().max(64.0) as u64;
+        let k = ((m as f64 / n) * ln2).round().clamp(1.0, 32.0) as u8;
+        Self::with_params(m, k)
+    }
+
+    /// Creates a filter with an explicit bit count (rounded up to a multiple
+    /// of 64) and number of hash probes (at least 1).
+    pub fn with_params(num_bits: u64, num_hashes: u8) -> Self {
+        let words = num_bits.max(64).div_ceil(64);
+        Self {
+            words: vec![0; words as usize],
+            num_bits: words * 64,
+            num_hashes: num_hashes.max(1),
+            items: 0,
+        }
+    }
+
+    /// Adds an ID to the filter.
+    pub fn insert(&mut self, id: &MicroShardUUID) {
+        let (h1, h2) = hash_pair(id);
+        for i in 0..self.num_hashes as u64 {
+            let bit = h1.wrapping_add(i.wrapping_mul(h2)) % self.num_bits;
+            self.words[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+        self.items += 1;
+    }
+
+    /// Returns `false` if the ID was definitely never inserted, `true` if it
+    /// probably was.
+    pub fn contains(&self, id: &MicroShardUUID) -> bool {
+        let (h1, h2) = hash_pair(id);
+        (0..self.num_hashes as u64).all(|i| {
+            let bit = h1.wrapping_add(i.wrapping_mul(h2)) % self.num_bits;
+            self.words[(bit / 64) as usize] & (1 << (bit % 64)) != 0
+        })
+    }
+
+    /// Merges `other` into `self` (set union).
+    ///
+    /// Both filters must have identical parameters.
+    pub fn union(&mut self, other: &Self) -> Result<(), MicroShardError> {
+        if self.num_bits != other.num_bits || self.num_hashes != other.num_hashes {
+            return Err(MicroShardError::InvalidEncoding);
+        }
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a |= b;
+        }
+        self.items += other.items;
+        Ok(())
+    }
+
+    /// Number of bits in the filter.
+    pub fn num_bits(&self) -> u64 {
+        self.num_bits
+    }
+
+    /// Number of hash probes per ID.
+    pub fn num_hashes(&self) -> u8 {
+        self.num_hashes
+    }
+
+    /// Number of insertions (including duplicates).
+    pub fn len(&self) -> u64 {
+        self.items
+    }
+
+    /// Returns `true` if nothing has been inserted.
+    pub fn is_empty(&self) -> bool {
+        self.items == 0
+    }
+
+    /// Estimated false-positive rate given the current fill ratio.
+    pub fn estimated_fpr(&self) -> f64 {
+        let set: u64 = self.words.iter().map(|w| w.count_ones() as u64).sum();
+        (set as f64 / self.num_bits as f64).powi(self.num_hashes as i32)
+    }
+
+    /// Serializes the filter.
+    ///
+    /// Layout: `"MSBF"`, version `u8`, k `u8`, num_bits `u64`, items `u64`,
+    /// then `num_bits / 64` little-endian `u64` words.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(HEADER_LEN + self.words.len() * 8);
+        out.extend_from_slice(MAGIC);
+        out.push(FORMAT_VERSION);
+        out.push(self.num_hashes);
+        out.extend_from_slice(&self.num_bits.to_le_bytes());
+        out.extend_from_slice(&self.items.to_le_bytes());
+        for w in &self.words {
+            out.extend_from_slice(&w.to_le_bytes());
+        }
+        out
+    }
+
+    /// Deserializes a filter produced by [`UuidBloom::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, MicroShardError> {
+        let err = MicroShardError::InvalidEncoding;
+        if bytes.len() < HEADER_LEN || &bytes[0..4] != MAGIC || bytes[4] != FORMAT_VERSION {
+            return Err(err);
+        }
+
+        let num_hashes = bytes[5];
+        let num_bits = u64::from_le_bytes(bytes[6..14].try_into().map_err(|_| err)?);
+        let items = u64::from_le_bytes(bytes[14..22].try_into().map_err(|_| err)?);
+
+        let body = &bytes[HEADER_LEN..];
+        if num_hashes == 0
+            || num_bits == 0
+            || num_bits % 64 != 0
+            || body.len() as u64 != num_bits / 8
+        {
+            return Err(err);
+        }
+
+        let words = body
+            .chunks_exact(8)
+            .map(|c| u64::from_le_bytes(c.try_into().unwrap()))
+            .collect();
+
+        Ok(Self {
+            words,
+            num_bits,
+            num_hashes,
+            items,
+        })
+    }
+}
+
+/// Internal: derives two independent 64-bit hashes from the ID.
+/// `h2` is forced odd so probes never collapse onto a single bit.
+#[inline(always)]
+fn hash_pair(id: &MicroShardUUID) -> (u64, u64) {
+    let mut hi = id.high();
+    let mut lo = id.low() ^ splitmix64(&mut hi);
+    let h1 = splitmix64(&mut lo);
+    let h2 = splitmix64(&mut lo) | 1;
+    (h1, h2)
+}
@@ -0,0 +1,113 @@
+//! MongoDB / BSON integration (enabled with the `bson` feature).
+//!
+//! MongoDB stores UUIDs as BSON binary subtype 4 (`BinData(4, ...)`) with the
+//! 16 big-endian bytes. Binary values of equal length and subtype compare
+//! byte by byte, so an index on MicroShard `_id`s is in creation order.
+//!
+//! The crate's plain `Serialize` impl writes a string under the BSON
+//! serializer. For a binary `_id`, annotate the field with [`as_binary`]:
+//!
+//! ```
+//! use microshard_uuid::MicroShardUUID;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Event {
+//!     #[serde(rename = "_id", with = "microshard_uuid::bson::as_binary")]
+//!     id: MicroShardUUID,
+//!     name: String,
+//! }
+//!
+//! let id = MicroShardUUID::from_parts(1_714_557_600_000_000, 7, 42).unwrap();
+//! let doc = bson::to_document(&Event { id, name: "created".into() }).unwrap();
+//! assert_eq!(doc.get("_id"), Some(&bson::Bson::from(id)));
+//! let event: Event = bson::from_document(doc).unwrap();
+//! assert_eq!(event.id, id);
+//! ```
+
+use ::bson::spec::BinarySubtype;
+use ::bson::{Binary, Bson, Uuid};
+
+use crate::{MicroShardError, MicroShardUUID};
+
+impl From<MicroShardUUID> for Uuid {
+    fn from(id: MicroShardUUID) -> Self {
+        Uuid::from_bytes(id.as_bytes())
+    }
+}
+
+impl TryFrom<Uuid> for MicroShardUUID {
+    type Error = MicroShardError;
+
+    fn try_from(uuid: Uuid) -> Result<Self, Self::Error> {
+        MicroShardUUID::from_bytes(uuid.bytes())
+    }
+}
+
+/// Binary subtype 4 (UUID).
+impl From<MicroShardUUID> for Binary {
+    fn from(id: MicroShardUUID) -> Self {
+        Binary {
+            subtype: BinarySubtype::Uuid,
+            bytes: id.as_bytes().to_vec(),
+        }
+    }
+}
+
+/// Accepts 16 bytes of binary subtype 4 only.
+impl TryFrom<Binary> for MicroShardUUID {
+    type Error = MicroShardError;
+
+    fn try_from(binary: Binary) -> Result<Self, Self::Error> {
+        if binary.subtype != BinarySubtype::Uuid {
+            return Err(MicroShardError::InvalidEncoding);
+        }
+        let bytes: [u8; 16] = binary
+            .bytes
+            .try_into()
+            .map_err(|_| MicroShardError::InvalidEncoding)?;
+        MicroShardUUID::from_bytes(bytes)
+    }
+}
+
+impl From<MicroShardUUID> for Bson {
+    fn from(id: MicroShardUUID) -> Self {
+        Bson::Binary(id.into())
+    }
+}
+
+/// Accepts a subtype 4 `Binary` only.
+impl TryFrom<Bson> for MicroShardUUID {
+    type Error = MicroShardError;
+
+    fn try_from(value: Bson) -> Result<Self, Self::Error> {
+        match value {
+            Bson::Binary(binary) => binary.try_into(),
+            _ => Err(MicroShardError::InvalidEncoding),
+        }
+    }
+}
+
+/// `#[serde(with = "microshard_uuid::bson::as_binary")]`: serializes as BSON
+/// binary subtype 4, the representation the MongoDB driver uses for UUIDs.
+///
+/// Formats other than BSON get `bson::Uuid`'s representation (a string in
+/// JSON). Deserialization also accepts the string form, so documents written
+/// with the plain `Serialize` impl still load.
+pub mod as_binary {
+    use ::bson::Uuid;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::MicroShardUUID;
+
+    pub fn serialize<S: Serializer>(id: &MicroShardUUID, serializer: S) -> Result<S::Ok, S::Error> {
+        Uuid::from(*id).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<MicroShardUUID, D::Error> {
+        MicroShardUUID::try_from(Uuid::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
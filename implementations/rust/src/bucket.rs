@@ -0,0 +1,126 @@
+//! Calendar time buckets (hour/day/month) for partition keys, and
+//! truncation to fixed time units for grouping keys.
+//!
+//! Warehouse tables (ClickHouse, BigQuery) partition by coarse time buckets
+//! derived from the row's ID. Buckets are computed in UTC from the embedded
+//! timestamp, so every service derives the same key for the same ID.
+
+use core::fmt;
+
+use crate::calendar::unix_to_civil;
+use crate::MicroShardUUID;
+
+const MICROS_PER_HOUR: u64 = 3_600_000_000;
+const MICROS_PER_DAY: u64 = 24 * MICROS_PER_HOUR;
+
+/// A UTC calendar bucket size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Bucket {
+    /// Whole hours, labelled `YYYY-MM-DDTHH`.
+    Hour,
+    /// Whole days, labelled `YYYY-MM-DD`.
+    Day,
+    /// Calendar months, labelled `YYYY-MM`.
+    Month,
+}
+
+/// A fixed-length unit of time, for [`MicroShardUUID::truncate_to`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimeUnit {
+    Millisecond,
+    Second,
+    Minute,
+    Hour,
+    /// A UTC day.
+    Day,
+}
+
+impl TimeUnit {
+    /// The unit's length in microseconds.
+    pub const fn micros(self) -> u64 {
+        match self {
+            Self::Millisecond => 1_000,
+            Self::Second => 1_000_000,
+            Self::Minute => 60_000_000,
+            Self::Hour => MICROS_PER_HOUR,
+            Self::Day => MICROS_PER_DAY,
+        }
+    }
+}
+
+/// Displays an ID's [`Bucket`] label, e.g. `2024-05-01`; returned by
+/// [`MicroShardUUID::time_bucket_label`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BucketLabel {
+    micros: u64,
+    bucket: Bucket,
+}
+
+impl fmt::Display for BucketLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (y, mo, d, h, _, _) = unix_to_civil((self.micros / 1_000_000) as i64);
+        match self.bucket {
+            Bucket::Hour => write!(f, "{:04}-{:02}-{:02}T{:02}", y, mo, d, h),
+            Bucket::Day => write!(f, "{:04}-{:02}-{:02}", y, mo, d),
+            Bucket::Month => write!(f, "{:04}-{:02}", y, mo),
+        }
+    }
+}
+
+impl MicroShardUUID {
+    /// The number of whole `bucket`s between the Unix epoch and the ID's
+    /// timestamp (UTC): hours, days, or calendar months since 1970-01.
+    ///
+    /// ```
+    /// use microshard_uuid::{Bucket, MicroShardUUID};
+    ///
+    /// // 2024-05-01T10:00:00Z
+    /// let id = MicroShardUUID::from_micros(1_714_557_600_000_000, 1).unwrap();
+    /// assert_eq!(id.time_bucket(Bucket::Day), 19_844);
+    /// assert_eq!(id.time_bucket(Bucket::Month), 652);
+    /// assert_eq!(id.time_bucket_label(Bucket::Day).to_string(), "2024-05-01");
+    /// ```
+    pub fn time_bucket(&self, bucket: Bucket) -> u64 {
+        let micros = self.timestamp_micros();
+        match bucket {
+            Bucket::Hour => micros / MICROS_PER_HOUR,
+            Bucket::Day => micros / MICROS_PER_DAY,
+            Bucket::Month => {
+                let (y, mo, ..) = unix_to_civil((micros / 1_000_000) as i64);
+                (y as u64 - 1970) * 12 + (mo as u64 - 1)
+            }
+        }
+    }
+
+    /// The ID's `bucket` as a sortable UTC label: `2024-05-01T10` (hour),
+    /// `2024-05-01` (day), or `2024-05` (month).
+    ///
+    /// Allocation-free; use `to_string()` for an owned `String`.
+    pub fn time_bucket_label(&self, bucket: Bucket) -> BucketLabel {
+        BucketLabel {
+            micros: self.timestamp_micros(),
+            bucket,
+        }
+    }
+
+    /// A grouping key: the ID with its timestamp floored to `unit` and its
+    /// random field zeroed, keeping the shard.
+    ///
+    /// Every ID from the same shard and unit maps to the same key, and keys
+    /// sort like the IDs they came from. Unlike
+    /// [`truncate_time`](Self::truncate_time), distinct IDs are deliberately
+    /// merged.
+    ///
+    /// ```
+    /// use microshard_uuid::{MicroShardUUID, TimeUnit};
+    ///
+    /// let a = MicroShardUUID::from_micros(1_714_557_612_345_678, 9).unwrap();
+    /// let b = MicroShardUUID::from_micros(1_714_557_659_000_001, 9).unwrap();
+    /// assert_eq!(a.truncate_to(TimeUnit::Minute), b.truncate_to(TimeUnit::Minute));
+    /// assert_eq!(a.truncate_to(TimeUnit::Minute).timestamp_micros(), 1_714_557_600_000_000);
+    /// ```
+    pub fn truncate_to(&self, unit: TimeUnit) -> Self {
+        let step = unit.micros();
+        Self::compose(self.timestamp_micros() / step * step, self.shard_id(), 0)
+    }
+}
@@ -0,0 +1,81 @@
+//! Fluent construction of IDs with only some fields fixed.
+
+use crate::{now_micros, validate_shard, MicroShardError, MicroShardUUID, Xoshiro256StarStar};
+
+/// Builds a [`MicroShardUUID`] field by field; unset fields get defaults.
+///
+/// | Field | Default |
+/// | :--- | :--- |
+/// | [`shard`](Self::shard) | `0` |
+/// | [`timestamp_micros`](Self::timestamp_micros) | the current time (honours `test_support::freeze_time`) |
+/// | [`random`](Self::random) | fresh bits from the thread-local PRNG |
+///
+/// Setters never fail; every field is validated in [`build`](Self::build).
+///
+/// ```
+/// use microshard_uuid::{MicroShardBuilder, MicroShardError};
+///
+/// let id = MicroShardBuilder::new()
+///     .shard(42)
+///     .timestamp_micros(1_700_000_000_000_000)
+///     .random(7)
+///     .build()
+///     .unwrap();
+/// assert_eq!((id.shard_id(), id.random()), (42, 7));
+///
+/// // Only the shard fixed: stamped now, with fresh randomness
+/// let live = MicroShardBuilder::new().shard(42).build().unwrap();
+/// assert_eq!(live.shard_id(), 42);
+///
+/// let err = MicroShardBuilder::new().random(1 << 36).build();
+/// assert_eq!(err, Err(MicroShardError::InvalidRandom(1 << 36)));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MicroShardBuilder {
+    shard_id: u32,
+    micros: Option<u64>,
+    random: Option<u64>,
+}
+
+impl MicroShardBuilder {
+    /// Starts a builder with every field at its default.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the shard id.
+    pub fn shard(mut self, shard_id: u32) -> Self {
+        self.shard_id = shard_id;
+        self
+    }
+
+    /// Sets the timestamp (microseconds since the Unix Epoch).
+    pub fn timestamp_micros(mut self, micros: u64) -> Self {
+        self.micros = Some(micros);
+        self
+    }
+
+    /// Sets the 36-bit random field.
+    pub fn random(mut self, random: u64) -> Self {
+        self.random = Some(random);
+        self
+    }
+
+    /// Validates the fields and builds the ID.
+    ///
+    /// # Errors
+    /// - [`MicroShardError::InvalidShardId`] for an invalid shard.
+    /// - [`MicroShardError::TimeOverflow`] if the timestamp exceeds 2^54 - 1.
+    /// - [`MicroShardError::InvalidRandom`] if the random field exceeds 2^36 - 1.
+    /// - [`MicroShardError::SystemTimeError`] if the default timestamp
+    ///   cannot be read.
+    pub fn build(&self) -> Result<MicroShardUUID, MicroShardError> {
+        validate_shard(self.shard_id)?;
+        let micros = match self.micros {
+            Some(micros) => micros,
+            None => now_micros()?,
+        };
+        let random = self.random.unwrap_or_else(Xoshiro256StarStar::next_36);
+        MicroShardUUID::from_parts(micros, self.shard_id, random)
+    }
+}
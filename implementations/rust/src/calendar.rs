@@ -0,0 +1,124 @@
+//! Zero-dependency civil calendar math shared by ISO parsing, ISO
+//! formatting, local-time rendering, and time buckets.
+
+// Each consumer uses a different subset depending on the enabled features.
+#![cfg_attr(not(all(feature = "parse", feature = "fmt-iso")), allow(dead_code))]
+
+/// Calculates the number of days from Year 0000 to the start of the given year.
+/// Formula: 365*y + y/4 - y/100 + y/400
+fn days_from_civil(y: i32) -> i64 {
+    // We calculate completed years, so subtract 1 from input
+    let y = y as i64 - 1;
+    y * 365 + y / 4 - y / 100 + y / 400
+}
+
+/// Converts Y/M/D to Unix Epoch Days (Days since 1970-01-01)
+pub(crate) fn date_to_days(y: i32, m: u32, d: u32) -> i64 {
+    const DAYS_BEFORE: [i64; 12] = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+
+    // 1. Calculate absolute days from Year 0
+    let mut days = days_from_civil(y);
+
+    // 2. Subtract the number of days from Year 0 to 1970-01-01
+    //    (This constant is derived from days_from_civil(1970))
+    days -= 719162;
+
+    // 3. Add days for months passed in current year
+    days += DAYS_BEFORE[(m - 1) as usize];
+
+    // 4. Leap year adjustment for current year
+    //    If it's a leap year AND we are past February, add 1 day
+    if m > 2 && is_leap(y) {
+        days += 1;
+    }
+
+    // 5. Add days in current month (1-based to 0-based)
+    days + (d as i64 - 1)
+}
+
+pub(crate) fn is_leap(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
+}
+
+/// Converts a Unix Timestamp (i64 seconds) into civil date components:
+/// (Year, Month, Day, Hour, Minute, Second).
+/// Negative timestamps (before 1970) are supported for local-time rendering.
+///
+/// Algorithm: Howard Hinnant's "civil_from_days".
+/// It shifts the epoch to 0000-03-01 to simplify leap year logic,
+/// effectively moving Feb 29 to the very end of the year cycle.
+pub(crate) fn unix_to_civil(ts: i64) -> (i32, u32, u32, u32, u32, u32) {
+    // --------------------------------------------------------
+    // Part 1: Time Extraction (HH:MM:SS)
+    // --------------------------------------------------------
+    // 86400 seconds in a day. Euclidean division gives total full days
+    // (rounding towards negative infinity for pre-1970 instants).
+    let days = ts.div_euclid(86400);
+    // Modulo gives the seconds elapsed in the current partial day.
+    let rem_sec = ts.rem_euclid(86400);
+
+    let hour = (rem_sec / 3600) as u32;
+    let min = ((rem_sec % 3600) / 60) as u32;
+    let sec = (rem_sec % 60) as u32;
+
+    // --------------------------------------------------------
+    // Part 2: Date Conversion (Hinnant's Algorithm)
+    // --------------------------------------------------------
+
+    // 1. Shift Epoch
+    // Unix Epoch is 1970-01-01. The algorithm requires an epoch of 0000-03-01.
+    // 719468 is the exact number of days between 0000-03-01 and 1970-01-01.
+    // 'z' is now the number of days since March 1st, year 0.
+    let z = days + 719468;
+
+    // 2. Calculate Era
+    // The Gregorian calendar repeats exactly every 400 years.
+    // One "Era" = 400 years = 146,097 days.
+    // We calculate which 400-year block we are in.
+    let era = (if z >= 0 { z } else { z - 146096 }) / 146097;
+
+    // 3. Day of Era (doe)
+    // How many days into this specific 400-year cycle are we? (0 to 146096)
+    let doe = (z - era * 146097) as u32;
+
+    // 4. Year of Era (yoe)
+    // Calculate which year (0-399) within the era we are in.
+    // This magic formula accounts for the leap year rules:
+    // - 1460   = days in 4 years
+    // - 36524  = days in 100 years
+    // - 146096 = days in 400 years
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+
+    // 5. Absolute Year (y)
+    // Reconstruct the actual year number.
+    let y = (yoe as i64) + era * 400;
+
+    // 6. Day of Year (doy)
+    // How many days into this specific year are we? (0 to 365)
+    // This removes the days contributed by previous years in the era.
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+
+    // 7. Month Position (mp)
+    // Map the Day of Year to a Month Index.
+    // Because we shifted the start to March, the months are:
+    // 0=March, 1=April ... 10=Jan, 11=Feb.
+    // The linear formula (5*doy + 2)/153 maps the uneven month lengths (30/31 days) perfectly.
+    let mp = (5 * doy + 2) / 153;
+
+    // 8. Day of Month (d)
+    // Inverse the logic above to get the specific day (1-31).
+    let d = doy - (153 * mp + 2) / 5 + 1;
+
+    // 9. Convert Month Index back to Civil Month (1-12)
+    // If mp < 10 (March...Dec), add 3 to get standard index.
+    // If mp >= 10 (Jan...Feb), subtract 9 to wrap around.
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+
+    // 10. Adjust Year
+    // Since we treated March as the start of the year, Jan and Feb
+    // actually belong to the *next* civil year.
+    // Example: "Month 11" in our math is actually Feb of (Year + 1).
+    let y = y + if m <= 2 { 1 } else { 0 };
+
+    (y as i32, m, d, hour, min, sec)
+}
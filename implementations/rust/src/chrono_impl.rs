@@ -0,0 +1,43 @@
+//! `chrono` interop (enabled with the `chrono` feature).
+//!
+//! Converts between the embedded timestamp and `DateTime<Utc>` at full
+//! microsecond precision; sub-microsecond digits of a `DateTime` are
+//! truncated.
+
+use chrono::{DateTime, Utc};
+
+use crate::MicroShardUUID;
+#[cfg(feature = "gen")]
+use crate::{validate_shard, MicroShardError};
+
+impl MicroShardUUID {
+    /// The creation time as a `DateTime<Utc>`.
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use microshard_uuid::MicroShardUUID;
+    ///
+    /// let id = MicroShardUUID::from_parts(1_700_000_000_123_456, 1, 0).unwrap();
+    /// let second = Utc.with_ymd_and_hms(2023, 11, 14, 22, 13, 20).unwrap();
+    /// assert_eq!(id.datetime(), second + chrono::Duration::microseconds(123_456));
+    /// ```
+    pub fn datetime(&self) -> DateTime<Utc> {
+        // 54-bit microseconds (year 2541) are well inside chrono's range.
+        DateTime::from_timestamp_micros(self.timestamp_micros() as i64)
+            .expect("54-bit timestamps fit DateTime<Utc>")
+    }
+
+    /// Generates an ID for `shard_id` stamped with `datetime`.
+    ///
+    /// # Errors
+    /// - [`MicroShardError::InvalidShardId`] for an invalid shard.
+    /// - [`MicroShardError::TimeUnderflow`] before 1970-01-01.
+    /// - [`MicroShardError::TimeOverflow`] after the year 2541.
+    #[cfg(feature = "gen")]
+    pub fn from_datetime(datetime: DateTime<Utc>, shard_id: u32) -> Result<Self, MicroShardError> {
+        validate_shard(shard_id)?;
+        let micros = u64::try_from(datetime.timestamp_micros())
+            .map_err(|_| MicroShardError::TimeUnderflow)?;
+        Self::build(micros, shard_id)
+    }
+}
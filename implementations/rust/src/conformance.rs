@@ -0,0 +1,222 @@
+//! Runtime conformance self-check.
+//!
+//! Runs a fixed battery of encode/decode/round-trip vectors (computed
+//! independently from the reference Python implementation) so embedded and
+//! FFI deployments can detect a miscompiled or byte-swapped build at startup,
+//! before it silently corrupts IDs.
+
+use core::fmt;
+
+#[cfg(feature = "parse")]
+use crate::parse_iso_strict;
+use crate::{MicroShardUUID, MAX_RANDOM};
+
+/// The stage of the self-check that produced a mismatch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    /// Bit packing of (time, shard, random) into the `u128`.
+    Encode,
+    /// Big-endian byte serialization.
+    Bytes,
+    /// Canonical 8-4-4-4-12 hex formatting.
+    Display,
+    /// Shard ID extraction.
+    ShardId,
+    /// Timestamp extraction.
+    Timestamp,
+    /// ISO 8601 formatting and parsing.
+    Iso,
+    /// Version/variant validation in `from_bytes` / `from_u128`.
+    Validation,
+    /// Live generation (clock + PRNG) producing a well-formed ID.
+    Generation,
+}
+
+/// Error returned by [`self_check`] describing the first failing vector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConformanceError {
+    /// Index of the failing vector (`None` for vector-independent checks).
+    pub vector: Option<usize>,
+    /// The failing check.
+    pub check: Check,
+}
+
+impl fmt::Display for ConformanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.vector {
+            Some(i) => write!(
+                f,
+                "Conformance check {:?} failed for vector {}",
+                self.check, i
+            ),
+            None => write!(f, "Conformance check {:?} failed", self.check),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ConformanceError {}
+
+/// Internal: a known-good (input, output) vector.
+struct Vector {
+    micros: u64,
+    shard_id: u32,
+    random: u64,
+    raw: u128,
+    bytes: [u8; 16],
+    text: &'static str,
+    #[cfg_attr(not(any(feature = "parse", feature = "fmt-iso")), allow(dead_code))]
+    iso: &'static str,
+}
+
+const VECTORS: [Vector; 3] = [
+    // All-zero payload: only version and variant bits set
+    Vector {
+        micros: 0,
+        shard_id: 0,
+        random: 0,
+        raw: 0x0000_0000_0000_8000_8000_0000_0000_0000,
+        bytes: [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00,
+        ],
+        text: "00000000-0000-8000-8000-000000000000",
+        iso: "1970-01-01T00:00:00.000000Z",
+    },
+    // Asymmetric payload: catches byte swaps and shifted fields
+    Vector {
+        micros: 1_765_503_300_123_456,
+        shard_id: 12345,
+        random: 0x1_2345_6789,
+        raw: 0x1916_dd14_36ed_8000_8003_0391_2345_6789,
+        bytes: [
+            0x19, 0x16, 0xdd, 0x14, 0x36, 0xed, 0x80, 0x00, 0x80, 0x03, 0x03, 0x91, 0x23, 0x45,
+            0x67, 0x89,
+        ],
+        text: "1916dd14-36ed-8000-8003-039123456789",
+        iso: "2025-12-12T01:35:00.123456Z",
+    },
+    // All fields saturated: catches truncation at field boundaries
+    Vector {
+        micros: 18_014_398_509_481_983,
+        shard_id: u32::MAX,
+        random: MAX_RANDOM,
+        raw: 0xffff_ffff_ffff_8fff_bfff_ffff_ffff_ffff,
+        bytes: [
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x8f, 0xff, 0xbf, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0xff, 0xff,
+        ],
+        text: "ffffffff-ffff-8fff-bfff-ffffffffffff",
+        iso: "2540-11-07T23:35:09.481983Z",
+    },
+];
+
+/// Runs the conformance battery, returning the first mismatch.
+///
+/// Cheap enough to call once at process start (a handful of vectors and one
+/// live generation). Formats into stack buffers, so it also runs on targets
+/// without an allocator.
+pub fn self_check() -> Result<(), ConformanceError> {
+    for (i, v) in VECTORS.iter().enumerate() {
+        let fail = |check| ConformanceError {
+            vector: Some(i),
+            check,
+        };
+        let ensure = |ok: bool, check| if ok { Ok(()) } else { Err(fail(check)) };
+
+        // 1. Encode
+        let uuid = MicroShardUUID::compose(v.micros, v.shard_id, v.random);
+        ensure(uuid.as_u128() == v.raw, Check::Encode)?;
+
+        // 2. Serialize & Format
+        ensure(uuid.as_bytes() == v.bytes, Check::Bytes)?;
+        let mut text = [0u8; 36];
+        ensure(uuid.encode_hyphenated(&mut text) == v.text, Check::Display)?;
+
+        // 3. Decode
+        ensure(uuid.shard_id() == v.shard_id, Check::ShardId)?;
+        ensure(uuid.timestamp_micros() == v.micros, Check::Timestamp)?;
+
+        // 4. ISO round-trip (only the halves compiled into this build)
+        #[cfg(feature = "fmt-iso")]
+        {
+            let mut iso = IsoBuf::default();
+            let written = fmt::write(&mut iso, format_args!("{}", uuid.iso_display()));
+            ensure(
+                written.is_ok() && iso.as_bytes() == v.iso.as_bytes(),
+                Check::Iso,
+            )?;
+        }
+        #[cfg(feature = "parse")]
+        ensure(parse_iso_strict(v.iso) == Ok(v.micros), Check::Iso)?;
+
+        // 5. Validated round-trip from raw bytes
+        ensure(
+            MicroShardUUID::from_bytes(v.bytes) == Ok(uuid),
+            Check::Validation,
+        )?;
+    }
+
+    // 6. Validation must reject non-v8 / non-variant-2 input
+    let nil_ok = MicroShardUUID::from_u128(0).is_err();
+    let v4_ok = MicroShardUUID::from_u128(0x0000_0000_0000_4000_8000_0000_0000_0000).is_err();
+    let var_ok = MicroShardUUID::from_u128(0x0000_0000_0000_8000_c000_0000_0000_0000).is_err();
+    if !(nil_ok && v4_ok && var_ok) {
+        return Err(ConformanceError {
+            vector: None,
+            check: Check::Validation,
+        });
+    }
+
+    // 7. Live generation: well-formed and decodes to the requested shard
+    #[cfg(feature = "gen")]
+    check_generation()?;
+
+    Ok(())
+}
+
+/// Internal: mints one live ID and checks it round-trips.
+#[cfg(feature = "gen")]
+fn check_generation() -> Result<(), ConformanceError> {
+    let generated = MicroShardUUID::generate(0xA5A5_5A5A).ok();
+    let well_formed = generated.is_some_and(|g| {
+        g.shard_id() == 0xA5A5_5A5A && MicroShardUUID::from_u128(g.as_u128()) == Ok(g)
+    });
+    if !well_formed {
+        return Err(ConformanceError {
+            vector: None,
+            check: Check::Generation,
+        });
+    }
+
+    Ok(())
+}
+
+/// Internal: fixed-capacity sink for the 27-byte ISO form. Longer output
+/// fails the write rather than being truncated.
+#[cfg(feature = "fmt-iso")]
+#[derive(Default)]
+struct IsoBuf {
+    bytes: [u8; 27],
+    len: usize,
+}
+
+#[cfg(feature = "fmt-iso")]
+impl IsoBuf {
+    fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+}
+
+#[cfg(feature = "fmt-iso")]
+impl fmt::Write for IsoBuf {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let dst = self
+            .bytes
+            .get_mut(self.len..self.len + s.len())
+            .ok_or(fmt::Error)?;
+        dst.copy_from_slice(s.as_bytes());
+        self.len += s.len();
+        Ok(())
+    }
+}
@@ -0,0 +1,203 @@
+//! Opaque, URL-safe pagination cursors.
+//!
+//! A [`Cursor`] wraps the ID a page ended on plus the paging direction, so
+//! every API built on this crate exchanges the same token format instead of
+//! raw UUIDs. Tokens are unpadded base64url (RFC 4648 §5).
+//!
+//! **Signed tokens** (`encode_signed`) are encrypted and authenticated with
+//! a server key: clients can neither read the ID (and so its timestamp and
+//! shard) nor forge or edit the token. Equal cursors give equal tokens.
+//! **Unsigned tokens** (`encode`) only encode the ID; anyone can decode it,
+//! so use them where the ID is not secret.
+//!
+//! **Token Layout:** format version (`1`), flags (bit 0: backward, bit 1:
+//! signed), then either the 16 ID bytes (unsigned, 24 characters) or a
+//! 16-byte tag followed by the encrypted ID (signed, 46 characters). The
+//! tag is HMAC-SHA256(mac key, version, flags, ID) truncated to 16 bytes,
+//! and the ID is XORed with HMAC-SHA256(encryption key, tag), as in SIV
+//! mode; both keys are HMACs of the caller's key with fixed labels.
+
+use alloc::string::String;
+
+use crate::base64::{decode_bytes, encode_bytes};
+use crate::sha256::hmac_sha256;
+use crate::{MicroShardError, MicroShardUUID};
+
+const FORMAT_VERSION: u8 = 1;
+const FLAG_BACKWARD: u8 = 0b01;
+const FLAG_SIGNED: u8 = 0b10;
+/// Version + flags + ID.
+const PAYLOAD_LEN: usize = 18;
+/// Truncated HMAC-SHA256 tag (128 bits).
+const TAG_LEN: usize = 16;
+/// Version + flags + tag + encrypted ID.
+const SEALED_LEN: usize = PAYLOAD_LEN + TAG_LEN;
+
+/// Which way the next page continues from the cursor's ID.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// IDs after the cursor (`id > cursor`), ascending.
+    Forward,
+    /// IDs before the cursor (`id < cursor`), descending.
+    Backward,
+}
+
+/// A pagination position: the last ID seen and the direction to continue.
+///
+/// ```
+/// use microshard_uuid::{Cursor, Direction, MicroShardUUID};
+///
+/// let last = MicroShardUUID::from_micros(1_714_557_600_000_000, 3).unwrap();
+/// let token = Cursor::after(last).encode_signed(b"server secret");
+///
+/// let cursor = Cursor::decode_signed(&token, b"server secret").unwrap();
+/// assert_eq!((cursor.id, cursor.direction), (last, Direction::Forward));
+/// assert!(Cursor::decode_signed(&token, b"wrong key").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cursor {
+    pub id: MicroShardUUID,
+    pub direction: Direction,
+}
+
+impl Cursor {
+    /// Creates a cursor.
+    pub fn new(id: MicroShardUUID, direction: Direction) -> Self {
+        Self { id, direction }
+    }
+
+    /// A cursor for the page after `id`.
+    pub fn after(id: MicroShardUUID) -> Self {
+        Self::new(id, Direction::Forward)
+    }
+
+    /// A cursor for the page before `id`.
+    pub fn before(id: MicroShardUUID) -> Self {
+        Self::new(id, Direction::Backward)
+    }
+
+    /// Encodes an unsigned token (24 characters).
+    ///
+    /// The ID is readable by anyone holding the token; use
+    /// [`encode_signed`](Self::encode_signed) to keep it from clients.
+    pub fn encode(&self) -> String {
+        encode_bytes(&self.payload(0))
+    }
+
+    /// Encodes a token encrypted and signed with `key` (46 characters).
+    pub fn encode_signed(&self, key: &[u8]) -> String {
+        let payload = self.payload(FLAG_SIGNED);
+        let tag = seal_tag(key, &payload);
+        let mut token = [0u8; SEALED_LEN];
+        token[..2].copy_from_slice(&payload[..2]);
+        token[2..PAYLOAD_LEN].copy_from_slice(&tag);
+        token[PAYLOAD_LEN..].copy_from_slice(&payload[2..]);
+        apply_keystream(key, &tag, &mut token[PAYLOAD_LEN..]);
+        encode_bytes(&token)
+    }
+
+    /// Decodes an unsigned token.
+    ///
+    /// # Errors
+    /// - [`MicroShardError::InvalidEncoding`] for a malformed token.
+    /// - [`MicroShardError::InvalidSignature`] for a signed token (verify it
+    ///   with [`decode_signed`](Self::decode_signed) instead).
+    /// - [`MicroShardError::InvalidVersion`] / [`MicroShardError::InvalidVariant`]
+    ///   if the wrapped value is not a MicroShard UUID.
+    pub fn decode(token: &str) -> Result<Self, MicroShardError> {
+        let mut buf = [0u8; SEALED_LEN];
+        let len = decode_bytes(token, &mut buf).ok_or(MicroShardError::InvalidEncoding)?;
+        match (len, buf[1] & FLAG_SIGNED) {
+            (PAYLOAD_LEN, 0) => Self::from_payload(&buf),
+            (SEALED_LEN, FLAG_SIGNED) => Err(MicroShardError::InvalidSignature),
+            _ => Err(MicroShardError::InvalidEncoding),
+        }
+    }
+
+    /// Decrypts a token signed with `key`, verifying its tag.
+    ///
+    /// # Errors
+    /// - [`MicroShardError::InvalidEncoding`] for a malformed token.
+    /// - [`MicroShardError::InvalidSignature`] if the token is unsigned, was
+    ///   signed with another key, or has been altered.
+    /// - [`MicroShardError::InvalidVersion`] / [`MicroShardError::InvalidVariant`]
+    ///   if the wrapped value is not a MicroShard UUID.
+    pub fn decode_signed(token: &str, key: &[u8]) -> Result<Self, MicroShardError> {
+        let mut buf = [0u8; SEALED_LEN];
+        let len = decode_bytes(token, &mut buf).ok_or(MicroShardError::InvalidEncoding)?;
+        if len != SEALED_LEN || buf[1] & FLAG_SIGNED == 0 {
+            return Err(if len == PAYLOAD_LEN {
+                MicroShardError::InvalidSignature
+            } else {
+                MicroShardError::InvalidEncoding
+            });
+        }
+        let mut tag = [0u8; TAG_LEN];
+        tag.copy_from_slice(&buf[2..PAYLOAD_LEN]);
+        let mut payload = [0u8; PAYLOAD_LEN];
+        payload[..2].copy_from_slice(&buf[..2]);
+        payload[2..].copy_from_slice(&buf[PAYLOAD_LEN..]);
+        apply_keystream(key, &tag, &mut payload[2..]);
+
+        let expected = seal_tag(key, &payload);
+        // Constant-time comparison, so the tag can't be guessed byte by byte
+        let diff = tag
+            .iter()
+            .zip(&expected)
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+        if diff != 0 {
+            return Err(MicroShardError::InvalidSignature);
+        }
+        Self::from_payload(&payload)
+    }
+
+    /// Internal: version, flags and ID bytes.
+    fn payload(&self, flags: u8) -> [u8; PAYLOAD_LEN] {
+        let mut out = [0u8; PAYLOAD_LEN];
+        out[0] = FORMAT_VERSION;
+        out[1] = flags
+            | match self.direction {
+                Direction::Forward => 0,
+                Direction::Backward => FLAG_BACKWARD,
+            };
+        out[2..].copy_from_slice(&self.id.as_bytes());
+        out
+    }
+
+    /// Internal: parses the payload at the start of `buf`.
+    fn from_payload(buf: &[u8]) -> Result<Self, MicroShardError> {
+        if buf[0] != FORMAT_VERSION || buf[1] & !(FLAG_BACKWARD | FLAG_SIGNED) != 0 {
+            return Err(MicroShardError::InvalidEncoding);
+        }
+        let direction = if buf[1] & FLAG_BACKWARD == 0 {
+            Direction::Forward
+        } else {
+            Direction::Backward
+        };
+        let mut id = [0u8; 16];
+        id.copy_from_slice(&buf[2..PAYLOAD_LEN]);
+        Ok(Self::new(MicroShardUUID::from_bytes(id)?, direction))
+    }
+}
+
+/// Internal: derives an independent subkey for one purpose from `key`.
+fn subkey(key: &[u8], label: &[u8]) -> [u8; 32] {
+    hmac_sha256(key, label)
+}
+
+/// Internal: the authentication tag over the plaintext payload.
+fn seal_tag(key: &[u8], payload: &[u8; PAYLOAD_LEN]) -> [u8; TAG_LEN] {
+    let mac = hmac_sha256(&subkey(key, b"microshard cursor mac"), payload);
+    let mut tag = [0u8; TAG_LEN];
+    tag.copy_from_slice(&mac[..TAG_LEN]);
+    tag
+}
+
+/// Internal: XORs the 16 ID bytes with the keystream for `tag` (its own
+/// inverse, so it both encrypts and decrypts).
+fn apply_keystream(key: &[u8], tag: &[u8; TAG_LEN], id: &mut [u8]) {
+    let stream = hmac_sha256(&subkey(key, b"microshard cursor enc"), tag);
+    for (b, k) in id.iter_mut().zip(stream) {
+        *b ^= k;
+    }
+}
@@ -0,0 +1,114 @@
+//! DataFusion scalar UDFs (enabled with the `datafusion` feature).
+//!
+//! | Function | Argument | Return |
+//! | :--- | :--- | :--- |
+//! | `microshard_timestamp` | `FixedSizeBinary(16)` | `Timestamp(Microsecond, "+00:00")` |
+//! | `microshard_shard` | `FixedSizeBinary(16)` | `UInt32` |
+//!
+//! Both are backed by the vectorized kernels in [`crate::arrow`], so filters
+//! and `GROUP BY`s on embedded fields run a batch at a time.
+//!
+//! Polars frames get the same two extractors as `Series` helpers in
+//! `microshard_uuid::polars` (the `polars` feature).
+//!
+//! ```ignore
+//! let ctx = SessionContext::new();
+//! microshard_uuid::datafusion::register_udfs(&mut ctx)?;
+//! ctx.sql("SELECT microshard_shard(id), COUNT(*) FROM events GROUP BY 1").await?;
+//! ```
+
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, FixedSizeBinaryArray};
+use arrow_schema::{DataType, TimeUnit};
+use datafusion_common::{exec_datafusion_err, Result, ScalarValue};
+use datafusion_expr::registry::FunctionRegistry;
+use datafusion_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl, Signature, Volatility,
+};
+
+use crate::arrow::{self as kernels, ID_WIDTH};
+
+/// Internal: the embedded field a UDF extracts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Field {
+    Timestamp,
+    Shard,
+}
+
+/// Internal: a single-argument UDF over an ID column.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct ExtractUdf {
+    field: Field,
+    signature: Signature,
+}
+
+impl ExtractUdf {
+    fn new(field: Field) -> Self {
+        Self {
+            field,
+            signature: Signature::exact(
+                vec![DataType::FixedSizeBinary(ID_WIDTH)],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for ExtractUdf {
+    fn name(&self) -> &str {
+        match self.field {
+            Field::Timestamp => "microshard_timestamp",
+            Field::Shard => "microshard_shard",
+        }
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(match self.field {
+            Field::Timestamp => DataType::Timestamp(TimeUnit::Microsecond, Some("+00:00".into())),
+            Field::Shard => DataType::UInt32,
+        })
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> Result<ColumnarValue> {
+        let arg = &args.args[0];
+        let is_scalar = matches!(arg, ColumnarValue::Scalar(_));
+        let array = arg.to_array(if is_scalar { 1 } else { args.number_rows })?;
+        let ids = array
+            .as_any()
+            .downcast_ref::<FixedSizeBinaryArray>()
+            .ok_or_else(|| exec_datafusion_err!("{} expects FixedSizeBinary(16)", self.name()))?;
+
+        let out: ArrayRef = match self.field {
+            Field::Timestamp => Arc::new(kernels::timestamps(ids)?),
+            Field::Shard => Arc::new(kernels::shard_ids(ids)?),
+        };
+
+        if is_scalar {
+            ScalarValue::try_from_array(&out, 0).map(ColumnarValue::Scalar)
+        } else {
+            Ok(ColumnarValue::Array(out))
+        }
+    }
+}
+
+/// Returns the `microshard_timestamp(id)` UDF.
+pub fn timestamp_udf() -> ScalarUDF {
+    ScalarUDF::new_from_impl(ExtractUdf::new(Field::Timestamp))
+}
+
+/// Returns the `microshard_shard(id)` UDF.
+pub fn shard_udf() -> ScalarUDF {
+    ScalarUDF::new_from_impl(ExtractUdf::new(Field::Shard))
+}
+
+/// Registers all MicroShard UDFs (e.g. on a `SessionContext`).
+pub fn register_udfs(registry: &mut dyn FunctionRegistry) -> Result<()> {
+    registry.register_udf(Arc::new(timestamp_udf()))?;
+    registry.register_udf(Arc::new(shard_udf()))?;
+    Ok(())
+}
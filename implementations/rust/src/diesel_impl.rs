@@ -0,0 +1,119 @@
+//! Diesel integration (enabled with the `diesel-postgres`, `diesel-mysql` and
+//! `diesel-sqlite` features, or all three with `diesel`).
+//!
+//! `MicroShardUUID` can be used directly as a column type in `table!`
+//! schemas and as a bind value in queries:
+//!
+//! | SQL type | Backends | Column type |
+//! | :--- | :--- | :--- |
+//! | `Binary` | PostgreSQL, MySQL, SQLite | `BYTEA` / `BINARY(16)` / `BLOB` |
+//! | `Uuid` | PostgreSQL | `UUID` |
+//!
+//! IDs are written as their 16 big-endian bytes. `Binary` columns holding
+//! the canonical 36-character string also decode, for tables filled as text.
+
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use diesel::sql_types::Binary;
+
+use crate::MicroShardUUID;
+
+#[derive(AsExpression, FromSqlRow)]
+#[diesel(foreign_derive)]
+#[diesel(sql_type = Binary)]
+#[allow(dead_code)]
+struct BinaryProxy(MicroShardUUID);
+
+/// Internal: decodes 16 raw bytes, or a UUID string stored as text.
+fn from_column(bytes: &[u8]) -> deserialize::Result<MicroShardUUID> {
+    match <[u8; 16]>::try_from(bytes) {
+        Ok(raw) => Ok(MicroShardUUID::from_bytes(raw)?),
+        Err(_) => Ok(MicroShardUUID::parse(core::str::from_utf8(bytes)?)?),
+    }
+}
+
+#[cfg(feature = "diesel-postgres")]
+mod postgres {
+    use std::io::Write;
+
+    use diesel::pg::{Pg, PgValue};
+    use diesel::sql_types::Uuid;
+
+    use super::*;
+
+    #[derive(AsExpression)]
+    #[diesel(foreign_derive)]
+    #[diesel(sql_type = Uuid)]
+    #[allow(dead_code)]
+    struct UuidProxy(MicroShardUUID);
+
+    impl ToSql<Uuid, Pg> for MicroShardUUID {
+        fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+            out.write_all(&self.as_bytes())?;
+            Ok(IsNull::No)
+        }
+    }
+
+    impl FromSql<Uuid, Pg> for MicroShardUUID {
+        fn from_sql(value: PgValue<'_>) -> deserialize::Result<Self> {
+            let bytes: [u8; 16] = value.as_bytes().try_into()?;
+            Ok(MicroShardUUID::from_bytes(bytes)?)
+        }
+    }
+
+    impl ToSql<Binary, Pg> for MicroShardUUID {
+        fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+            out.write_all(&self.as_bytes())?;
+            Ok(IsNull::No)
+        }
+    }
+
+    impl FromSql<Binary, Pg> for MicroShardUUID {
+        fn from_sql(value: PgValue<'_>) -> deserialize::Result<Self> {
+            from_column(value.as_bytes())
+        }
+    }
+}
+
+#[cfg(feature = "diesel-mysql")]
+mod mysql {
+    use std::io::Write;
+
+    use diesel::mysql::{Mysql, MysqlValue};
+
+    use super::*;
+
+    impl ToSql<Binary, Mysql> for MicroShardUUID {
+        fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Mysql>) -> serialize::Result {
+            out.write_all(&self.as_bytes())?;
+            Ok(IsNull::No)
+        }
+    }
+
+    impl FromSql<Binary, Mysql> for MicroShardUUID {
+        fn from_sql(value: MysqlValue<'_>) -> deserialize::Result<Self> {
+            from_column(value.as_bytes())
+        }
+    }
+}
+
+#[cfg(feature = "diesel-sqlite")]
+mod sqlite {
+    use diesel::sqlite::{Sqlite, SqliteValue};
+
+    use super::*;
+
+    impl ToSql<Binary, Sqlite> for MicroShardUUID {
+        fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+            out.set_value(self.as_bytes().to_vec());
+            Ok(IsNull::No)
+        }
+    }
+
+    impl FromSql<Binary, Sqlite> for MicroShardUUID {
+        fn from_sql(mut value: SqliteValue<'_, '_, '_>) -> deserialize::Result<Self> {
+            from_column(value.read_blob())
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Self-describing wire envelope.
+//!
+//! Messages that cross organizational boundaries cannot assume both sides
+//! agree on the bit layout. The envelope prefixes the 16-byte payload with a
+//! magic byte and a layout tag, so receivers can reject foreign data early and
+//! dispatch on the layout once it evolves (custom epochs, different splits).
+//!
+//! **Wire Format (18 bytes):** `[MAGIC] [layout] [16-byte big-endian ID]`
+
+use crate::{MicroShardError, MicroShardUUID};
+
+/// First byte of every envelope (`'M'`).
+pub const MAGIC: u8 = 0x4D;
+/// Layout tag for the standard 54/32/36 Unix-epoch layout.
+pub const LAYOUT_V1: u8 = 1;
+/// Size of an encoded envelope in bytes.
+pub const ENVELOPE_LEN: usize = 18;
+
+/// Wraps `id` in an envelope tagged with [`LAYOUT_V1`].
+pub fn encode(id: &MicroShardUUID) -> [u8; ENVELOPE_LEN] {
+    let mut out = [0u8; ENVELOPE_LEN];
+    out[0] = MAGIC;
+    out[1] = LAYOUT_V1;
+    out[2..].copy_from_slice(&id.as_bytes());
+    out
+}
+
+/// Unwraps an envelope with the [`LAYOUT_V1`] tag and validates the payload.
+///
+/// Returns [`MicroShardError::InvalidEncoding`] for a wrong length, magic, or
+/// an unknown layout tag; use [`decode_raw`] to handle other layouts.
+pub fn decode(bytes: &[u8]) -> Result<MicroShardUUID, MicroShardError> {
+    match decode_raw(bytes)? {
+        (LAYOUT_V1, payload) => MicroShardUUID::from_bytes(payload),
+        _ => Err(MicroShardError::InvalidEncoding),
+    }
+}
+
+/// Unwraps an envelope of any layout, returning `(layout, payload)` without
+/// interpreting the payload.
+pub fn decode_raw(bytes: &[u8]) -> Result<(u8, [u8; 16]), MicroShardError> {
+    if bytes.len() != ENVELOPE_LEN || bytes[0] != MAGIC {
+        return Err(MicroShardError::InvalidEncoding);
+    }
+    let payload: [u8; 16] = bytes[2..].try_into().unwrap();
+    Ok((bytes[1], payload))
+}
+
+impl MicroShardUUID {
+    /// Shorthand for [`encode`].
+    pub fn to_envelope(&self) -> [u8; ENVELOPE_LEN] {
+        encode(self)
+    }
+
+    /// Shorthand for [`decode`].
+    pub fn from_envelope(bytes: &[u8]) -> Result<Self, MicroShardError> {
+        decode(bytes)
+    }
+}
@@ -0,0 +1,89 @@
+//! A fixed-length, inline ASCII string for allocation-free formatting.
+
+use core::fmt;
+use core::ops::Deref;
+
+use crate::MicroShardUUID;
+
+/// An inline string of exactly `N` ASCII bytes, returned by
+/// [`MicroShardUUID::to_array_string`].
+///
+/// Lives on the stack and derefs to `&str`, so callers that only need the
+/// text briefly (a log line, a header value) avoid a heap allocation.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FixedStr<const N: usize> {
+    bytes: [u8; N],
+}
+
+impl<const N: usize> FixedStr<N> {
+    /// Internal: wraps bytes the caller guarantees are ASCII.
+    pub(crate) fn from_ascii(bytes: [u8; N]) -> Self {
+        debug_assert!(bytes.is_ascii());
+        Self { bytes }
+    }
+
+    /// Returns the contents as a string slice.
+    pub fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.bytes).expect("FixedStr holds only ASCII")
+    }
+
+    /// Returns the raw bytes.
+    pub fn as_bytes(&self) -> &[u8; N] {
+        &self.bytes
+    }
+}
+
+impl<const N: usize> Deref for FixedStr<N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> AsRef<str> for FixedStr<N> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> fmt::Display for FixedStr<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+impl<const N: usize> fmt::Debug for FixedStr<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> PartialEq<str> for FixedStr<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for FixedStr<N> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl MicroShardUUID {
+    /// Returns the lowercase hyphenated form as an inline 36-byte string.
+    ///
+    /// ```
+    /// # use microshard_uuid::MicroShardUUID;
+    /// let id = MicroShardUUID::parse("1916dd14-36ed-8000-8003-039123456789").unwrap();
+    /// let s = id.to_array_string();
+    /// assert_eq!(s.len(), 36);
+    /// assert!(s.starts_with("1916dd14"));
+    /// ```
+    pub fn to_array_string(&self) -> FixedStr<36> {
+        let mut buf = [0u8; 36];
+        self.encode_hyphenated(&mut buf);
+        FixedStr::from_ascii(buf)
+    }
+}
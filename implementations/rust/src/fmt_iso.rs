@@ -0,0 +1,162 @@
+//! ISO 8601 formatting (enabled with the `fmt-iso` feature).
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::fmt;
+
+use crate::calendar::unix_to_civil;
+use crate::MicroShardUUID;
+
+/// Fractional digits stored in an ID (microseconds).
+const MAX_PRECISION: u8 = 6;
+
+impl MicroShardUUID {
+    /// Extracts the creation time and formats it as an ISO 8601 string.
+    /// Format: `YYYY-MM-DDTHH:MM:SS.mmmmmmZ`
+    #[cfg(feature = "alloc")]
+    pub fn to_iso_string(&self) -> String {
+        let mut out = String::with_capacity(27);
+        self.write_iso(&mut out)
+            .expect("writing to a String cannot fail");
+        out
+    }
+
+    /// Like [`to_iso_string`](Self::to_iso_string), with `digits` fractional
+    /// digits (0 to 6; larger values are treated as 6) for schemas that
+    /// expect whole seconds or milliseconds. Extra digits are truncated, not
+    /// rounded, so the output never moves into the next second.
+    ///
+    /// ```
+    /// use microshard_uuid::MicroShardUUID;
+    ///
+    /// let id = MicroShardUUID::from_parts(1_765_503_300_123_456, 7, 0).unwrap();
+    /// assert_eq!(id.to_iso_string_with_precision(0), "2025-12-12T01:35:00Z");
+    /// assert_eq!(id.to_iso_string_with_precision(3), "2025-12-12T01:35:00.123Z");
+    /// assert_eq!(id.to_iso_string_with_precision(6), id.to_iso_string());
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn to_iso_string_with_precision(&self, digits: u8) -> String {
+        let mut out = String::with_capacity(27);
+        self.iso_display()
+            .precision(digits)
+            .write_to(&mut out)
+            .expect("writing to a String cannot fail");
+        out
+    }
+
+    /// Returns an adapter that formats the creation time as ISO 8601 via
+    /// `Display`, without allocating. Usable under `no_std`:
+    /// `write!(uart, "{}", id.iso_display())`.
+    ///
+    /// The adapter also selects the RFC 3339 / ISO 8601 variant that a
+    /// downstream consumer expects (see [`IsoDisplay`]).
+    pub fn iso_display(&self) -> IsoDisplay {
+        IsoDisplay {
+            id: *self,
+            precision: MAX_PRECISION,
+            separator: 'T',
+            numeric_offset: false,
+            basic: false,
+        }
+    }
+
+    /// Internal: writes the ISO 8601 form into any `fmt::Write` sink.
+    #[cfg(any(feature = "alloc", feature = "heapless"))]
+    pub(crate) fn write_iso<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        self.iso_display().write_to(w)
+    }
+}
+
+/// Allocation-free ISO 8601 view of an ID's timestamp, returned by
+/// [`MicroShardUUID::iso_display`].
+///
+/// Defaults to `YYYY-MM-DDTHH:MM:SS.ffffffZ`; the builder methods pick the
+/// variant a consumer demands and combine freely:
+///
+/// ```
+/// use microshard_uuid::MicroShardUUID;
+///
+/// let id = MicroShardUUID::from_parts(1_714_557_600_123_456, 7, 0).unwrap();
+/// let iso = id.iso_display();
+/// assert_eq!(iso.to_string(), "2024-05-01T10:00:00.123456Z");
+/// assert_eq!(iso.numeric_offset().to_string(), "2024-05-01T10:00:00.123456+00:00");
+/// assert_eq!(iso.separator(' ').precision(3).to_string(), "2024-05-01 10:00:00.123Z");
+/// assert_eq!(iso.basic().precision(0).to_string(), "20240501T100000Z");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct IsoDisplay {
+    id: MicroShardUUID,
+    precision: u8,
+    separator: char,
+    numeric_offset: bool,
+    basic: bool,
+}
+
+impl IsoDisplay {
+    /// Sets the number of fractional digits (0 to 6, default 6; larger
+    /// values are treated as 6). Extra digits are truncated.
+    pub fn precision(mut self, digits: u8) -> Self {
+        self.precision = digits.min(MAX_PRECISION);
+        self
+    }
+
+    /// Sets the character between date and time (default `T`). RFC 3339
+    /// permits a space, which some log pipelines and SQL dialects require.
+    pub fn separator(mut self, separator: char) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Writes the zone as `+00:00` (`+0000` in basic form) instead of `Z`.
+    pub fn numeric_offset(mut self) -> Self {
+        self.numeric_offset = true;
+        self
+    }
+
+    /// Uses the ISO 8601 basic (compact) form without `-` and `:`
+    /// delimiters: `YYYYMMDDTHHMMSS.ffffffZ`.
+    pub fn basic(mut self) -> Self {
+        self.basic = true;
+        self
+    }
+
+    /// Internal: writes the configured form into any `fmt::Write` sink.
+    fn write_to<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        let total_micros = self.id.timestamp_micros();
+
+        let seconds = total_micros / 1_000_000;
+        let micros = total_micros % 1_000_000;
+
+        let (year, month, day, hour, min, sec) = unix_to_civil(seconds as i64);
+
+        let (date_sep, time_sep) = if self.basic { ("", "") } else { ("-", ":") };
+        write!(
+            w,
+            "{:04}{ds}{:02}{ds}{:02}{}{:02}{ts}{:02}{ts}{:02}",
+            year,
+            month,
+            day,
+            self.separator,
+            hour,
+            min,
+            sec,
+            ds = date_sep,
+            ts = time_sep
+        )?;
+        if self.precision > 0 {
+            let fraction = micros / 10u64.pow((MAX_PRECISION - self.precision) as u32);
+            write!(w, ".{:0width$}", fraction, width = self.precision as usize)?;
+        }
+        match (self.numeric_offset, self.basic) {
+            (false, _) => w.write_char('Z'),
+            (true, false) => w.write_str("+00:00"),
+            (true, true) => w.write_str("+0000"),
+        }
+    }
+}
+
+impl fmt::Display for IsoDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_to(f)
+    }
+}
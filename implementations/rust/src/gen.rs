@@ -0,0 +1,358 @@
+//! ID generation: the thread-local PRNG, the system clock, and the
+//! constructors that use them (enabled with the `gen` feature).
+
+use std::cell::RefCell;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::time_source::{SystemTimeSource, TimeSource};
+use crate::{splitmix64, test_support, validate_shard, MicroShardGenerator};
+use crate::{MicroShardError, MicroShardUUID, MAX_RANDOM, MAX_TIME_MICROS};
+
+impl MicroShardUUID {
+    // -------------------------------------------------------------------------
+    // Constructors
+    // -------------------------------------------------------------------------
+
+    /// Generates a new `MicroShardUUID` using the current system time.
+    ///
+    /// # Arguments
+    /// * `shard_id` - A unique identifier for the machine/process generating the ID (max u32).
+    pub fn generate(shard_id: u32) -> Result<Self, MicroShardError> {
+        validate_shard(shard_id)?;
+        let micros = now_micros()?;
+        Self::build(micros, shard_id)
+    }
+
+    /// Generates a `MicroShardUUID` for the current system time shifted by
+    /// `offset_micros` (negative values point into the past).
+    ///
+    /// Intended for load generators and test harnesses that need IDs
+    /// "N microseconds ago/ahead" without computing epoch micros by hand.
+    pub fn generate_offset(shard_id: u32, offset_micros: i64) -> Result<Self, MicroShardError> {
+        validate_shard(shard_id)?;
+        let now = now_micros()?;
+        let micros = if offset_micros < 0 {
+            now.checked_sub(offset_micros.unsigned_abs())
+                .ok_or(MicroShardError::TimeUnderflow)?
+        } else {
+            now.checked_add(offset_micros as u64)
+                .ok_or(MicroShardError::TimeOverflow)?
+        };
+        Self::build(micros, shard_id)
+    }
+
+    /// Generates a `MicroShardUUID` for the instant `ago` before now.
+    ///
+    /// # Example
+    /// `MicroShardUUID::from_duration_ago(Duration::from_secs(300), 1)` mints
+    /// an ID stamped five minutes in the past.
+    pub fn from_duration_ago(ago: Duration, shard_id: u32) -> Result<Self, MicroShardError> {
+        validate_shard(shard_id)?;
+        let ago_micros =
+            u64::try_from(ago.as_micros()).map_err(|_| MicroShardError::TimeUnderflow)?;
+        let micros = now_micros()?
+            .checked_sub(ago_micros)
+            .ok_or(MicroShardError::TimeUnderflow)?;
+        Self::build(micros, shard_id)
+    }
+
+    /// Generates a `MicroShardUUID` using time read from `source`.
+    ///
+    /// `freeze_time` does not apply here: the caller's source is authoritative.
+    pub fn generate_with(source: &impl TimeSource, shard_id: u32) -> Result<Self, MicroShardError> {
+        validate_shard(shard_id)?;
+        Self::build(source.now_micros()?, shard_id)
+    }
+
+    /// Generates a `MicroShardUUID` for the current system time, drawing the
+    /// 36 random bits from `rng` instead of the built-in PRNG.
+    #[cfg(feature = "rand")]
+    pub fn generate_with_rng(
+        shard_id: u32,
+        rng: &mut impl rand_core::RngCore,
+    ) -> Result<Self, MicroShardError> {
+        validate_shard(shard_id)?;
+        let micros = now_micros()?;
+        if micros > MAX_TIME_MICROS {
+            return Err(MicroShardError::TimeOverflow);
+        }
+        Ok(Self::compose(micros, shard_id, rng.next_u64() & MAX_RANDOM))
+    }
+
+    /// Generates `n` unique, strictly increasing IDs for `shard_id` in one call.
+    ///
+    /// Reads the clock once per 4096 IDs instead of once per ID; see
+    /// [`MicroShardGenerator::fill_batch`] for the exact guarantees. IDs from
+    /// separate calls are unique with the same odds as [`generate`](Self::generate)
+    /// but not ordered against each other; use one
+    /// [`MicroShardGenerator::monotonic`] for order across batches.
+    pub fn generate_batch(shard_id: u32, n: usize) -> Result<Vec<Self>, MicroShardError> {
+        MicroShardGenerator::new(shard_id)?.generate_batch(n)
+    }
+
+    /// Overwrites every element of `out` with unique, strictly increasing IDs
+    /// for `shard_id` (see [`generate_batch`](Self::generate_batch)).
+    pub fn fill_batch(shard_id: u32, out: &mut [Self]) -> Result<(), MicroShardError> {
+        MicroShardGenerator::new(shard_id)?.fill_batch(out)
+    }
+
+    /// Generates a `MicroShardUUID` from a specific timestamp in microseconds.
+    pub fn from_micros(micros: u64, shard_id: u32) -> Result<Self, MicroShardError> {
+        validate_shard(shard_id)?;
+        Self::build(micros, shard_id)
+    }
+
+    /// How long ago this ID was stamped: now minus the embedded timestamp.
+    ///
+    /// For TTL checks and staleness metrics. Honours
+    /// [`test_support::freeze_time`].
+    ///
+    /// ```
+    /// use microshard_uuid::{test_support, MicroShardUUID};
+    /// use std::time::Duration;
+    ///
+    /// let _clock = test_support::freeze_time(1_700_000_060_000_000);
+    /// let id = MicroShardUUID::from_micros(1_700_000_000_000_000, 1).unwrap();
+    /// assert_eq!(id.age(), Ok(Duration::from_secs(60)));
+    /// ```
+    ///
+    /// # Errors
+    /// [`MicroShardError::TimestampInFuture`] if the ID is stamped after the
+    /// current time (with how far ahead, in µs).
+    pub fn age(&self) -> Result<Duration, MicroShardError> {
+        let now = now_micros()?;
+        let micros = self.timestamp_micros();
+        match now.checked_sub(micros) {
+            Some(age) => Ok(Duration::from_micros(age)),
+            None => Err(MicroShardError::TimestampInFuture(micros - now)),
+        }
+    }
+
+    /// Rejects IDs stamped more than `max_skew` ahead of the current time.
+    ///
+    /// `from_micros`, `from_iso` and `parse` accept any timestamp up to the
+    /// year 2541, so a bad upstream clock can silently push IDs centuries
+    /// ahead and break time-based partitioning. Chain this at ingestion:
+    ///
+    /// ```
+    /// use microshard_uuid::{MicroShardError, MicroShardUUID};
+    /// use std::time::Duration;
+    ///
+    /// let skew = Duration::from_secs(60);
+    /// let ok = MicroShardUUID::generate(1).unwrap();
+    /// assert_eq!(ok.check_future_skew(skew), Ok(ok));
+    ///
+    /// let year_2500 = MicroShardUUID::from_iso("2500-01-01T00:00:00.000000Z", 1).unwrap();
+    /// assert!(matches!(
+    ///     year_2500.check_future_skew(skew),
+    ///     Err(MicroShardError::TimestampInFuture(_))
+    /// ));
+    /// ```
+    ///
+    /// Honours [`test_support::freeze_time`].
+    ///
+    /// # Errors
+    /// [`MicroShardError::TimestampInFuture`] with how far (µs) the ID is
+    /// ahead of the clock.
+    pub fn check_future_skew(self, max_skew: Duration) -> Result<Self, MicroShardError> {
+        check_future_skew(self.timestamp_micros(), now_micros()?, max_skew)?;
+        Ok(self)
+    }
+
+    // -------------------------------------------------------------------------
+    // Internal Construction Helper
+    // -------------------------------------------------------------------------
+
+    /// Internal builder that composes the bits.
+    pub(crate) fn build(micros: u64, shard_id: u32) -> Result<Self, MicroShardError> {
+        if micros > MAX_TIME_MICROS {
+            return Err(MicroShardError::TimeOverflow);
+        }
+
+        // Get 36 bits of randomness from Thread-Local Xoshiro256**
+        let rnd_val = Xoshiro256StarStar::next_36();
+
+        Ok(Self::compose(micros, shard_id, rnd_val))
+    }
+}
+
+/// Internal: fails if `micros` is more than `max_skew` ahead of `now`.
+pub(crate) fn check_future_skew(
+    micros: u64,
+    now: u64,
+    max_skew: Duration,
+) -> Result<(), MicroShardError> {
+    let ahead = micros.saturating_sub(now);
+    if ahead as u128 > max_skew.as_micros() {
+        return Err(MicroShardError::TimestampInFuture(ahead));
+    }
+    Ok(())
+}
+
+// ==========================================
+// Internal: PRNG (Xoshiro256**)
+// ==========================================
+
+/// Internal State for Xoshiro256**
+pub(crate) struct XoshiroState {
+    s: [u64; 4],
+    init: bool,
+}
+
+impl XoshiroState {
+    const fn new() -> Self {
+        Self {
+            s: [0; 4],
+            init: false,
+        }
+    }
+
+    /// Internal: expands a 64-bit seed into the full state via SplitMix64.
+    fn seed(&mut self, mut seed_val: u64) {
+        self.s[0] = splitmix64(&mut seed_val);
+        self.s[1] = splitmix64(&mut seed_val);
+        self.s[2] = splitmix64(&mut seed_val);
+        self.s[3] = splitmix64(&mut seed_val);
+        self.init = true;
+    }
+
+    /// Internal: a state expanded from a fixed seed (reproducible streams).
+    pub(crate) fn seeded(seed_val: u64) -> Self {
+        let mut state = Self::new();
+        state.seed(seed_val);
+        state
+    }
+
+    /// Internal: one step of the Xoshiro256** algorithm.
+    #[inline(always)]
+    fn next_u64(&mut self) -> u64 {
+        let result = Xoshiro256StarStar::rotl(self.s[1].wrapping_mul(5), 7).wrapping_mul(9);
+        let t = self.s[1] << 17;
+
+        self.s[2] ^= self.s[0];
+        self.s[3] ^= self.s[1];
+        self.s[1] ^= self.s[2];
+        self.s[0] ^= self.s[3];
+
+        self.s[2] ^= t;
+        self.s[3] = Xoshiro256StarStar::rotl(self.s[3], 45);
+
+        result
+    }
+
+    /// Internal: next 36 bits from an owned (already seeded) state.
+    #[cfg(not(feature = "secure-rng"))]
+    #[inline(always)]
+    pub(crate) fn next_36(&mut self) -> u64 {
+        self.next_36_prng()
+    }
+
+    /// Internal: next 36 bits from the PRNG itself, even with `secure-rng`
+    /// (for seeded, reproducible streams).
+    #[inline(always)]
+    pub(crate) fn next_36_prng(&mut self) -> u64 {
+        self.next_u64() & MAX_RANDOM
+    }
+
+    /// Internal: with `secure-rng`, every random field bypasses the PRNG and
+    /// comes straight from the OS CSPRNG.
+    #[cfg(feature = "secure-rng")]
+    pub(crate) fn next_36(&mut self) -> u64 {
+        os_random_36()
+    }
+}
+
+/// Internal: 36 bits from the operating system's CSPRNG.
+///
+/// Panics if the OS source is unavailable, matching `std`'s own
+/// `RandomState`: there is no sound fallback for a security-relevant draw.
+#[cfg(feature = "secure-rng")]
+fn os_random_36() -> u64 {
+    let mut buf = [0u8; 8];
+    getrandom::fill(&mut buf).expect("OS random number generator unavailable");
+    u64::from_ne_bytes(buf) & MAX_RANDOM
+}
+
+// Thread-Local Storage for the RNG state.
+// This acts like `static MS_TLS` in C. The state is seeded once per thread on
+// first use and only advanced afterwards, so IDs minted in the same
+// nanosecond never share a seed (see `benches/generate.rs`).
+thread_local! {
+    static RNG_STATE: RefCell<XoshiroState> = const { RefCell::new(XoshiroState::new()) };
+}
+
+pub(crate) struct Xoshiro256StarStar;
+
+impl Xoshiro256StarStar {
+    /// Internal: Rotate Left
+    #[inline(always)]
+    fn rotl(x: u64, k: u32) -> u64 {
+        x.rotate_left(k)
+    }
+
+    /// Internal: Get High-Res Nanoseconds for Seeding
+    fn get_nanos_seed() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos() as u64
+    }
+
+    /// Internal: runs `f` on this thread's state, seeding it on first use.
+    fn with_state<T>(f: impl FnOnce(&mut XoshiroState) -> T) -> T {
+        RNG_STATE.with(|cell| {
+            let mut ctx = cell.borrow_mut();
+
+            if !ctx.init {
+                let now = Self::get_nanos_seed();
+
+                // ASLR Entropy: XOR time with the address of the state variable on stack/heap
+                let ptr = &*ctx as *const _ as u64;
+                ctx.seed(now ^ ptr);
+            }
+
+            f(&mut ctx)
+        })
+    }
+
+    /// Public: Get next 36 bits of randomness.
+    /// Handles lazy initialization.
+    pub(crate) fn next_36() -> u64 {
+        Self::with_state(XoshiroState::next_36)
+    }
+
+    /// Internal: an independent state for a generator that owns its RNG,
+    /// seeded from this thread's stream.
+    pub(crate) fn fork() -> XoshiroState {
+        let mut state = XoshiroState::new();
+        state.seed(Self::with_state(XoshiroState::next_u64));
+        state
+    }
+}
+
+// ==========================================
+// Internal: Clock
+// ==========================================
+
+/// Reads the system clock as microseconds since the Unix Epoch.
+/// Honours a thread-local override installed by `test_support::freeze_time`.
+pub(crate) fn now_micros() -> Result<u64, MicroShardError> {
+    if let Some(frozen) = test_support::frozen_micros() {
+        return Ok(frozen);
+    }
+    SystemTimeSource.now_micros()
+}
+
+/// Reads the system clock as nanoseconds since the Unix Epoch.
+/// A `freeze_time` override applies at whole microseconds.
+pub(crate) fn now_nanos() -> Result<u64, MicroShardError> {
+    if let Some(frozen) = test_support::frozen_micros() {
+        return frozen
+            .checked_mul(1_000)
+            .ok_or(MicroShardError::TimeOverflow);
+    }
+    let since_epoch = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| MicroShardError::SystemTimeError)?;
+    u64::try_from(since_epoch.as_nanos()).map_err(|_| MicroShardError::TimeOverflow)
+}
@@ -0,0 +1,491 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::time_source::TimeSource;
+use crate::MAX_CLOCK_SEQUENCE;
+use crate::{check_future_skew, now_micros, MicroShardError, MicroShardUUID, ShardBits};
+use crate::{validate_shard, Xoshiro256StarStar, XoshiroState, MAX_RANDOM, MAX_TIME_MICROS};
+
+/// Monotonic mode: the top 12 of the 36 random bits hold the sequence.
+const SEQUENCE_SHIFT: u32 = 24;
+const MAX_SEQUENCE: u64 = MAX_RANDOM >> SEQUENCE_SHIFT; // 4095
+const SEQUENCE_RANDOM_MASK: u64 = (1 << SEQUENCE_SHIFT) - 1;
+
+// ==========================================
+// Per-Shard Hot-Path Generator
+// ==========================================
+
+/// What a [`MicroShardGenerator`] does when the clock reads earlier than the
+/// last ID it issued (e.g. after an NTP step), set with
+/// [`with_regression_policy`](MicroShardGenerator::with_regression_policy).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockRegressionPolicy {
+    /// Fail with [`MicroShardError::ClockMovedBackwards`] (the lag in µs).
+    Error,
+    /// Stamp the last issued timestamp + 1µs, running ahead of the clock
+    /// until it catches up.
+    NextMicro,
+    /// Sleep until the clock catches up, for at most the given duration,
+    /// then fail as [`Error`](Self::Error) does. Only `next` and batch
+    /// calls wait; `next_at` has no clock to re-read and fails immediately.
+    Wait(Duration),
+    /// Like UUIDv1's `clock_seq`: keep stamping the clock's (earlier) time,
+    /// but bump a 4-bit clock sequence carried in the lowest random bits of
+    /// every ID ([`MicroShardUUID::clock_sequence`]). IDs issued after the
+    /// step can then never repeat one issued before it, and sorting by
+    /// `(clock_sequence, id)` restores issue order across up to 15 steps.
+    /// Timestamps stay truthful, at the cost of raw `u128` order.
+    ClockSequence,
+}
+
+/// A single-owner generator bound to one shard.
+///
+/// [`MicroShardUUID::generate`] re-validates the shard and re-packs its bits on
+/// every call. `MicroShardGenerator` does that once in [`new`](Self::new):
+/// the shard, version and variant bits are cached pre-shifted, and the
+/// generator owns its own Xoshiro256** state (forked from the thread-local
+/// stream), so each ID costs one clock read, one PRNG step and two ORs.
+///
+/// Methods take `&mut self`; keep one generator per thread or task. For a
+/// shared, duplicate-free generator use [`UniqueGenerator`](crate::UniqueGenerator).
+///
+/// **Monotonic mode** ([`monotonic`](Self::monotonic)): IDs from the same
+/// generator are strictly increasing. The top 12 random bits become a
+/// per-microsecond sequence (4096 IDs/µs) and the low 24 stay random:
+/// - **New microsecond:** the sequence restarts at 0.
+/// - **Same or earlier microsecond:** the last timestamp is held and the
+///   sequence is incremented, so a clock step backwards never reorders IDs.
+/// - **Sequence exhausted:** the timestamp is advanced by 1µs.
+///
+/// **Clock regression** ([`with_regression_policy`](Self::with_regression_policy)):
+/// without a policy, a non-monotonic generator stamps whatever the clock
+/// says, so IDs can sort before ones issued earlier. A policy makes the
+/// generator remember its last timestamp and never go below it.
+///
+/// ```
+/// use microshard_uuid::MicroShardGenerator;
+///
+/// let mut ids = MicroShardGenerator::new(101).unwrap();
+/// let id = ids.next().unwrap();
+/// assert_eq!(id.shard_id(), 101);
+///
+/// let backfill = ids.next_at(1_700_000_000_000_000).unwrap();
+/// assert_eq!(backfill.timestamp_micros(), 1_700_000_000_000_000);
+/// ```
+pub struct MicroShardGenerator {
+    shard_id: u32,
+    shard: ShardBits,
+    rng: RngSource,
+    sequence: Option<Sequence>,
+    epoch_micros: u64,
+    clock: Option<Box<dyn TimeSource + Send>>,
+    regression_policy: Option<ClockRegressionPolicy>,
+    max_future_skew: Option<Duration>,
+    /// Last issued timestamp (stored form, i.e. relative to `epoch_micros`).
+    last_micros: u64,
+    clock_sequence: u8,
+}
+
+/// Internal: where a generator draws its random fields from.
+enum RngSource {
+    Builtin(XoshiroState),
+    /// Fixed-seed PRNG; bypasses `secure-rng` so the stream is reproducible.
+    Seeded(XoshiroState),
+    #[cfg(feature = "rand")]
+    Custom(Box<dyn rand_core::RngCore + Send>),
+}
+
+impl RngSource {
+    #[inline(always)]
+    fn next_36(&mut self) -> u64 {
+        match self {
+            Self::Builtin(state) => state.next_36(),
+            Self::Seeded(state) => state.next_36_prng(),
+            #[cfg(feature = "rand")]
+            Self::Custom(rng) => rng.next_u64() & MAX_RANDOM,
+        }
+    }
+}
+
+/// Internal: monotonic-mode state (last issued timestamp and its sequence).
+#[derive(Debug)]
+struct Sequence {
+    last_micros: u64,
+    counter: u64,
+}
+
+impl Sequence {
+    const fn new() -> Self {
+        Self {
+            last_micros: 0,
+            counter: 0,
+        }
+    }
+
+    /// A sequence whose next `advance` at `micros` yields counter
+    /// `start + 1`, so independent batches in one microsecond don't line up.
+    fn resuming(micros: u64, start: u64) -> Self {
+        Self {
+            last_micros: micros,
+            counter: start % MAX_SEQUENCE,
+        }
+    }
+
+    /// True when the next `advance` on the held timestamp spills into the next µs.
+    fn is_exhausted(&self) -> bool {
+        self.counter == MAX_SEQUENCE
+    }
+
+    /// Returns the (timestamp, random field) for the next ID.
+    fn advance(&mut self, micros: u64, random: u64) -> Result<(u64, u64), MicroShardError> {
+        if micros > self.last_micros {
+            self.last_micros = micros;
+            self.counter = 0;
+        } else if self.counter < MAX_SEQUENCE {
+            self.counter += 1;
+        } else {
+            if self.last_micros >= MAX_TIME_MICROS {
+                return Err(MicroShardError::TimeOverflow);
+            }
+            self.last_micros += 1;
+            self.counter = 0;
+        }
+        let field = (self.counter << SEQUENCE_SHIFT) | (random & SEQUENCE_RANDOM_MASK);
+        Ok((self.last_micros, field))
+    }
+}
+
+impl fmt::Debug for MicroShardGenerator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MicroShardGenerator")
+            .field("shard_id", &self.shard_id)
+            .field("sequence", &self.sequence)
+            .field("epoch_micros", &self.epoch_micros)
+            .field("clock", &self.clock.is_some())
+            .field("regression_policy", &self.regression_policy)
+            .field("max_future_skew", &self.max_future_skew)
+            .field("last_micros", &self.last_micros)
+            .field("clock_sequence", &self.clock_sequence)
+            .finish_non_exhaustive()
+    }
+}
+
+impl MicroShardGenerator {
+    /// Creates a generator for `shard_id`, validating it once.
+    pub fn new(shard_id: u32) -> Result<Self, MicroShardError> {
+        validate_shard(shard_id)?;
+        Ok(Self {
+            shard_id,
+            shard: ShardBits::new(shard_id),
+            rng: RngSource::Builtin(Xoshiro256StarStar::fork()),
+            sequence: None,
+            epoch_micros: 0,
+            clock: None,
+            regression_policy: None,
+            max_future_skew: None,
+            last_micros: 0,
+            clock_sequence: 0,
+        })
+    }
+
+    /// Creates a generator for `shard_id` whose IDs are strictly increasing.
+    pub fn monotonic(shard_id: u32) -> Result<Self, MicroShardError> {
+        Ok(Self::new(shard_id)?.into_monotonic())
+    }
+
+    /// Switches this generator to monotonic mode (see [`monotonic`](Self::monotonic)).
+    /// A generator that is already monotonic keeps its current sequence.
+    pub fn into_monotonic(mut self) -> Self {
+        self.sequence.get_or_insert_with(Sequence::new);
+        self
+    }
+
+    /// Creates a generator for `shard_id` that draws every random field from
+    /// `rng` (ChaCha20, hardware entropy, a seeded test RNG, ...).
+    ///
+    /// The caller's RNG takes precedence over the `secure-rng` feature. Chain
+    /// [`into_monotonic`](Self::into_monotonic) for strictly increasing IDs.
+    #[cfg(feature = "rand")]
+    pub fn with_rng<R>(shard_id: u32, rng: R) -> Result<Self, MicroShardError>
+    where
+        R: rand_core::RngCore + Send + 'static,
+    {
+        let mut generator = Self::new(shard_id)?;
+        generator.rng = RngSource::Custom(Box::new(rng));
+        Ok(generator)
+    }
+
+    /// Internal: a generator whose random fields come from a fixed seed
+    /// (see `test_support::DeterministicGenerator`).
+    pub(crate) fn seeded(shard_id: u32, seed: u64) -> Result<Self, MicroShardError> {
+        let mut generator = Self::new(shard_id)?;
+        generator.rng = RngSource::Seeded(XoshiroState::seeded(seed));
+        Ok(generator)
+    }
+
+    /// Stores time as an offset from a custom epoch instead of 1970-01-01.
+    ///
+    /// The 54-bit field then covers `epoch_micros ..= epoch_micros + 2^54 - 1`
+    /// (~570 years from the chosen epoch) instead of 1970–2541. The epoch is
+    /// not recorded in the ID: readers must decode with the same value via
+    /// [`MicroShardUUID::timestamp_micros_with_epoch`]. Times before the epoch
+    /// fail with [`MicroShardError::TimeUnderflow`].
+    ///
+    /// ```
+    /// use microshard_uuid::MicroShardGenerator;
+    ///
+    /// const EPOCH_2020: u64 = 1_577_836_800_000_000; // 2020-01-01T00:00:00Z
+    /// let mut ids = MicroShardGenerator::new(1).unwrap().with_epoch(EPOCH_2020);
+    /// let id = ids.next_at(1_700_000_000_000_000).unwrap();
+    /// assert_eq!(id.timestamp_micros_with_epoch(EPOCH_2020), Ok(1_700_000_000_000_000));
+    /// ```
+    pub fn with_epoch(mut self, epoch_micros: u64) -> Self {
+        self.epoch_micros = epoch_micros;
+        self
+    }
+
+    /// Reads time from `clock` instead of the system clock (simulations,
+    /// deterministic tests, an external time service). Applies to
+    /// [`next`](Self::next) and [`fill_batch`](Self::fill_batch);
+    /// `freeze_time` is then ignored, as the caller's clock is authoritative.
+    pub fn with_clock(mut self, clock: impl TimeSource + Send + 'static) -> Self {
+        self.clock = Some(Box::new(clock));
+        self
+    }
+
+    /// Guards against the clock stepping backwards: IDs are never stamped
+    /// earlier than the last one this generator issued (see
+    /// [`ClockRegressionPolicy`]). Takes precedence over the monotonic hold.
+    ///
+    /// ```
+    /// use microshard_uuid::{ClockRegressionPolicy, MicroShardError, MicroShardGenerator};
+    ///
+    /// let mut ids = MicroShardGenerator::new(1)
+    ///     .unwrap()
+    ///     .with_regression_policy(ClockRegressionPolicy::NextMicro);
+    /// ids.next_at(1_700_000_000_000_500).unwrap();
+    /// let id = ids.next_at(1_700_000_000_000_000).unwrap(); // clock stepped back 500µs
+    /// assert_eq!(id.timestamp_micros(), 1_700_000_000_000_501);
+    /// ```
+    pub fn with_regression_policy(mut self, policy: ClockRegressionPolicy) -> Self {
+        self.regression_policy = Some(policy);
+        self
+    }
+
+    /// Rejects explicit timestamps more than `max_skew` ahead of the clock.
+    ///
+    /// [`next_at`](Self::next_at) takes any time up to the year 2541, so a
+    /// bad upstream clock can mint IDs centuries ahead and break time-based
+    /// partitioning. With a tolerance set, such calls fail instead. IDs
+    /// stamped from the generator's own clock are never rejected.
+    ///
+    /// ```
+    /// use microshard_uuid::{MicroShardError, MicroShardGenerator};
+    /// use std::time::Duration;
+    ///
+    /// let mut ids = MicroShardGenerator::new(1)
+    ///     .unwrap()
+    ///     .with_clock(|| Ok(1_700_000_000_000_000))
+    ///     .with_max_future_skew(Duration::from_secs(1));
+    /// assert!(ids.next_at(1_700_000_000_500_000).is_ok());
+    /// assert_eq!(
+    ///     ids.next_at(1_700_000_002_000_000),
+    ///     Err(MicroShardError::TimestampInFuture(2_000_000))
+    /// );
+    /// ```
+    pub fn with_max_future_skew(mut self, max_skew: Duration) -> Self {
+        self.max_future_skew = Some(max_skew);
+        self
+    }
+
+    /// The tolerance set with [`with_max_future_skew`](Self::with_max_future_skew).
+    pub fn max_future_skew(&self) -> Option<Duration> {
+        self.max_future_skew
+    }
+
+    /// The policy set with [`with_regression_policy`](Self::with_regression_policy).
+    pub fn regression_policy(&self) -> Option<ClockRegressionPolicy> {
+        self.regression_policy
+    }
+
+    /// The current 4-bit clock sequence (see [`ClockRegressionPolicy::ClockSequence`]).
+    pub fn clock_sequence(&self) -> u8 {
+        self.clock_sequence
+    }
+
+    /// The custom epoch in Unix microseconds (0 for the default Unix epoch).
+    pub fn epoch_micros(&self) -> u64 {
+        self.epoch_micros
+    }
+
+    /// Whether this generator was created with [`monotonic`](Self::monotonic).
+    pub fn is_monotonic(&self) -> bool {
+        self.sequence.is_some()
+    }
+
+    /// The shard every ID from this generator is bound to.
+    pub fn shard_id(&self) -> u32 {
+        self.shard_id
+    }
+
+    /// Generates an ID stamped with the current system time.
+    ///
+    /// Honours [`test_support::freeze_time`](crate::test_support::freeze_time)
+    /// unless a clock was set with [`with_clock`](Self::with_clock).
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Result<MicroShardUUID, MicroShardError> {
+        let micros = self.now()?;
+        self.mint(micros, true)
+    }
+
+    /// Generates an ID stamped with `micros` (microseconds since the Unix Epoch).
+    ///
+    /// In monotonic mode the stamped time may be later than `micros` (held
+    /// after a regression, or bumped when the sequence is exhausted).
+    ///
+    /// # Errors
+    /// [`MicroShardError::TimestampInFuture`] if `micros` is beyond the
+    /// [`with_max_future_skew`](Self::with_max_future_skew) tolerance.
+    pub fn next_at(&mut self, micros: u64) -> Result<MicroShardUUID, MicroShardError> {
+        if let Some(max_skew) = self.max_future_skew {
+            check_future_skew(micros, self.now()?, max_skew)?;
+        }
+        self.mint(micros, false)
+    }
+
+    /// Internal: stamps one ID; `can_wait` if the clock may be re-read.
+    fn mint(&mut self, micros: u64, can_wait: bool) -> Result<MicroShardUUID, MicroShardError> {
+        let micros = stored_micros(micros, self.epoch_micros)?;
+        let micros = self.check_regression(micros, can_wait)?;
+        let random = self.rng.next_36();
+        let (micros, random) = match &mut self.sequence {
+            Some(sequence) => sequence.advance(micros, random)?,
+            None => (micros, random),
+        };
+        self.last_micros = micros;
+        let (keep, tag) = self.clock_sequence_bits();
+        let random = (random & keep) | tag;
+        Ok(MicroShardUUID::compose_with(micros, self.shard, random))
+    }
+
+    /// Fills `out` with IDs that are strictly increasing, and therefore unique
+    /// within the batch.
+    ///
+    /// The clock is read once, then again only each time the 4096-ID
+    /// per-microsecond sequence (see [`monotonic`](Self::monotonic)) runs out,
+    /// so large batches may be stamped slightly ahead of the wall clock. A
+    /// monotonic generator continues its own sequence; otherwise each batch
+    /// starts a fresh one at a random counter, so two batches minted in the
+    /// same microsecond collide no more often than the same number of
+    /// `generate` calls. On error, `out` is left partially overwritten.
+    pub fn fill_batch(&mut self, out: &mut [MicroShardUUID]) -> Result<(), MicroShardError> {
+        let first = stored_micros(self.now()?, self.epoch_micros)?;
+        let mut now = self.check_regression(first, true)?;
+
+        let (keep, tag) = self.clock_sequence_bits();
+        let mut batch_sequence = Sequence::resuming(now, self.rng.next_36());
+        let sequence = self.sequence.as_mut().unwrap_or(&mut batch_sequence);
+        let epoch_micros = self.epoch_micros;
+        let clock = self.clock.as_deref();
+        let now_stored = || stored_micros(read_clock(clock)?, epoch_micros);
+        for slot in out.iter_mut() {
+            if sequence.is_exhausted() {
+                // Within the batch the sequence holds time on a regression.
+                now = now_stored()?;
+            }
+            let (micros, random) = sequence.advance(now, self.rng.next_36())?;
+            let random = (random & keep) | tag;
+            *slot = MicroShardUUID::compose_with(micros, self.shard, random);
+            self.last_micros = micros;
+        }
+        Ok(())
+    }
+
+    /// Generates `n` strictly increasing IDs (see [`fill_batch`](Self::fill_batch)).
+    pub fn generate_batch(&mut self, n: usize) -> Result<Vec<MicroShardUUID>, MicroShardError> {
+        let mut out = vec![MicroShardUUID(0); n];
+        self.fill_batch(&mut out)?;
+        Ok(out)
+    }
+
+    /// Internal: the configured clock, or the (freezable) system clock.
+    fn now(&self) -> Result<u64, MicroShardError> {
+        read_clock(self.clock.as_deref())
+    }
+
+    /// Internal: `(keep, tag)` masks that write the clock sequence into the
+    /// low random bits (`(random & keep) | tag`); a no-op unless enabled.
+    fn clock_sequence_bits(&self) -> (u64, u64) {
+        match self.regression_policy {
+            Some(ClockRegressionPolicy::ClockSequence) => {
+                (!MAX_CLOCK_SEQUENCE, self.clock_sequence as u64)
+            }
+            _ => (u64::MAX, 0),
+        }
+    }
+
+    /// Internal: applies the regression policy to a stored timestamp.
+    fn check_regression(&mut self, micros: u64, can_wait: bool) -> Result<u64, MicroShardError> {
+        let policy = match self.regression_policy {
+            Some(policy) if micros < self.last_micros => policy,
+            _ => return Ok(micros),
+        };
+        let behind = self.last_micros - micros;
+        match policy {
+            ClockRegressionPolicy::Error => Err(MicroShardError::ClockMovedBackwards(behind)),
+            ClockRegressionPolicy::NextMicro => {
+                if self.last_micros >= MAX_TIME_MICROS {
+                    return Err(MicroShardError::TimeOverflow);
+                }
+                Ok(self.last_micros + 1)
+            }
+            ClockRegressionPolicy::Wait(max_wait) if can_wait => self.wait_for(behind, max_wait),
+            ClockRegressionPolicy::Wait(_) => Err(MicroShardError::ClockMovedBackwards(behind)),
+            ClockRegressionPolicy::ClockSequence => {
+                self.clock_sequence = (self.clock_sequence + 1) & MAX_CLOCK_SEQUENCE as u8;
+                // Restart the monotonic sequence so it accepts the earlier time.
+                if let Some(sequence) = &mut self.sequence {
+                    *sequence = Sequence::new();
+                }
+                Ok(micros)
+            }
+        }
+    }
+
+    /// Internal: sleeps for the remaining lag until the clock reaches the
+    /// last issued timestamp or `max_wait` runs out.
+    fn wait_for(&self, mut behind: u64, max_wait: Duration) -> Result<u64, MicroShardError> {
+        let deadline = Instant::now() + max_wait;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(MicroShardError::ClockMovedBackwards(behind));
+            }
+            std::thread::sleep(Duration::from_micros(behind).min(remaining));
+            let micros = stored_micros(self.now()?, self.epoch_micros)?;
+            if micros >= self.last_micros {
+                return Ok(micros);
+            }
+            behind = self.last_micros - micros;
+        }
+    }
+}
+
+/// Internal: reads `clock`, falling back to the system clock.
+fn read_clock(clock: Option<&(dyn TimeSource + Send)>) -> Result<u64, MicroShardError> {
+    match clock {
+        Some(clock) => clock.now_micros(),
+        None => now_micros(),
+    }
+}
+
+/// Internal: converts Unix micros to the stored offset from `epoch_micros`.
+fn stored_micros(micros: u64, epoch_micros: u64) -> Result<u64, MicroShardError> {
+    let stored = micros
+        .checked_sub(epoch_micros)
+        .ok_or(MicroShardError::TimeUnderflow)?;
+    if stored > MAX_TIME_MICROS {
+        return Err(MicroShardError::TimeOverflow);
+    }
+    Ok(stored)
+}
@@ -0,0 +1,29 @@
+//! Allocation-free text forms (enabled with the `heapless` feature).
+//!
+//! Firmware without an allocator can still emit both textual forms, e.g. for
+//! UART logs or MQTT payloads. Both forms have a fixed length, so the
+//! capacities below are exact and formatting cannot fail.
+
+use heapless::String;
+
+use crate::{write_hyphenated, MicroShardUUID};
+
+impl MicroShardUUID {
+    /// Like `to_iso_string()`, into a fixed-capacity string
+    /// (`YYYY-MM-DDTHH:MM:SS.ffffffZ`, always 27 bytes).
+    #[cfg(feature = "fmt-iso")]
+    pub fn to_iso_heapless(&self) -> String<27> {
+        let mut out = String::new();
+        self.write_iso(&mut out)
+            .expect("ISO form is always 27 bytes");
+        out
+    }
+
+    /// Like `to_string()`, into a fixed-capacity string (8-4-4-4-12, always
+    /// 36 bytes).
+    pub fn to_hex_heapless(&self) -> String<36> {
+        let mut out = String::new();
+        write_hyphenated(self.as_u128(), &mut out).expect("hyphenated form is always 36 bytes");
+        out
+    }
+}
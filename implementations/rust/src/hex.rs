@@ -0,0 +1,320 @@
+//! Hex encoding and decoding kernels for the hyphenated and simple forms.
+//!
+//! The 16 bytes of an ID fit one 128-bit register, so each direction is a
+//! handful of vector instructions: SSE2 on x86_64 and NEON on aarch64, both
+//! part of those targets' baseline, so no runtime detection is needed (and
+//! AVX2's wider registers would sit half empty). Other targets use a
+//! branch-free scalar fallback with the same arithmetic: no lookup tables, no
+//! per-character `match`, and invalid input accumulated into a single flag
+//! checked once at the end.
+//!
+//! Decoding also has `const_` variants, scalar only, for `const fn` parsing
+//! (`MicroShardUUID::parse`, `msuuid!`). See `benches/hex.rs` for throughput.
+
+/// Byte offsets of the hyphens in the 8-4-4-4-12 form.
+#[cfg(feature = "parse")]
+const HYPHENS: [usize; 4] = [8, 13, 18, 23];
+
+/// Internal: the 32 ASCII hex digits of `v`, most significant first.
+#[inline]
+pub(crate) fn encode_simple(v: u128, upper: bool) -> [u8; 32] {
+    // Distance from '9' + 1 to the first letter: 'a' (39) or 'A' (7).
+    let gap = if upper {
+        b'A' - b'9' - 1
+    } else {
+        b'a' - b'9' - 1
+    };
+    // Big Endian (Network) order regardless of the host machine's endianness.
+    arch::encode(v.to_be_bytes(), gap)
+}
+
+/// Internal: the 36 ASCII bytes of the 8-4-4-4-12 form.
+#[inline]
+pub(crate) fn encode_hyphenated(v: u128, upper: bool) -> [u8; 36] {
+    let digits = encode_simple(v, upper);
+    let mut out = [b'-'; 36];
+    out[0..8].copy_from_slice(&digits[0..8]);
+    out[9..13].copy_from_slice(&digits[8..12]);
+    out[14..18].copy_from_slice(&digits[12..16]);
+    out[19..23].copy_from_slice(&digits[16..20]);
+    out[24..36].copy_from_slice(&digits[20..32]);
+    out
+}
+
+/// Internal: one nibble (0..=15) to its ASCII digit, without branching.
+#[cfg(not(any(
+    all(target_arch = "x86_64", target_feature = "sse2"),
+    all(target_arch = "aarch64", target_feature = "neon")
+)))]
+#[inline(always)]
+fn hex_char(n: u8, gap: u8) -> u8 {
+    // 0xFF when n > 9 (the subtraction wraps and sets the top bit), else 0.
+    let letter = (9u8.wrapping_sub(n) >> 7).wrapping_neg();
+    b'0' + n + (letter & gap)
+}
+
+/// Internal: parses 32 hex digits (either case) into a raw `u128`.
+/// Returns `None` on any non-hex byte.
+#[cfg(feature = "parse")]
+#[inline]
+pub(crate) fn decode_simple(b: &[u8; 32]) -> Option<u128> {
+    arch::decode(b).map(u128::from_be_bytes)
+}
+
+/// Internal: parses the 36-byte 8-4-4-4-12 form into a raw `u128`.
+/// Returns `None` on a misplaced hyphen or any non-hex digit.
+#[cfg(feature = "parse")]
+#[inline]
+pub(crate) fn decode_hyphenated(b: &[u8; 36]) -> Option<u128> {
+    let bad = HYPHENS.iter().fold(0, |bad, &h| bad | (b[h] ^ b'-'));
+    let mut digits = [0u8; 32];
+    digits[0..8].copy_from_slice(&b[0..8]);
+    digits[8..12].copy_from_slice(&b[9..13]);
+    digits[12..16].copy_from_slice(&b[14..18]);
+    digits[16..20].copy_from_slice(&b[19..23]);
+    digits[20..32].copy_from_slice(&b[24..36]);
+    if bad != 0 {
+        return None;
+    }
+    decode_simple(&digits)
+}
+
+/// Internal: `const` (scalar) [`decode_simple`].
+#[cfg(feature = "parse")]
+pub(crate) const fn const_decode_simple(b: &[u8; 32]) -> Option<u128> {
+    // `while` rather than iterators so this stays usable in const contexts
+    let mut bytes = [0u8; 16];
+    let mut bad = 0u8;
+    let mut i = 0;
+    while i < 16 {
+        let (hi, hi_bad) = unhex(b[2 * i]);
+        let (lo, lo_bad) = unhex(b[2 * i + 1]);
+        bytes[i] = (hi << 4) | lo;
+        bad |= hi_bad | lo_bad;
+        i += 1;
+    }
+    if bad != 0 {
+        return None;
+    }
+    Some(u128::from_be_bytes(bytes))
+}
+
+/// Internal: `const` (scalar) [`decode_hyphenated`].
+#[cfg(feature = "parse")]
+pub(crate) const fn const_decode_hyphenated(b: &[u8; 36]) -> Option<u128> {
+    let mut bad = 0u8;
+    let mut h = 0;
+    while h < HYPHENS.len() {
+        bad |= b[HYPHENS[h]] ^ b'-';
+        h += 1;
+    }
+
+    let mut bytes = [0u8; 16];
+    let mut i = 0;
+    while i < 16 {
+        // Skip the hyphens arithmetically: byte pairs 4, 6, 8, 10 start a group.
+        let pos =
+            2 * i + (i >= 4) as usize + (i >= 6) as usize + (i >= 8) as usize + (i >= 10) as usize;
+        let (hi, hi_bad) = unhex(b[pos]);
+        let (lo, lo_bad) = unhex(b[pos + 1]);
+        bytes[i] = (hi << 4) | lo;
+        bad |= hi_bad | lo_bad;
+        i += 1;
+    }
+    if bad != 0 {
+        return None;
+    }
+    Some(u128::from_be_bytes(bytes))
+}
+
+/// Internal: one ASCII hex digit to `(value, error)`, without branching.
+/// `error` is non-zero when `c` is not `0-9`, `a-f` or `A-F`.
+#[cfg(feature = "parse")]
+#[inline(always)]
+const fn unhex(c: u8) -> (u8, u8) {
+    let digit = c.wrapping_sub(b'0');
+    let letter = (c | 0x20).wrapping_sub(b'a');
+    // 0xFF masks selecting which interpretation (if any) is valid.
+    let is_digit = ((digit < 10) as u8).wrapping_neg();
+    let is_letter = ((letter < 6) as u8).wrapping_neg();
+    let value = (digit & is_digit) | (letter.wrapping_add(10) & is_letter);
+    (value & 0xF, !(is_digit | is_letter))
+}
+
+/// SSE2 kernels (x86_64 baseline).
+#[cfg(all(target_arch = "x86_64", target_feature = "sse2"))]
+mod arch {
+    use core::arch::x86_64::*;
+
+    /// Internal: the 32 ASCII digits of 16 bytes.
+    #[inline]
+    pub(super) fn encode(bytes: [u8; 16], gap: u8) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        // SAFETY: SSE2 is enabled for this target; the loads and stores are
+        // unaligned and stay within the 16- and 32-byte arrays.
+        unsafe {
+            let v = _mm_loadu_si128(bytes.as_ptr().cast());
+            let mask = _mm_set1_epi8(0x0F);
+            let hi = _mm_and_si128(_mm_srli_epi16::<4>(v), mask);
+            let lo = _mm_and_si128(v, mask);
+            // Interleave to hi0 lo0 hi1 lo1 ... (digit order)
+            let first = ascii(_mm_unpacklo_epi8(hi, lo), gap);
+            let second = ascii(_mm_unpackhi_epi8(hi, lo), gap);
+            _mm_storeu_si128(out.as_mut_ptr().cast(), first);
+            _mm_storeu_si128(out.as_mut_ptr().add(16).cast(), second);
+        }
+        out
+    }
+
+    /// Internal: nibbles (0..=15) to ASCII digits.
+    #[inline(always)]
+    unsafe fn ascii(n: __m128i, gap: u8) -> __m128i {
+        let letter = _mm_cmpgt_epi8(n, _mm_set1_epi8(9));
+        let digit = _mm_add_epi8(n, _mm_set1_epi8(b'0' as i8));
+        _mm_add_epi8(digit, _mm_and_si128(letter, _mm_set1_epi8(gap as i8)))
+    }
+
+    /// Internal: 32 hex digits to 16 bytes, or `None` on any non-hex byte.
+    #[cfg(feature = "parse")]
+    #[inline]
+    pub(super) fn decode(digits: &[u8; 32]) -> Option<[u8; 16]> {
+        let mut out = [0u8; 16];
+        // SAFETY: as in `encode`.
+        unsafe {
+            let (a, a_ok) = nibbles(_mm_loadu_si128(digits.as_ptr().cast()));
+            let (b, b_ok) = nibbles(_mm_loadu_si128(digits.as_ptr().add(16).cast()));
+            if _mm_movemask_epi8(_mm_and_si128(a_ok, b_ok)) != 0xFFFF {
+                return None;
+            }
+            // Each 16-bit lane holds (hi, lo) with hi in the low byte
+            let byte_mask = _mm_set1_epi16(0xFF);
+            let join = |v| {
+                _mm_or_si128(
+                    _mm_slli_epi16::<4>(_mm_and_si128(v, byte_mask)),
+                    _mm_srli_epi16::<8>(v),
+                )
+            };
+            _mm_storeu_si128(out.as_mut_ptr().cast(), _mm_packus_epi16(join(a), join(b)));
+        }
+        Some(out)
+    }
+
+    /// Internal: ASCII hex digits to `(nibbles, 0xFF where valid)`.
+    #[cfg(feature = "parse")]
+    #[inline(always)]
+    unsafe fn nibbles(c: __m128i) -> (__m128i, __m128i) {
+        let digit = _mm_sub_epi8(c, _mm_set1_epi8(b'0' as i8));
+        let letter = _mm_sub_epi8(
+            _mm_or_si128(c, _mm_set1_epi8(0x20)),
+            _mm_set1_epi8(b'a' as i8),
+        );
+        let is_digit = lt_unsigned(digit, 10);
+        let is_letter = lt_unsigned(letter, 6);
+        let letter_value = _mm_add_epi8(letter, _mm_set1_epi8(10));
+        let value = _mm_or_si128(
+            _mm_and_si128(digit, is_digit),
+            _mm_and_si128(letter_value, is_letter),
+        );
+        (value, _mm_or_si128(is_digit, is_letter))
+    }
+
+    /// Internal: unsigned `x < n` per byte (SSE2 only compares signed, so
+    /// both sides are shifted by 128 first).
+    #[cfg(feature = "parse")]
+    #[inline(always)]
+    unsafe fn lt_unsigned(x: __m128i, n: u8) -> __m128i {
+        let flip = _mm_set1_epi8(i8::MIN);
+        _mm_cmplt_epi8(_mm_xor_si128(x, flip), _mm_set1_epi8((n ^ 0x80) as i8))
+    }
+}
+
+/// NEON kernels (aarch64 baseline).
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+mod arch {
+    use core::arch::aarch64::*;
+
+    /// Internal: the 32 ASCII digits of 16 bytes.
+    #[inline]
+    pub(super) fn encode(bytes: [u8; 16], gap: u8) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        // SAFETY: NEON is enabled for this target; the loads and stores stay
+        // within the 16- and 32-byte arrays.
+        unsafe {
+            let v = vld1q_u8(bytes.as_ptr());
+            let hi = vshrq_n_u8::<4>(v);
+            let lo = vandq_u8(v, vdupq_n_u8(0x0F));
+            // Interleave to hi0 lo0 hi1 lo1 ... (digit order)
+            vst1q_u8(out.as_mut_ptr(), ascii(vzip1q_u8(hi, lo), gap));
+            vst1q_u8(out.as_mut_ptr().add(16), ascii(vzip2q_u8(hi, lo), gap));
+        }
+        out
+    }
+
+    /// Internal: nibbles (0..=15) to ASCII digits.
+    #[inline(always)]
+    unsafe fn ascii(n: uint8x16_t, gap: u8) -> uint8x16_t {
+        let letter = vcgtq_u8(n, vdupq_n_u8(9));
+        let digit = vaddq_u8(n, vdupq_n_u8(b'0'));
+        vaddq_u8(digit, vandq_u8(letter, vdupq_n_u8(gap)))
+    }
+
+    /// Internal: 32 hex digits to 16 bytes, or `None` on any non-hex byte.
+    #[cfg(feature = "parse")]
+    #[inline]
+    pub(super) fn decode(digits: &[u8; 32]) -> Option<[u8; 16]> {
+        let mut out = [0u8; 16];
+        // SAFETY: as in `encode`.
+        unsafe {
+            let (a, a_ok) = nibbles(vld1q_u8(digits.as_ptr()));
+            let (b, b_ok) = nibbles(vld1q_u8(digits.as_ptr().add(16)));
+            if vminvq_u8(vandq_u8(a_ok, b_ok)) == 0 {
+                return None;
+            }
+            // Even lanes are high nibbles, odd lanes low nibbles
+            let hi = vuzp1q_u8(a, b);
+            let lo = vuzp2q_u8(a, b);
+            vst1q_u8(out.as_mut_ptr(), vorrq_u8(vshlq_n_u8::<4>(hi), lo));
+        }
+        Some(out)
+    }
+
+    /// Internal: ASCII hex digits to `(nibbles, 0xFF where valid)`.
+    #[cfg(feature = "parse")]
+    #[inline(always)]
+    unsafe fn nibbles(c: uint8x16_t) -> (uint8x16_t, uint8x16_t) {
+        let digit = vsubq_u8(c, vdupq_n_u8(b'0'));
+        let letter = vsubq_u8(vorrq_u8(c, vdupq_n_u8(0x20)), vdupq_n_u8(b'a'));
+        let is_digit = vcltq_u8(digit, vdupq_n_u8(10));
+        let is_letter = vcltq_u8(letter, vdupq_n_u8(6));
+        let letter_value = vaddq_u8(letter, vdupq_n_u8(10));
+        let value = vorrq_u8(vandq_u8(digit, is_digit), vandq_u8(letter_value, is_letter));
+        (value, vorrq_u8(is_digit, is_letter))
+    }
+}
+
+/// Scalar fallback for targets without a baseline SIMD unit.
+#[cfg(not(any(
+    all(target_arch = "x86_64", target_feature = "sse2"),
+    all(target_arch = "aarch64", target_feature = "neon")
+)))]
+mod arch {
+    use super::hex_char;
+
+    /// Internal: the 32 ASCII digits of 16 bytes.
+    #[inline]
+    pub(super) fn encode(bytes: [u8; 16], gap: u8) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        for (pair, byte) in out.chunks_exact_mut(2).zip(bytes) {
+            pair[0] = hex_char(byte >> 4, gap);
+            pair[1] = hex_char(byte & 0xF, gap);
+        }
+        out
+    }
+
+    /// Internal: 32 hex digits to 16 bytes, or `None` on any non-hex byte.
+    #[cfg(feature = "parse")]
+    #[inline]
+    pub(super) fn decode(digits: &[u8; 32]) -> Option<[u8; 16]> {
+        super::const_decode_simple(digits).map(u128::to_be_bytes)
+    }
+}
@@ -0,0 +1,226 @@
+//! Memory-mapped sorted index files.
+//!
+//! Enabled with the `mmap-index` feature. Because MicroShard IDs are fixed
+//! width and sort numerically as big-endian bytes, an index is simply the
+//! sorted records followed by a small footer. Membership checks and range
+//! scans binary-search the mapped bytes directly; nothing is loaded into memory.
+//!
+//! **File Layout:**
+//! - **Records:** `count` × 16-byte big-endian IDs, strictly ascending.
+//! - **Footer (16 bytes):** `count` (u64 LE), format version (u32 LE), magic `"MSIX"`.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::ops::{Bound, RangeBounds};
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use crate::MicroShardUUID;
+
+/// Magic suffix identifying an index file.
+const MAGIC: &[u8; 4] = b"MSIX";
+/// Current on-disk format version.
+const FORMAT_VERSION: u32 = 1;
+/// Size of one record in bytes.
+const RECORD_LEN: usize = 16;
+/// Size of the trailing footer in bytes.
+const FOOTER_LEN: usize = 16;
+
+// ==========================================
+// Writer
+// ==========================================
+
+/// Streams sorted IDs into a new index file.
+///
+/// IDs must be pushed in strictly ascending order; duplicates and
+/// out-of-order IDs are rejected with `ErrorKind::InvalidInput`.
+#[derive(Debug)]
+pub struct IndexWriter {
+    out: BufWriter<File>,
+    count: u64,
+    last: Option<MicroShardUUID>,
+}
+
+impl IndexWriter {
+    /// Creates (or truncates) the index file at `path`.
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        Ok(Self {
+            out: BufWriter::new(file),
+            count: 0,
+            last: None,
+        })
+    }
+
+    /// Appends the next ID.
+    pub fn push(&mut self, id: MicroShardUUID) -> io::Result<()> {
+        if self.last.is_some_and(|last| id <= last) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "index IDs must be strictly ascending",
+            ));
+        }
+        self.out.write_all(&id.as_bytes())?;
+        self.last = Some(id);
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Writes the footer, syncs the file to disk, and returns the record count.
+    pub fn finish(mut self) -> io::Result<u64> {
+        self.out.write_all(&self.count.to_le_bytes())?;
+        self.out.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        self.out.write_all(MAGIC)?;
+        let file = self.out.into_inner().map_err(|e| e.into_error())?;
+        file.sync_all()?;
+        Ok(self.count)
+    }
+
+    /// Sorts, de-duplicates, and writes `ids` to `path` in one call.
+    pub fn write_all<P: AsRef<Path>>(path: P, ids: &mut Vec<MicroShardUUID>) -> io::Result<u64> {
+        ids.sort_unstable();
+        ids.dedup();
+        let mut writer = Self::create(path)?;
+        for id in ids.iter() {
+            writer.push(*id)?;
+        }
+        writer.finish()
+    }
+}
+
+// ==========================================
+// Reader
+// ==========================================
+
+/// A read-only, memory-mapped view of an index file.
+#[derive(Debug)]
+pub struct IndexReader {
+    map: Mmap,
+    count: usize,
+}
+
+impl IndexReader {
+    /// Maps and validates the index file at `path`.
+    ///
+    /// The file must not be modified while the reader is alive; doing so is
+    /// undefined behaviour (as with any memory-mapped file).
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: the mapping is read-only and the documented contract is that
+        // index files are immutable once `IndexWriter::finish` returns.
+        let map = unsafe { Mmap::map(&file)? };
+
+        let invalid = |msg| io::Error::new(io::ErrorKind::InvalidData, msg);
+        if map.len() < FOOTER_LEN {
+            return Err(invalid("index file too short"));
+        }
+
+        let footer = &map[map.len() - FOOTER_LEN..];
+        if &footer[12..16] != MAGIC {
+            return Err(invalid("missing index magic"));
+        }
+        let version = u32::from_le_bytes(footer[8..12].try_into().unwrap());
+        if version != FORMAT_VERSION {
+            return Err(invalid("unsupported index version"));
+        }
+        let count = u64::from_le_bytes(footer[0..8].try_into().unwrap());
+        let expected = count
+            .checked_mul(RECORD_LEN as u64)
+            .and_then(|n| n.checked_add(FOOTER_LEN as u64));
+        if expected != Some(map.len() as u64) {
+            return Err(invalid("index record count does not match file size"));
+        }
+
+        Ok(Self {
+            map,
+            count: count as usize,
+        })
+    }
+
+    /// Number of IDs in the index.
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    /// Returns `true` if the index holds no IDs.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns the ID at position `i` (ascending order).
+    pub fn get(&self, i: usize) -> Option<MicroShardUUID> {
+        (i < self.count).then(|| self.decode(i))
+    }
+
+    /// Returns `true` if `id` is present (binary search, O(log n)).
+    pub fn contains(&self, id: &MicroShardUUID) -> bool {
+        let key = id.as_bytes();
+        let i = self.lower_bound(&key);
+        i < self.count && self.record(i) == key
+    }
+
+    /// Iterates the IDs within `range` in ascending order.
+    pub fn range<R: RangeBounds<MicroShardUUID>>(
+        &self,
+        range: R,
+    ) -> impl Iterator<Item = MicroShardUUID> + '_ {
+        let start = match range.start_bound() {
+            Bound::Included(id) => self.lower_bound(&id.as_bytes()),
+            Bound::Excluded(id) => self.upper_bound(&id.as_bytes()),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(id) => self.upper_bound(&id.as_bytes()),
+            Bound::Excluded(id) => self.lower_bound(&id.as_bytes()),
+            Bound::Unbounded => self.count,
+        };
+        (start..end.max(start)).map(move |i| self.decode(i))
+    }
+
+    /// Iterates all IDs in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = MicroShardUUID> + '_ {
+        (0..self.count).map(move |i| self.decode(i))
+    }
+
+    /// Internal: raw bytes of record `i`.
+    #[inline(always)]
+    fn record(&self, i: usize) -> [u8; 16] {
+        let off = i * RECORD_LEN;
+        self.map[off..off + RECORD_LEN].try_into().unwrap()
+    }
+
+    /// Internal: records were validated by the writer, so skip re-validation.
+    #[inline(always)]
+    fn decode(&self, i: usize) -> MicroShardUUID {
+        MicroShardUUID(u128::from_be_bytes(self.record(i)))
+    }
+
+    /// Internal: first index whose record is `>= key`.
+    fn lower_bound(&self, key: &[u8; 16]) -> usize {
+        partition(self.count, |i| self.record(i) < *key)
+    }
+
+    /// Internal: first index whose record is `> key`.
+    fn upper_bound(&self, key: &[u8; 16]) -> usize {
+        partition(self.count, |i| self.record(i) <= *key)
+    }
+}
+
+/// Internal: binary search for the first index where `pred` is false.
+fn partition(len: usize, pred: impl Fn(usize) -> bool) -> usize {
+    let (mut lo, mut hi) = (0, len);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if pred(mid) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
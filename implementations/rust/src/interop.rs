@@ -0,0 +1,114 @@
+//! Byte-level interoperability with database wire and storage formats.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::{MicroShardError, MicroShardUUID};
+
+// ==========================================
+// PostgreSQL (Extended Protocol, Binary Format)
+// ==========================================
+
+/// Type OID of the PostgreSQL `uuid` type, for `Parse` parameter type lists.
+pub const PG_UUID_OID: u32 = 2950;
+
+impl MicroShardUUID {
+    /// Returns the binary-format (format code 1) parameter value for a
+    /// PostgreSQL `uuid`, exactly as `uuid_send` produces it: the 16 bytes in
+    /// network (big-endian) order.
+    ///
+    /// In a `Bind` message the value is preceded by its Int32 length (`16`);
+    /// see [`MicroShardUUID::write_pg_binary_param`].
+    pub fn to_pg_binary_param(&self) -> [u8; 16] {
+        self.as_bytes()
+    }
+
+    /// Appends the length-prefixed parameter (`Int32 16` + 16 bytes) to a
+    /// `Bind` message buffer.
+    #[cfg(feature = "alloc")]
+    pub fn write_pg_binary_param(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&16i32.to_be_bytes());
+        buf.extend_from_slice(&self.to_pg_binary_param());
+    }
+
+    /// Decodes a binary-format `uuid` column value from a `DataRow`
+    /// (the value bytes, without the length prefix).
+    ///
+    /// Returns [`MicroShardError::InvalidEncoding`] unless exactly 16 bytes are
+    /// given, then validates version and variant.
+    pub fn from_pg_binary(bytes: &[u8]) -> Result<Self, MicroShardError> {
+        let raw: [u8; 16] = bytes
+            .try_into()
+            .map_err(|_| MicroShardError::InvalidEncoding)?;
+        Self::from_bytes(raw)
+    }
+}
+
+// ==========================================
+// MySQL (UUID_TO_BIN / BIN_TO_UUID swap flag)
+// ==========================================
+
+impl MicroShardUUID {
+    /// Returns the bytes as MySQL's `UUID_TO_BIN(uuid, 1)` stores them.
+    ///
+    /// **Permutation** (canonical byte indices → stored order):
+    /// `[6, 7, 4, 5, 0, 1, 2, 3, 8, 9, 10, 11, 12, 13, 14, 15]`
+    /// i.e. the `time_hi` and `time_low` groups of the text form are swapped.
+    ///
+    /// The swap flag exists to front-load the timestamp of **UUIDv1**. MicroShard
+    /// IDs already carry their timestamp in the leading bytes, so the plain
+    /// [`MicroShardUUID::as_bytes`] (`UUID_TO_BIN(uuid)` / swap flag 0) is the
+    /// append-friendly choice for new `BINARY(16)` columns. Use this only to
+    /// interoperate with existing columns written with the swap flag set.
+    pub fn to_mysql_ordered_bytes(&self) -> [u8; 16] {
+        let b = self.as_bytes();
+        [
+            b[6], b[7], b[4], b[5], b[0], b[1], b[2], b[3], b[8], b[9], b[10], b[11], b[12], b[13],
+            b[14], b[15],
+        ]
+    }
+
+    /// Inverse of [`MicroShardUUID::to_mysql_ordered_bytes`]
+    /// (`BIN_TO_UUID(bin, 1)`), with version/variant validation.
+    pub fn from_mysql_ordered_bytes(bytes: [u8; 16]) -> Result<Self, MicroShardError> {
+        let b = bytes;
+        Self::from_bytes([
+            b[4], b[5], b[6], b[7], b[2], b[3], b[0], b[1], b[8], b[9], b[10], b[11], b[12], b[13],
+            b[14], b[15],
+        ])
+    }
+}
+
+// ==========================================
+// ClickHouse (UUID internal / RowBinary layout)
+// ==========================================
+
+impl MicroShardUUID {
+    /// Returns the bytes as ClickHouse stores a `UUID`: two `UInt64` halves,
+    /// each little-endian, high half first. This is the `RowBinary` /
+    /// `Native` wire format and the value `reinterpretAsUInt128(id)` reads.
+    ///
+    /// Read as one little-endian `UInt128`, the **low** half (shard and
+    /// random bits) ends up most significant, which is why a plain
+    /// `ORDER BY id` or `id BETWEEN ...` in ClickHouse is not time-ordered.
+    /// Sort and filter on the swapped value instead; see
+    /// `sql::clickhouse_sort_key_expr` and `sql::clickhouse_between`.
+    pub fn to_clickhouse_bytes(&self) -> [u8; 16] {
+        let mut out = [0u8; 16];
+        out[..8].copy_from_slice(&((self.as_u128() >> 64) as u64).to_le_bytes());
+        out[8..].copy_from_slice(&(self.as_u128() as u64).to_le_bytes());
+        out
+    }
+
+    /// Inverse of [`MicroShardUUID::to_clickhouse_bytes`], with
+    /// version/variant validation.
+    pub fn from_clickhouse_bytes(bytes: [u8; 16]) -> Result<Self, MicroShardError> {
+        let mut high = [0u8; 8];
+        let mut low = [0u8; 8];
+        high.copy_from_slice(&bytes[..8]);
+        low.copy_from_slice(&bytes[8..]);
+        Self::from_u128(
+            ((u64::from_le_bytes(high) as u128) << 64) | u64::from_le_bytes(low) as u128,
+        )
+    }
+}
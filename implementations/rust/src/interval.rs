@@ -0,0 +1,127 @@
+//! Time-interval index mapping named windows to ID ranges.
+//!
+//! Typical use: resolving retention tiers ("hot", "warm", "cold") from an ID
+//! in O(log n). Windows are half-open `[start, end)` microsecond intervals
+//! and must not overlap.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{MicroShardError, MicroShardUUID, MAX_RANDOM, MAX_TIME_MICROS};
+
+/// A named, half-open time window `[start_micros, end_micros)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Window {
+    pub name: String,
+    pub start_micros: u64,
+    pub end_micros: u64,
+}
+
+impl Window {
+    /// Creates a window. Validation happens in [`IntervalIndex::new`].
+    pub fn new(name: impl Into<String>, start_micros: u64, end_micros: u64) -> Self {
+        Self {
+            name: name.into(),
+            start_micros,
+            end_micros,
+        }
+    }
+
+    /// Returns `true` if `micros` falls inside the window.
+    pub fn contains_micros(&self, micros: u64) -> bool {
+        micros >= self.start_micros && micros < self.end_micros
+    }
+
+    /// Returns `true` if the ID's timestamp falls inside the window.
+    pub fn contains(&self, id: &MicroShardUUID) -> bool {
+        self.contains_micros(id.timestamp_micros())
+    }
+
+    /// Returns the smallest and largest IDs inside the window (inclusive),
+    /// for use as range predicates against ID-keyed stores.
+    ///
+    /// Returns `None` if the window is empty or ends past the 54-bit time
+    /// range, the windows [`IntervalIndex::new`] rejects.
+    pub fn id_range(&self) -> Option<(MicroShardUUID, MicroShardUUID)> {
+        self.is_valid().then(|| {
+            (
+                MicroShardUUID::compose(self.start_micros, 0, 0),
+                MicroShardUUID::compose(self.end_micros - 1, u32::MAX, MAX_RANDOM),
+            )
+        })
+    }
+
+    /// Internal: non-empty and within the 54-bit time range.
+    fn is_valid(&self) -> bool {
+        self.start_micros < self.end_micros && self.end_micros - 1 <= MAX_TIME_MICROS
+    }
+}
+
+/// A sorted set of non-overlapping [`Window`]s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalIndex {
+    windows: Vec<Window>,
+}
+
+impl IntervalIndex {
+    /// Builds the index, sorting windows by start time.
+    ///
+    /// Returns [`MicroShardError::InvalidRange`] if a window is empty,
+    /// exceeds the 54-bit time range, or overlaps another window.
+    pub fn new(mut windows: Vec<Window>) -> Result<Self, MicroShardError> {
+        windows.sort_by_key(|w| w.start_micros);
+
+        if !windows.iter().all(Window::is_valid) {
+            return Err(MicroShardError::InvalidRange);
+        }
+        if windows
+            .windows(2)
+            .any(|pair| pair[1].start_micros < pair[0].end_micros)
+        {
+            return Err(MicroShardError::InvalidRange);
+        }
+
+        Ok(Self { windows })
+    }
+
+    /// Returns the window containing the ID's timestamp.
+    pub fn lookup(&self, id: &MicroShardUUID) -> Option<&Window> {
+        self.lookup_micros(id.timestamp_micros())
+    }
+
+    /// Returns the window containing `micros`.
+    pub fn lookup_micros(&self, micros: u64) -> Option<&Window> {
+        // Last window starting at or before `micros`
+        let idx = self.windows.partition_point(|w| w.start_micros <= micros);
+        let w = self.windows.get(idx.checked_sub(1)?)?;
+        w.contains_micros(micros).then_some(w)
+    }
+
+    /// Returns all windows overlapping `[start_micros, end_micros)`.
+    pub fn overlapping(&self, start_micros: u64, end_micros: u64) -> &[Window] {
+        if end_micros <= start_micros {
+            return &[];
+        }
+        // Windows are disjoint and sorted, so end times are sorted too
+        let lo = self
+            .windows
+            .partition_point(|w| w.end_micros <= start_micros);
+        let hi = self
+            .windows
+            .partition_point(|w| w.start_micros < end_micros);
+        &self.windows[lo..hi.max(lo)]
+    }
+
+    /// Returns all windows overlapping the inclusive ID range `[lo, hi]`.
+    pub fn overlapping_ids(&self, lo: &MicroShardUUID, hi: &MicroShardUUID) -> &[Window] {
+        self.overlapping(
+            lo.timestamp_micros(),
+            hi.timestamp_micros().saturating_add(1),
+        )
+    }
+
+    /// Returns the windows in ascending time order.
+    pub fn windows(&self) -> &[Window] {
+        &self.windows
+    }
+}
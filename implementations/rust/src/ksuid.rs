@@ -0,0 +1,204 @@
+//! KSUID interop (Segment's 20-byte, second-precision IDs).
+//!
+//! A KSUID is `[Seconds since 2014-05-13T16:53:20Z (32 bits)] [Payload (128 bits)]`,
+//! written as 27 base62 characters. [`Ksuid`] is a minimal value type for
+//! parsing, printing and converting them, without another dependency.
+//!
+//! **Precision:** a KSUID timestamp has whole-second resolution and covers
+//! 2014-05-13 to 2150-06-19.
+//! - `MicroShardUUID` → `Ksuid` stores the whole ID as the payload, so
+//!   nothing is lost and [`MicroShardUUID::try_from`] restores it exactly.
+//! - Foreign KSUIDs ([`MicroShardUUID::from_ksuid`]) only know the second;
+//!   the sub-second digits are synthesized from the payload (see there).
+
+use core::fmt;
+use core::str::FromStr;
+
+use crate::{MicroShardError, MicroShardUUID, MAX_RANDOM};
+
+/// The KSUID epoch (2014-05-13T16:53:20Z) in Unix seconds.
+pub const KSUID_EPOCH_SECS: u64 = 1_400_000_000;
+
+const KSUID_LEN: usize = 20;
+const BASE62_LEN: usize = 27;
+const ALPHABET: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// A KSUID as its 20 raw bytes (big-endian timestamp, then payload).
+///
+/// Ordering is byte-wise, which matches both the timestamp order and the
+/// order of the base62 strings.
+///
+/// ```
+/// use microshard_uuid::{Ksuid, MicroShardUUID};
+///
+/// let id = MicroShardUUID::from_parts(1_700_000_000_123_456, 42, 7).unwrap();
+/// let ksuid = Ksuid::try_from(id).unwrap();
+/// assert_eq!(ksuid.timestamp_secs(), 1_700_000_000);
+/// assert_eq!(MicroShardUUID::try_from(ksuid), Ok(id));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
+pub struct Ksuid([u8; KSUID_LEN]);
+
+impl Ksuid {
+    /// Wraps the 20 raw bytes. Every byte string is a valid KSUID.
+    pub const fn from_bytes(bytes: [u8; KSUID_LEN]) -> Self {
+        Self(bytes)
+    }
+
+    /// Builds a KSUID from Unix seconds and a 16-byte payload.
+    ///
+    /// # Errors
+    /// - [`MicroShardError::TimeUnderflow`] before the KSUID epoch.
+    /// - [`MicroShardError::TimeOverflow`] after 2150-06-19.
+    pub fn from_parts(unix_secs: u64, payload: [u8; 16]) -> Result<Self, MicroShardError> {
+        let offset = unix_secs
+            .checked_sub(KSUID_EPOCH_SECS)
+            .ok_or(MicroShardError::TimeUnderflow)?;
+        let offset = u32::try_from(offset).map_err(|_| MicroShardError::TimeOverflow)?;
+        let mut bytes = [0u8; KSUID_LEN];
+        bytes[..4].copy_from_slice(&offset.to_be_bytes());
+        bytes[4..].copy_from_slice(&payload);
+        Ok(Self(bytes))
+    }
+
+    /// Parses the 27-character base62 form.
+    ///
+    /// # Errors
+    /// [`MicroShardError::InvalidEncoding`] for a wrong length, a character
+    /// outside `0-9A-Za-z`, or a value above 160 bits.
+    pub fn parse(s: &str) -> Result<Self, MicroShardError> {
+        let bytes = s.as_bytes();
+        if bytes.len() != BASE62_LEN {
+            return Err(MicroShardError::InvalidEncoding);
+        }
+        // Big number as 5 big-endian u32 limbs: n = n * 62 + digit.
+        let mut limbs = [0u32; 5];
+        for &c in bytes {
+            let mut carry = decode_digit(c).ok_or(MicroShardError::InvalidEncoding)? as u64;
+            for limb in limbs.iter_mut().rev() {
+                let acc = *limb as u64 * 62 + carry;
+                *limb = acc as u32;
+                carry = acc >> 32;
+            }
+            if carry != 0 {
+                return Err(MicroShardError::InvalidEncoding);
+            }
+        }
+        let mut out = [0u8; KSUID_LEN];
+        for (chunk, limb) in out.chunks_exact_mut(4).zip(limbs) {
+            chunk.copy_from_slice(&limb.to_be_bytes());
+        }
+        Ok(Self(out))
+    }
+
+    /// Returns the 20 raw bytes.
+    pub fn as_bytes(&self) -> &[u8; KSUID_LEN] {
+        &self.0
+    }
+
+    /// The creation time in Unix seconds.
+    pub fn timestamp_secs(&self) -> u64 {
+        let offset = u32::from_be_bytes([self.0[0], self.0[1], self.0[2], self.0[3]]);
+        KSUID_EPOCH_SECS + offset as u64
+    }
+
+    /// The 16-byte payload.
+    pub fn payload(&self) -> [u8; 16] {
+        let mut payload = [0u8; 16];
+        payload.copy_from_slice(&self.0[4..]);
+        payload
+    }
+}
+
+impl MicroShardUUID {
+    /// Best-effort import of a KSUID from any producer into `shard_id`.
+    ///
+    /// The second is kept. KSUIDs carry no sub-second time, so the
+    /// microseconds within the second are derived from the top 20 payload
+    /// bits and the random field from the next 36: the result is
+    /// deterministic, keeps KSUID order (ties aside), and keeps 56 bits of
+    /// the payload's entropy. Do not read the sub-second part of
+    /// [`timestamp_micros`](Self::timestamp_micros) as real time.
+    ///
+    /// Use `MicroShardUUID::try_from(ksuid)` for KSUIDs made from a
+    /// `MicroShardUUID`; that conversion is exact.
+    pub fn from_ksuid(ksuid: &Ksuid, shard_id: u32) -> Self {
+        let payload = u128::from_be_bytes(ksuid.payload());
+        // Scale the top 20 bits (0..2^20) onto 0..1_000_000, order-preserving.
+        let sub_second = ((payload >> 108) as u64 * 1_000_000) >> 20;
+        let random = (payload >> 72) as u64 & MAX_RANDOM;
+        // KSUID seconds end in 2150, well inside the 54-bit range.
+        let micros = ksuid.timestamp_secs() * 1_000_000 + sub_second;
+        Self::compose(micros, shard_id, random)
+    }
+}
+
+/// Stores the whole ID as the payload (lossless).
+///
+/// # Errors
+/// [`MicroShardError::TimeUnderflow`] / [`MicroShardError::TimeOverflow`]
+/// outside the KSUID range (2014-05-13 to 2150-06-19).
+impl TryFrom<MicroShardUUID> for Ksuid {
+    type Error = MicroShardError;
+
+    fn try_from(id: MicroShardUUID) -> Result<Self, Self::Error> {
+        Self::from_parts(id.timestamp_micros() / 1_000_000, id.as_bytes())
+    }
+}
+
+/// Recovers an ID stored by `TryFrom<MicroShardUUID>`.
+///
+/// # Errors
+/// [`MicroShardError::InvalidEncoding`] unless the payload is a MicroShard
+/// ID from the KSUID's own second; use [`MicroShardUUID::from_ksuid`] for
+/// KSUIDs from other producers.
+impl TryFrom<Ksuid> for MicroShardUUID {
+    type Error = MicroShardError;
+
+    fn try_from(ksuid: Ksuid) -> Result<Self, Self::Error> {
+        match MicroShardUUID::from_bytes(ksuid.payload()) {
+            Ok(id) if id.timestamp_micros() / 1_000_000 == ksuid.timestamp_secs() => Ok(id),
+            _ => Err(MicroShardError::InvalidEncoding),
+        }
+    }
+}
+
+// Canonical 27-character base62 form
+impl fmt::Display for Ksuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut limbs = [0u32; 5];
+        for (limb, chunk) in limbs.iter_mut().zip(self.0.chunks_exact(4)) {
+            *limb = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        // Repeated long division by 62, least significant digit first.
+        let mut out = [b'0'; BASE62_LEN];
+        for slot in out.iter_mut().rev() {
+            let mut rem = 0u64;
+            for limb in limbs.iter_mut() {
+                let acc = (rem << 32) | *limb as u64;
+                *limb = (acc / 62) as u32;
+                rem = acc % 62;
+            }
+            *slot = ALPHABET[rem as usize];
+        }
+        f.write_str(core::str::from_utf8(&out).expect("base62 digits are ASCII"))
+    }
+}
+
+impl FromStr for Ksuid {
+    type Err = MicroShardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Internal: base62 digit value.
+fn decode_digit(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'A'..=b'Z' => Some(c - b'A' + 10),
+        b'a'..=b'z' => Some(c - b'a' + 36),
+        _ => None,
+    }
+}
@@ -0,0 +1,192 @@
+//! Runtime bit layouts, for tools that learn the split from configuration.
+//!
+//! A [`Layout`] describes how the 122 payload bits of a UUIDv8 are divided
+//! between time (µs since the Unix Epoch), shard and random, in that order,
+//! around the fixed version and variant bits. [`encode`] and [`decode`]
+//! work on any such layout; [`ShardedUuid`](crate::ShardedUuid) is the
+//! compile-time equivalent and produces identical bits.
+//!
+//! ```
+//! use microshard_uuid::layout::{self, Layout};
+//! use microshard_uuid::MicroShardUUID;
+//!
+//! let small = Layout::new(54, 8, 60).unwrap(); // e.g. parsed from config
+//! let v = layout::encode(small, 1_700_000_000_000_000, 200, 7).unwrap();
+//! let fields = layout::decode(small, v).unwrap();
+//! assert_eq!((fields.micros, fields.shard, fields.random), (1_700_000_000_000_000, 200, 7));
+//!
+//! // The default layout decodes any MicroShardUUID
+//! let id = MicroShardUUID::from_parts(1_700_000_000_000_000, 42, 7).unwrap();
+//! assert_eq!(layout::decode(Layout::MICROSHARD, id.as_u128()).unwrap().shard, 42);
+//! ```
+
+use crate::MicroShardError;
+
+/// Bits available for time, shard and random (128 minus version and variant).
+const PAYLOAD_BITS: u32 = 122;
+
+/// How the 122 payload bits are split between time, shard and random.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Layout {
+    time_bits: u32,
+    shard_bits: u32,
+    random_bits: u32,
+}
+
+/// The fields of an ID decoded with a [`Layout`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Fields {
+    /// Microseconds since the Unix Epoch.
+    pub micros: u64,
+    /// Shard id.
+    pub shard: u64,
+    /// Random field.
+    pub random: u64,
+}
+
+impl Layout {
+    /// The standard MicroShard layout (54/32/36).
+    pub const MICROSHARD: Layout = Layout {
+        time_bits: 54,
+        shard_bits: 32,
+        random_bits: 36,
+    };
+
+    /// Validates a split.
+    ///
+    /// # Errors
+    /// [`MicroShardError::InvalidRange`] unless the widths sum to 122, time
+    /// has 1 to 64 bits, and shard and random have at most 64 each.
+    pub const fn new(
+        time_bits: u32,
+        shard_bits: u32,
+        random_bits: u32,
+    ) -> Result<Self, MicroShardError> {
+        let fits = time_bits >= 1 && time_bits <= 64 && shard_bits <= 64 && random_bits <= 64;
+        if !fits || time_bits + shard_bits + random_bits != PAYLOAD_BITS {
+            return Err(MicroShardError::InvalidRange);
+        }
+        Ok(Self {
+            time_bits,
+            shard_bits,
+            random_bits,
+        })
+    }
+
+    /// Width of the time field.
+    pub const fn time_bits(&self) -> u32 {
+        self.time_bits
+    }
+
+    /// Width of the shard field.
+    pub const fn shard_bits(&self) -> u32 {
+        self.shard_bits
+    }
+
+    /// Width of the random field.
+    pub const fn random_bits(&self) -> u32 {
+        self.random_bits
+    }
+
+    /// Largest timestamp (µs since the Unix Epoch).
+    pub const fn max_time(&self) -> u64 {
+        mask(self.time_bits)
+    }
+
+    /// Largest shard id.
+    pub const fn max_shard(&self) -> u64 {
+        mask(self.shard_bits)
+    }
+
+    /// Largest random value.
+    pub const fn max_random(&self) -> u64 {
+        mask(self.random_bits)
+    }
+}
+
+/// Builds the raw `u128` of a v8 ID with `layout`.
+///
+/// # Errors
+/// - [`MicroShardError::TimeOverflow`] if `micros` exceeds the time field.
+/// - [`MicroShardError::InvalidShardId`] if `shard` exceeds the shard field
+///   (carrying the maximum, saturated to `u32`).
+/// - [`MicroShardError::InvalidRandom`] if `random` exceeds the random field.
+pub const fn encode(
+    layout: Layout,
+    micros: u64,
+    shard: u64,
+    random: u64,
+) -> Result<u128, MicroShardError> {
+    if micros > layout.max_time() {
+        return Err(MicroShardError::TimeOverflow);
+    }
+    if shard > layout.max_shard() {
+        let max = if layout.max_shard() > u32::MAX as u64 {
+            u32::MAX
+        } else {
+            layout.max_shard() as u32
+        };
+        return Err(MicroShardError::InvalidShardId(max));
+    }
+    if random > layout.max_random() {
+        return Err(MicroShardError::InvalidRandom(random));
+    }
+    let payload = ((micros as u128) << (layout.shard_bits + layout.random_bits))
+        | ((shard as u128) << layout.random_bits)
+        | random as u128;
+    Ok(pack_payload(payload))
+}
+
+/// Splits a raw `u128` into its fields according to `layout`.
+///
+/// # Errors
+/// [`MicroShardError::InvalidVersion`] / [`MicroShardError::InvalidVariant`]
+/// unless `v` is a version 8, variant 2 UUID.
+pub const fn decode(layout: Layout, v: u128) -> Result<Fields, MicroShardError> {
+    let version = ((v >> 76) & 0xF) as u8;
+    if version != 8 {
+        return Err(MicroShardError::InvalidVersion(version));
+    }
+    let variant = ((v >> 62) & 0x3) as u8;
+    if variant != 2 {
+        return Err(MicroShardError::InvalidVariant(variant));
+    }
+    Ok(decode_fields(layout, v))
+}
+
+/// Internal: [`decode`] without the version and variant checks.
+pub(crate) const fn decode_fields(layout: Layout, v: u128) -> Fields {
+    let payload = unpack_payload(v);
+    Fields {
+        micros: (payload >> (layout.shard_bits + layout.random_bits)) as u64 & layout.max_time(),
+        shard: (payload >> layout.random_bits) as u64 & layout.max_shard(),
+        random: payload as u64 & layout.max_random(),
+    }
+}
+
+// ==========================================
+// Internal: Payload Placement
+// ==========================================
+
+/// Internal: all-ones in the low `bits` bits (`bits <= 64`).
+const fn mask(bits: u32) -> u64 {
+    if bits >= 64 {
+        u64::MAX
+    } else {
+        (1 << bits) - 1
+    }
+}
+
+/// Internal: spreads a 122-bit payload around the version and variant bits
+/// (top 48 bits, `Ver=8`, next 12, `Var=2`, last 62).
+const fn pack_payload(payload: u128) -> u128 {
+    let top = (payload >> 74) & ((1 << 48) - 1);
+    let mid = (payload >> 62) & 0xFFF;
+    let low = payload & ((1 << 62) - 1);
+    (top << 80) | (8 << 76) | (mid << 64) | (2 << 62) | low
+}
+
+/// Internal: inverse of [`pack_payload`].
+const fn unpack_payload(v: u128) -> u128 {
+    ((v >> 80) << 74) | (((v >> 64) & 0xFFF) << 62) | (v & ((1 << 62) - 1))
+}
@@ -1,14 +1,154 @@
-use std::cell::RefCell;
-use std::fmt;
-use std::time::{SystemTime, UNIX_EPOCH};
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+use core::fmt;
+use core::time::Duration;
+
+pub mod affinity;
+#[cfg(feature = "shard-allocator")]
+pub mod allocator;
+pub mod any;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+mod base32;
+mod base64;
+#[cfg(feature = "std")]
+pub mod bloom;
+#[cfg(feature = "bson")]
+pub mod bson;
+mod bucket;
+#[cfg(feature = "gen")]
+mod builder;
+mod calendar;
+#[cfg(feature = "chrono")]
+mod chrono_impl;
+pub mod conformance;
+#[cfg(feature = "alloc")]
+mod cursor;
+#[cfg(feature = "datafusion")]
+pub mod datafusion;
+#[cfg(any(
+    feature = "diesel-postgres",
+    feature = "diesel-mysql",
+    feature = "diesel-sqlite"
+))]
+mod diesel_impl;
+pub mod envelope;
+mod fixed_str;
+#[cfg(feature = "fmt-iso")]
+mod fmt_iso;
+#[cfg(feature = "gen")]
+mod gen;
+#[cfg(feature = "gen")]
+mod generator;
+#[cfg(feature = "heapless")]
+mod heapless_fmt;
+mod hex;
+#[cfg(feature = "mmap-index")]
+pub mod index;
+#[cfg(feature = "interop")]
+pub mod interop;
+#[cfg(feature = "alloc")]
+pub mod interval;
+mod ksuid;
+pub mod layout;
+#[cfg(feature = "local-time")]
+pub mod local_time;
+#[cfg(feature = "alloc")]
+pub mod merge;
+mod nanos;
+mod object_id;
+#[cfg(feature = "parse")]
+mod parse;
+#[cfg(feature = "gen")]
+mod persistent;
+#[cfg(feature = "polars")]
+pub mod polars;
+#[cfg(feature = "redis")]
+pub mod redis;
+#[cfg(feature = "alloc")]
+pub mod routing;
+#[cfg(feature = "scylla")]
+mod scylla_impl;
+#[cfg(feature = "sea-orm")]
+mod sea_orm_impl;
+pub mod search;
+#[cfg(feature = "serde")]
+mod serde_impl;
+#[cfg(feature = "alloc")]
+mod sha256;
+#[cfg(feature = "std")]
+pub mod shard;
+mod sharded;
+pub mod snowflake;
+#[cfg(feature = "alloc")]
+pub mod sql;
+#[cfg(feature = "rusqlite")]
+pub mod sqlite;
+#[cfg(any(
+    feature = "sqlx-postgres",
+    feature = "sqlx-mysql",
+    feature = "sqlx-sqlite"
+))]
+mod sqlx_impl;
+#[cfg(feature = "gen")]
+pub mod stress;
+#[cfg(feature = "gen")]
+pub mod test_support;
+pub mod time_source;
+mod timeuuid;
+pub mod tsid;
+mod ulid;
+#[cfg(feature = "gen")]
+mod unique;
+mod v7;
+#[cfg(feature = "alloc")]
+pub mod validate;
+
+#[cfg(feature = "gen")]
+pub use builder::MicroShardBuilder;
+#[cfg(feature = "gen")]
+pub use generator::{ClockRegressionPolicy, MicroShardGenerator};
+#[cfg(feature = "gen")]
+pub use persistent::{PersistentGenerator, DEFAULT_RESERVE_AHEAD};
+#[cfg(feature = "std")]
+pub use shard::{ShardId, ShardIdError};
+#[cfg(feature = "gen")]
+pub use unique::{GenerateHook, UniqueGenerator, DEFAULT_MAX_REGRESSION};
+
+pub use bucket::{Bucket, BucketLabel, TimeUnit};
+#[cfg(feature = "local-time")]
+use calendar::{date_to_days, is_leap, unix_to_civil};
+#[cfg(feature = "alloc")]
+pub use cursor::{Cursor, Direction};
+pub use fixed_str::FixedStr;
+#[cfg(feature = "fmt-iso")]
+pub use fmt_iso::IsoDisplay;
+#[cfg(feature = "gen")]
+use gen::{check_future_skew, now_micros, now_nanos, Xoshiro256StarStar, XoshiroState};
+pub use ksuid::{Ksuid, KSUID_EPOCH_SECS};
+pub use nanos::MicroShardUUIDNanos;
+#[cfg(feature = "parse")]
+pub use parse::{iso_to_micros, iso_to_micros_lenient};
+#[cfg(feature = "parse")]
+use parse::{parse_hyphenated, parse_iso_strict};
+pub use sharded::ShardedUuid;
+pub use ulid::Ulid;
+pub use v7::MicroShardV7;
 
 // ==========================================
 // Constants & Configuration
 // ==========================================
 
+#[cfg(feature = "std")]
 const MAX_SHARD_ID: u32 = 4_294_967_295; // 2^32 - 1
 const MAX_TIME_MICROS: u64 = 18_014_398_509_481_983; // 2^54 - 1
 const MAX_RANDOM: u64 = 68_719_476_735; // 2^36 - 1
+const REVISION_SHIFT: u32 = 34; // Top 2 of the 36 random bits
+const MAX_REVISION: u8 = 3;
+const MAX_CLOCK_SEQUENCE: u64 = 0xF; // Low 4 of the 36 random bits
 
 // ==========================================
 // Error Handling
@@ -24,6 +164,16 @@ pub enum MicroShardError {
     SystemTimeError,
     InvalidVersion(u8),
     InvalidVariant(u8),
+    ClockMovedBackwards(u64),
+    InvalidTimeZone,
+    TimeUnderflow,
+    InvalidEncoding,
+    InvalidRange,
+    InvalidUuidFormat,
+    InvalidRandom(u64),
+    TimestampInFuture(u64),
+    InvalidUtcOffset,
+    InvalidSignature,
 }
 
 impl fmt::Display for MicroShardError {
@@ -35,10 +185,29 @@ impl fmt::Display for MicroShardError {
             Self::SystemTimeError => write!(f, "System time went backwards"),
             Self::InvalidVersion(v) => write!(f, "Invalid UUID Version: {}, expected 8", v),
             Self::InvalidVariant(v) => write!(f, "Invalid UUID Variant: {}, expected 2", v),
+            Self::ClockMovedBackwards(d) => {
+                write!(f, "Clock moved backwards by {} microseconds", d)
+            }
+            Self::InvalidTimeZone => write!(f, "Unknown or malformed timezone"),
+            Self::TimeUnderflow => write!(f, "Time underflow (before 1970-01-01)"),
+            Self::InvalidEncoding => write!(f, "Malformed encoded data"),
+            Self::InvalidRange => write!(f, "Invalid or overlapping range"),
+            Self::InvalidUuidFormat => {
+                write!(f, "Invalid UUID string, expected 8-4-4-4-12 hex digits")
+            }
+            Self::InvalidRandom(r) => {
+                write!(f, "Random field {} does not fit in 36 bits", r)
+            }
+            Self::TimestampInFuture(d) => {
+                write!(f, "Timestamp is {} microseconds ahead of the clock", d)
+            }
+            Self::InvalidUtcOffset => write!(f, "Invalid UTC offset, expected ±HH:MM"),
+            Self::InvalidSignature => write!(f, "Missing or invalid signature"),
         }
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for MicroShardError {}
 
 // ==========================================
@@ -57,7 +226,6 @@ impl std::error::Error for MicroShardError {}
 pub struct MicroShardUUID(u128);
 
 impl MicroShardUUID {
-
     pub fn high(&self) -> u64 {
         (self.0 >> 64) as u64
     }
@@ -69,44 +237,11 @@ impl MicroShardUUID {
     // -------------------------------------------------------------------------
     // Constructors
     // -------------------------------------------------------------------------
-
-    /// Generates a new `MicroShardUUID` using the current system time.
-    ///
-    /// # Arguments
-    /// * `shard_id` - A unique identifier for the machine/process generating the ID (max u32).
-    pub fn generate(shard_id: u32) -> Result<Self, MicroShardError> {
-        validate_shard(shard_id)?;
-
-        let start = SystemTime::now();
-        let since_epoch = start
-            .duration_since(UNIX_EPOCH)
-            .map_err(|_| MicroShardError::SystemTimeError)?;
-
-        let micros = since_epoch.as_micros() as u64;
-
-        Self::build(micros, shard_id)
-    }
-
-    /// Generates a `MicroShardUUID` from a specific timestamp in microseconds.
-    pub fn from_micros(micros: u64, shard_id: u32) -> Result<Self, MicroShardError> {
-        validate_shard(shard_id)?;
-        Self::build(micros, shard_id)
-    }
-
-    /// Generates a `MicroShardUUID` from an ISO 8601 string.
-    ///
-    /// # Format
-    /// Expected format: `YYYY-MM-DDTHH:MM:SS.mmmmmmZ`
-    ///
-    pub fn from_iso(iso_str: &str, shard_id: u32) -> Result<Self, MicroShardError> {
-        validate_shard(shard_id)?;
-        let micros = parse_iso_strict(iso_str)?;
-        Self::build(micros, shard_id)
-    }
+    // (Time-based constructors live in `gen`, string parsing in `parse`.)
 
     /// Constructs a UUID from a raw `u128` value with strict validation.
     /// Checks for RFC 9562 compliance (Version 8, Variant 2).
-    pub fn from_u128(v: u128) -> Result<Self, MicroShardError> {
+    pub const fn from_u128(v: u128) -> Result<Self, MicroShardError> {
         // 1. Check Version (Must be 8)
         // Layout High 64: [TimeHigh 48] [Ver 4] [TimeLow 6] [ShardHigh 6]
         // Version is at bits 12-15 of the High 64-bit word.
@@ -129,6 +264,47 @@ impl MicroShardUUID {
         Ok(Self(v))
     }
 
+    /// Constructs a UUID from a raw `u128` **without** checking the version
+    /// and variant bits.
+    ///
+    /// For trusted, high-throughput paths (e.g. reading back a column that
+    /// only ever stored MicroShard IDs). Passing a non-v8 value is a logic
+    /// error, not undefined behaviour: the accessors then decode whatever
+    /// bits are present. Debug builds assert validity.
+    #[inline(always)]
+    pub const fn from_u128_unchecked(v: u128) -> Self {
+        debug_assert!(
+            Self::from_u128(v).is_ok(),
+            "from_u128_unchecked: not a v8/variant-2 UUID"
+        );
+        Self(v)
+    }
+
+    /// Unchecked counterpart of [`from_bytes`](Self::from_bytes); see
+    /// [`from_u128_unchecked`](Self::from_u128_unchecked).
+    #[inline(always)]
+    pub const fn from_bytes_unchecked(bytes: [u8; 16]) -> Self {
+        Self::from_u128_unchecked(u128::from_be_bytes(bytes))
+    }
+
+    /// Constructs a UUID from explicit field values, with no PRNG involved.
+    ///
+    /// Intended for reproducible tests and cross-language test vectors:
+    /// `from_parts(m, s, r).decompose()` returns exactly `m`, `s` and `r`.
+    ///
+    /// # Errors
+    /// - [`MicroShardError::TimeOverflow`] if `micros` exceeds 2^54 - 1.
+    /// - [`MicroShardError::InvalidRandom`] if `random` exceeds 2^36 - 1.
+    pub fn from_parts(micros: u64, shard_id: u32, random: u64) -> Result<Self, MicroShardError> {
+        if micros > MAX_TIME_MICROS {
+            return Err(MicroShardError::TimeOverflow);
+        }
+        if random > MAX_RANDOM {
+            return Err(MicroShardError::InvalidRandom(random));
+        }
+        Ok(Self::compose(micros, shard_id, random))
+    }
+
     /// Constructs a UUID from a 16-byte array (Big Endian).
     pub fn from_bytes(bytes: [u8; 16]) -> Result<Self, MicroShardError> {
         Self::from_u128(u128::from_be_bytes(bytes))
@@ -151,6 +327,50 @@ impl MicroShardUUID {
         self.0.to_be_bytes()
     }
 
+    /// Returns the uppercase hyphenated form (same as `format!("{:X}", id)`),
+    /// e.g. for Oracle `RAW` text or SAP interfaces that reject lowercase.
+    #[cfg(feature = "alloc")]
+    pub fn to_string_upper(&self) -> alloc::string::String {
+        alloc::format!("{:X}", self)
+    }
+
+    /// Returns the 32-character "simple" form: lowercase hex, no hyphens.
+    /// Parse it back with `parse_simple` (or `parse_lenient`).
+    #[cfg(feature = "alloc")]
+    pub fn to_simple_string(&self) -> alloc::string::String {
+        self.encode_simple(&mut [0; 32]).into()
+    }
+
+    /// Writes the lowercase hyphenated form into `buf` and returns it as a
+    /// `&str`, without allocating. For hot logging paths and `no_std` targets.
+    ///
+    /// ```
+    /// # use microshard_uuid::MicroShardUUID;
+    /// let id = MicroShardUUID::parse("1916dd14-36ed-8000-8003-039123456789").unwrap();
+    /// let mut buf = [0u8; 36];
+    /// assert_eq!(id.encode_hyphenated(&mut buf), "1916dd14-36ed-8000-8003-039123456789");
+    /// ```
+    pub fn encode_hyphenated<'a>(&self, buf: &'a mut [u8; 36]) -> &'a str {
+        *buf = hex::encode_hyphenated(self.0, false);
+        core::str::from_utf8(buf).expect("hex digits are ASCII")
+    }
+
+    /// Like [`encode_hyphenated`](Self::encode_hyphenated), for the 32-character
+    /// simple form (no hyphens).
+    pub fn encode_simple<'a>(&self, buf: &'a mut [u8; 32]) -> &'a str {
+        *buf = hex::encode_simple(self.0, false);
+        core::str::from_utf8(buf).expect("hex digits are ASCII")
+    }
+
+    /// Returns the RFC 9562 URN form: `urn:uuid:xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx`.
+    #[cfg(feature = "alloc")]
+    pub fn to_urn(&self) -> alloc::string::String {
+        let mut out = alloc::string::String::with_capacity(45);
+        out.push_str("urn:uuid:");
+        write_hyphenated(self.0, &mut out).expect("writing to a String cannot fail");
+        out
+    }
+
     /// Extracts the 32-bit Shard ID embedded in the UUID.
     pub fn shard_id(&self) -> u32 {
         let val = self.0; // Direct access to u128
@@ -180,386 +400,309 @@ impl MicroShardUUID {
         (time_high << 6) | time_low
     }
 
-    /// Extracts the creation time and formats it as an ISO 8601 string.
-    /// Format: `YYYY-MM-DDTHH:MM:SS.mmmmmmZ`
-    pub fn to_iso_string(&self) -> String {
-        let total_micros = self.timestamp_micros();
+    /// Extracts the creation time of an ID minted with a custom epoch
+    /// (`MicroShardGenerator::with_epoch`): the stored offset plus
+    /// `epoch_micros`, in microseconds since the Unix Epoch.
+    ///
+    /// With `epoch_micros == 0` this equals [`timestamp_micros`](Self::timestamp_micros).
+    pub fn timestamp_micros_with_epoch(&self, epoch_micros: u64) -> Result<u64, MicroShardError> {
+        self.timestamp_micros()
+            .checked_add(epoch_micros)
+            .ok_or(MicroShardError::TimeOverflow)
+    }
+
+    /// The creation time as a `Duration` since the Unix Epoch (µs precision).
+    ///
+    /// ```
+    /// use microshard_uuid::MicroShardUUID;
+    /// use std::time::Duration;
+    ///
+    /// let id = MicroShardUUID::from_parts(1_700_000_000_123_456, 1, 0).unwrap();
+    /// assert_eq!(id.since_epoch(), Duration::new(1_700_000_000, 123_456_000));
+    /// ```
+    pub fn since_epoch(&self) -> Duration {
+        Duration::from_micros(self.timestamp_micros())
+    }
+
+    /// The creation time as a `SystemTime` (µs precision).
+    ///
+    /// ```
+    /// use microshard_uuid::MicroShardUUID;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// let id = MicroShardUUID::from_parts(1_700_000_000_123_456, 1, 0).unwrap();
+    /// assert_eq!(id.timestamp(), UNIX_EPOCH + Duration::from_micros(1_700_000_000_123_456));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn timestamp(&self) -> std::time::SystemTime {
+        std::time::UNIX_EPOCH + self.since_epoch()
+    }
+
+    /// Extracts the 36-bit random field (the entropy portion of the ID).
+    ///
+    /// For IDs from a monotonic generator the top 12 bits are the
+    /// per-microsecond sequence; for revision-tagged IDs the top 2 bits are
+    /// the [`layout_revision`](Self::layout_revision); with the clock-sequence
+    /// policy the low 4 bits are the [`clock_sequence`](Self::clock_sequence).
+    pub fn random(&self) -> u64 {
+        self.low() & MAX_RANDOM
+    }
 
-        let seconds = total_micros / 1_000_000;
-        let micros = total_micros % 1_000_000;
+    /// Returns the 2-bit layout revision tag (top 2 bits of the random field).
+    ///
+    /// Only meaningful for IDs minted by a generator configured with
+    /// [`UniqueGenerator::with_layout_revision`]; in untagged IDs these bits
+    /// are random. Producers and consumers agree out of band on *whether*
+    /// tagging is in use, and the tag then says *which* layout wrote the ID.
+    pub fn layout_revision(&self) -> u8 {
+        ((self.low() >> REVISION_SHIFT) & MAX_REVISION as u64) as u8
+    }
 
-        let (year, month, day, hour, min, sec) = unix_to_civil(seconds);
+    /// Returns the 4-bit clock sequence (low 4 bits of the random field).
+    ///
+    /// Only meaningful for IDs minted by a generator using the
+    /// `ClockRegressionPolicy::ClockSequence` policy, which bumps it each time
+    /// the clock steps backwards; in other IDs these bits are random.
+    pub fn clock_sequence(&self) -> u8 {
+        (self.low() & MAX_CLOCK_SEQUENCE) as u8
+    }
 
-        format!(
-            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:06}Z",
-            year, month, day, hour, min, sec, micros
-        )
+    /// Decodes every field at once, for debugging and auditing.
+    pub fn decompose(&self) -> DecodedMicroShard {
+        DecodedMicroShard {
+            timestamp_micros: self.timestamp_micros(),
+            shard_id: self.shard_id(),
+            random: self.random(),
+            version: ((self.high() >> 12) & 0xF) as u8,
+            variant: (self.low() >> 62) as u8,
+        }
     }
 
     // -------------------------------------------------------------------------
-    // Internal Construction Helper
+    // Range Boundaries
     // -------------------------------------------------------------------------
 
-    /// Internal builder that composes the bits.
-    fn build(micros: u64, shard_id: u32) -> Result<Self, MicroShardError> {
+    /// The smallest valid ID stamped `micros` (shard 0, random bits zeroed).
+    ///
+    /// Together with [`max_for_micros`](Self::max_for_micros) this gives an
+    /// inclusive range predicate over an ID column: every ID created in
+    /// `[t1, t2]` satisfies `min_for_micros(t1) <= id <= max_for_micros(t2)`.
+    pub fn min_for_micros(micros: u64) -> Result<Self, MicroShardError> {
         if micros > MAX_TIME_MICROS {
             return Err(MicroShardError::TimeOverflow);
         }
+        Ok(Self::compose(micros, 0, 0))
+    }
 
-        // Get 36 bits of randomness from Thread-Local Xoshiro256**
-        let rnd_val = Xoshiro256StarStar::next_36();
-
-        let shard_id_64 = shard_id as u64;
+    /// The largest valid ID stamped `micros` (shard and random bits maxed).
+    pub fn max_for_micros(micros: u64) -> Result<Self, MicroShardError> {
+        if micros > MAX_TIME_MICROS {
+            return Err(MicroShardError::TimeOverflow);
+        }
+        Ok(Self::compose(micros, u32::MAX, MAX_RANDOM))
+    }
 
-        // --- High 64 Bits ---
-        let time_high = (micros >> 6) & 0xFFFFFFFFFFFF;
-        let time_low = micros & 0x3F;
-        let shard_high = (shard_id_64 >> 26) & 0x3F;
+    /// The next valid ID in sort order, or `None` for the largest one.
+    ///
+    /// The 122 variable bits form one (time, shard, random) counter, so the
+    /// random field carries into the shard and the shard into the time,
+    /// skipping the fixed version/variant bits. Turns an inclusive keyset
+    /// bound into an exclusive one (`id > last` becomes `id >= last.successor()`).
+    ///
+    /// ```
+    /// use microshard_uuid::MicroShardUUID;
+    ///
+    /// let last = MicroShardUUID::max_for_micros(1_000).unwrap();
+    /// assert_eq!(last.successor(), MicroShardUUID::min_for_micros(1_001).ok());
+    /// ```
+    pub fn successor(&self) -> Option<Self> {
+        let (micros, shard, random) = (self.timestamp_micros(), self.shard_id(), self.random());
+        if random < MAX_RANDOM {
+            Some(Self::compose(micros, shard, random + 1))
+        } else if shard < u32::MAX {
+            Some(Self::compose(micros, shard + 1, 0))
+        } else {
+            (micros < MAX_TIME_MICROS).then(|| Self::compose(micros + 1, 0, 0))
+        }
+    }
 
-        // Version 8 at pos 12
-        let high_64 = (time_high << 16) | (8 << 12) | (time_low << 6) | shard_high;
+    /// The previous valid ID in sort order, or `None` for the smallest one
+    /// (the inverse of [`successor`](Self::successor)).
+    pub fn predecessor(&self) -> Option<Self> {
+        let (micros, shard, random) = (self.timestamp_micros(), self.shard_id(), self.random());
+        if random > 0 {
+            Some(Self::compose(micros, shard, random - 1))
+        } else if shard > 0 {
+            Some(Self::compose(micros, shard - 1, MAX_RANDOM))
+        } else {
+            (micros > 0).then(|| Self::compose(micros - 1, u32::MAX, MAX_RANDOM))
+        }
+    }
 
-        // --- Low 64 Bits ---
-        let shard_low = shard_id_64 & 0x3FFFFFF;
-        // Variant 2 at pos 62
-        let low_64 = (2 << 62) | (shard_low << 36) | rnd_val;
+    // -------------------------------------------------------------------------
+    // Anonymization
+    // -------------------------------------------------------------------------
 
-        // Combine into u128 directly
-        Ok(Self(((high_64 as u128) << 64) | (low_64 as u128)))
+    /// Returns a copy with the 36 random bits zeroed, keeping time and shard.
+    ///
+    /// Removes linkability to specific records while preserving temporal
+    /// structure for analytics exports. Note that IDs minted in the same
+    /// microsecond on the same shard collapse to one value.
+    pub fn anonymize(&self) -> Self {
+        Self(self.0 & !(MAX_RANDOM as u128))
     }
-}
-
-// -----------------------------------------------------------------------------
-// Trait Implementations
-// -----------------------------------------------------------------------------
 
-// Implements standard 8-4-4-4-12 hex string formatting
-impl fmt::Display for MicroShardUUID {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // We convert to bytes for formatting to ensure Big Endian (Network) order
-        // regardless of the host machine's endianness.
-        let b = self.as_bytes();
-        write!(
-            f,
-            "{:02x}{:02x}{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
-            b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7],
-            b[8], b[9], b[10], b[11], b[12], b[13], b[14], b[15]
-        )
+    /// Like [`MicroShardUUID::anonymize`], but also coarsens the shard ID by
+    /// flooring it to a multiple of `bucket_size` (e.g. `1024` groups shards
+    /// 0..=1023 together). A `bucket_size` of 0 or 1 keeps the shard as is.
+    pub fn anonymize_shard(&self, bucket_size: u32) -> Self {
+        let bucket = bucket_size.max(1);
+        let shard = self.shard_id() / bucket * bucket;
+        Self::compose(self.timestamp_micros(), shard, 0)
     }
-}
 
+    /// Returns a copy whose timestamp is floored to `granularity`, reducing
+    /// timing precision for GDPR-style exports without dropping the ID.
+    ///
+    /// The random bits are re-derived deterministically from the original ID,
+    /// so the same input always maps to the same output and distinct inputs in
+    /// the same time bucket stay distinct with high probability. Granularities
+    /// below 2µs return the ID unchanged.
+    pub fn truncate_time(&self, granularity: Duration) -> Self {
+        let step = granularity.as_micros().min(u64::MAX as u128) as u64;
+        if step <= 1 {
+            return *self;
+        }
 
-// ==========================================
-// Internal: PRNG (Xoshiro256**)
-// ==========================================
+        let micros = self.timestamp_micros() / step * step;
 
-/// Internal State for Xoshiro256**
-struct XoshiroState {
-    s: [u64; 4],
-    init: bool,
-}
+        // Chain both halves through SplitMix64 so every original bit
+        // influences the new random field.
+        let mut seed = self.high();
+        let mut mixed = self.low() ^ splitmix64(&mut seed);
+        let random = splitmix64(&mut mixed) & MAX_RANDOM;
 
-impl XoshiroState {
-    const fn new() -> Self {
-        Self {
-            s: [0; 4],
-            init: false,
-        }
+        Self::compose(micros, self.shard_id(), random)
     }
-}
-
-// Thread-Local Storage for the RNG state.
-// This acts like `static MS_TLS` in C.
-thread_local! {
-    static RNG_STATE: RefCell<XoshiroState> = RefCell::new(XoshiroState::new());
-}
 
-struct Xoshiro256StarStar;
+    // -------------------------------------------------------------------------
+    // Internal Construction Helper
+    // -------------------------------------------------------------------------
 
-impl Xoshiro256StarStar {
-    /// Internal: Rotate Left
+    /// Internal: packs already-validated fields into the 54/32/36 layout.
+    /// `micros` must fit in 54 bits and `rnd_val` in 36 bits.
     #[inline(always)]
-    fn rotl(x: u64, k: i32) -> u64 {
-        (x << k) | (x >> (64 - k))
-    }
-
-    /// Internal: SplitMix64 (Used for bootstrapping seed)
-    fn splitmix64(x: &mut u64) -> u64 {
-        *x = x.wrapping_add(0x9e3779b97f4a7c15);
-        let mut z = *x;
-        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
-        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
-        z ^ (z >> 31)
-    }
-
-    /// Internal: Get High-Res Nanoseconds for Seeding
-    fn get_nanos_seed() -> u64 {
-        SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_nanos() as u64
-    }
-
-    /// Public: Get next 36 bits of randomness.
-    /// Handles lazy initialization.
-    fn next_36() -> u64 {
-        RNG_STATE.with(|cell| {
-            let mut ctx = cell.borrow_mut();
-
-            // 1. Auto-Seed if not initialized
-            if !ctx.init {
-                let now = Self::get_nanos_seed();
-
-                // ASLR Entropy: XOR time with the address of the state variable on stack/heap
-                let ptr = &*ctx as *const _ as u64;
-                let mut seed_val = now ^ ptr;
-
-                ctx.s[0] = Self::splitmix64(&mut seed_val);
-                ctx.s[1] = Self::splitmix64(&mut seed_val);
-                ctx.s[2] = Self::splitmix64(&mut seed_val);
-                ctx.s[3] = Self::splitmix64(&mut seed_val);
-                ctx.init = true;
-            }
-
-            // 2. Xoshiro256** Algorithm
-            let result = Self::rotl(ctx.s[1].wrapping_mul(5), 7).wrapping_mul(9);
-            let t = ctx.s[1] << 17;
+    fn compose(micros: u64, shard_id: u32, rnd_val: u64) -> Self {
+        Self::compose_with(micros, ShardBits::new(shard_id), rnd_val)
+    }
 
-            ctx.s[2] ^= ctx.s[0];
-            ctx.s[3] ^= ctx.s[1];
-            ctx.s[1] ^= ctx.s[2];
-            ctx.s[0] ^= ctx.s[3];
+    /// Internal: `compose` with the shard (plus version/variant) bits already
+    /// positioned, so hot loops only place the time and random fields.
+    #[inline(always)]
+    fn compose_with(micros: u64, shard: ShardBits, rnd_val: u64) -> Self {
+        // --- High 64 Bits ---
+        let time_high = (micros >> 6) & 0xFFFFFFFFFFFF;
+        let time_low = micros & 0x3F;
+        let high_64 = (time_high << 16) | (time_low << 6) | shard.high;
 
-            ctx.s[2] ^= t;
-            ctx.s[3] = Self::rotl(ctx.s[3], 45);
+        // --- Low 64 Bits ---
+        let low_64 = shard.low | rnd_val;
 
-            // 3. Return truncated to 36 bits
-            result & MAX_RANDOM
-        })
+        // Combine into u128 directly
+        Self(((high_64 as u128) << 64) | (low_64 as u128))
     }
 }
 
-// ==========================================
-// Internal: Helpers & Utilities
-// ==========================================
-
-#[inline(always)]
-fn validate_shard(shard_id: u32) -> Result<(), MicroShardError> {
-    if shard_id > MAX_SHARD_ID {
-        return Err(MicroShardError::InvalidShardId(MAX_SHARD_ID));
-    }
-    Ok(())
+/// Internal: the constant, pre-shifted part of each 64-bit half for one shard.
+#[derive(Debug, Clone, Copy)]
+struct ShardBits {
+    /// Version 8 at pos 12 and the top 6 shard bits.
+    high: u64,
+    /// Variant 2 at pos 62 and the low 26 shard bits.
+    low: u64,
 }
 
-// ==========================================
-// Internal: Zero-Dependency Date/Time Logic
-// ==========================================
-
-/// Internal helper: Parses ISO string to microseconds.
-/// Contains all the strict validation logic (Zero-Dep).
-fn parse_iso_strict(iso_str: &str) -> Result<u64, MicroShardError> {
-    // 1. Basic Length Check
-    // minimal: "2023-01-01T00:00:00Z" (20 chars)
-    if iso_str.len() < 20 {
-        return Err(MicroShardError::InvalidIsoFormat);
-    }
-
-    // 2. Separator Check (Strict ISO 8601)
-    // Expect: YYYY-MM-DDTHH:MM:SS...
-    let b = iso_str.as_bytes();
-    if b[4] != b'-' || b[7] != b'-' || b[10] != b'T' || b[13] != b':' || b[16] != b':' {
-        return Err(MicroShardError::InvalidIsoFormat);
-    }
-
-    // 3. Parse Numbers
-    let parse_chunk = |s: &str| -> Result<u32, MicroShardError> {
-        s.parse::<u32>().map_err(|_| MicroShardError::InvalidIsoFormat)
-    };
-
-    let year = iso_str[0..4].parse::<i32>().map_err(|_| MicroShardError::InvalidIsoFormat)?;
-    let month = parse_chunk(&iso_str[5..7])?;
-    let day = parse_chunk(&iso_str[8..10])?;
-    let hour = parse_chunk(&iso_str[11..13])?;
-    let min = parse_chunk(&iso_str[14..16])?;
-    let sec = parse_chunk(&iso_str[17..19])?;
-
-    // 4. Logical Range Validation
-    if month < 1 || month > 12 {
-        return Err(MicroShardError::InvalidIsoFormat);
-    }
-    if hour > 23 || min > 59 || sec > 60 {
-        // 60 allowed for leap seconds
-        return Err(MicroShardError::InvalidIsoFormat);
-    }
-
-    // Days in Month Check (Handles Feb 29)
-    let days_in_month = match month {
-        4 | 6 | 9 | 11 => 30,
-        2 => {
-            if is_leap(year) {
-                29
-            } else {
-                28
-            }
+impl ShardBits {
+    #[inline(always)]
+    const fn new(shard_id: u32) -> Self {
+        let shard_id_64 = shard_id as u64;
+        let shard_high = (shard_id_64 >> 26) & 0x3F;
+        let shard_low = shard_id_64 & 0x3FFFFFF;
+        Self {
+            high: (8 << 12) | shard_high,
+            low: (2 << 62) | (shard_low << 36),
         }
-        _ => 31,
-    };
-
-    if day < 1 || day > days_in_month {
-        return Err(MicroShardError::InvalidIsoFormat);
     }
+}
 
-    // 5. Parse Microseconds (Optional)
-    let mut micros = 0;
-    if iso_str.len() > 20 {
-        // Must start with dot
-        if b[19] != b'.' {
-            return Err(MicroShardError::InvalidIsoFormat);
-        }
+/// All fields of a [`MicroShardUUID`], as returned by [`MicroShardUUID::decompose`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DecodedMicroShard {
+    /// Microseconds since the Unix Epoch (54 bits).
+    pub timestamp_micros: u64,
+    /// Logical shard / tenant (32 bits).
+    pub shard_id: u32,
+    /// Entropy (36 bits; the top 2 carry the layout revision when tagged).
+    pub random: u64,
+    /// RFC 9562 version nibble (always 8).
+    pub version: u8,
+    /// RFC 9562 variant bits (always 2).
+    pub variant: u8,
+}
 
-        let end = iso_str.find('Z').unwrap_or(iso_str.len());
-        let frac_str = &iso_str[20..end];
-
-        let mut multiplier = 100_000;
-        for c in frac_str.chars() {
-            if let Some(digit) = c.to_digit(10) {
-                if multiplier >= 1 {
-                    micros += digit * multiplier;
-                    multiplier /= 10;
-                }
-            } else {
-                return Err(MicroShardError::InvalidIsoFormat);
-            }
-        }
-    }
+// -----------------------------------------------------------------------------
+// Trait Implementations
+// -----------------------------------------------------------------------------
 
-    // 6. Convert to Unix Epoch
-    let days_since_epoch = date_to_days(year, month, day);
-    if days_since_epoch < 0 {
-        return Err(MicroShardError::InvalidIsoFormat);
+// Implements standard 8-4-4-4-12 hex string formatting
+impl fmt::Display for MicroShardUUID {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hyphenated(self.0, f)
     }
-
-    let seconds = (days_since_epoch as u64 * 86400)
-        + (hour as u64 * 3600)
-        + (min as u64 * 60)
-        + sec as u64;
-
-    Ok(seconds * 1_000_000 + micros as u64)
 }
 
-/// Calculates the number of days from Year 0000 to the start of the given year.
-/// Formula: 365*y + y/4 - y/100 + y/400
-fn days_from_civil(y: i32) -> i64 {
-    // We calculate completed years, so subtract 1 from input
-    let y = y as i64 - 1;
-    y * 365 + y / 4 - y / 100 + y / 400
+// `{:x}` matches Display; `{:X}` is the uppercase form some legacy systems require
+impl fmt::LowerHex for MicroShardUUID {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hyphenated(self.0, f)
+    }
 }
 
-/// Converts Y/M/D to Unix Epoch Days (Days since 1970-01-01)
-fn date_to_days(y: i32, m: u32, d: u32) -> i64 {
-    const DAYS_BEFORE: [i64; 12] = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
-
-    // 1. Calculate absolute days from Year 0
-    let mut days = days_from_civil(y);
-
-    // 2. Subtract the number of days from Year 0 to 1970-01-01
-    //    (This constant is derived from days_from_civil(1970))
-    days -= 719162;
-
-    // 3. Add days for months passed in current year
-    days += DAYS_BEFORE[(m - 1) as usize];
-
-    // 4. Leap year adjustment for current year
-    //    If it's a leap year AND we are past February, add 1 day
-    if m > 2 && is_leap(y) {
-        days += 1;
+impl fmt::UpperHex for MicroShardUUID {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let buf = hex::encode_hyphenated(self.0, true);
+        f.write_str(core::str::from_utf8(&buf).expect("hex digits are ASCII"))
     }
+}
 
-    // 5. Add days in current month (1-based to 0-based)
-    days + (d as i64 - 1)
+/// Internal: writes any 128-bit value in lowercase 8-4-4-4-12 hex form.
+fn write_hyphenated<W: fmt::Write>(v: u128, f: &mut W) -> fmt::Result {
+    let buf = hex::encode_hyphenated(v, false);
+    f.write_str(core::str::from_utf8(&buf).expect("hex digits are ASCII"))
 }
 
-fn is_leap(year: i32) -> bool {
-    (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
+// ==========================================
+// Internal: Helpers & Utilities
+// ==========================================
+
+/// Internal: SplitMix64 step. Seeds the PRNG and derives deterministic
+/// hashes (Bloom probes, `truncate_time`), so it lives outside `gen`.
+fn splitmix64(x: &mut u64) -> u64 {
+    *x = x.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = *x;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
 }
 
-/// Converts a Unix Timestamp (u64 seconds) into civil date components:
-/// (Year, Month, Day, Hour, Minute, Second).
-///
-/// Algorithm: Howard Hinnant's "civil_from_days".
-/// It shifts the epoch to 0000-03-01 to simplify leap year logic,
-/// effectively moving Feb 29 to the very end of the year cycle.
-fn unix_to_civil(ts: u64) -> (i32, u32, u32, u32, u32, u32) {
-    // --------------------------------------------------------
-    // Part 1: Time Extraction (HH:MM:SS)
-    // --------------------------------------------------------
-    // 86400 seconds in a day. Integer division gives total full days.
-    let days = ts / 86400;
-    // Modulo gives the seconds elapsed in the current partial day.
-    let rem_sec = ts % 86400;
-
-    let hour = (rem_sec / 3600) as u32;
-    let min = ((rem_sec % 3600) / 60) as u32;
-    let sec = (rem_sec % 60) as u32;
-
-    // --------------------------------------------------------
-    // Part 2: Date Conversion (Hinnant's Algorithm)
-    // --------------------------------------------------------
-
-    // 1. Shift Epoch
-    // Unix Epoch is 1970-01-01. The algorithm requires an epoch of 0000-03-01.
-    // 719468 is the exact number of days between 0000-03-01 and 1970-01-01.
-    // 'z' is now the number of days since March 1st, year 0.
-    let z = days as i64 + 719468;
-
-    // 2. Calculate Era
-    // The Gregorian calendar repeats exactly every 400 years.
-    // One "Era" = 400 years = 146,097 days.
-    // We calculate which 400-year block we are in.
-    let era = (if z >= 0 { z } else { z - 146096 }) / 146097;
-
-    // 3. Day of Era (doe)
-    // How many days into this specific 400-year cycle are we? (0 to 146096)
-    let doe = (z - era * 146097) as u32;
-
-    // 4. Year of Era (yoe)
-    // Calculate which year (0-399) within the era we are in.
-    // This magic formula accounts for the leap year rules:
-    // - 1460   = days in 4 years
-    // - 36524  = days in 100 years
-    // - 146096 = days in 400 years
-    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
-
-    // 5. Absolute Year (y)
-    // Reconstruct the actual year number.
-    let y = (yoe as i64) + era * 400;
-
-    // 6. Day of Year (doy)
-    // How many days into this specific year are we? (0 to 365)
-    // This removes the days contributed by previous years in the era.
-    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
-
-    // 7. Month Position (mp)
-    // Map the Day of Year to a Month Index.
-    // Because we shifted the start to March, the months are:
-    // 0=March, 1=April ... 10=Jan, 11=Feb.
-    // The linear formula (5*doy + 2)/153 maps the uneven month lengths (30/31 days) perfectly.
-    let mp = (5 * doy + 2) / 153;
-
-    // 8. Day of Month (d)
-    // Inverse the logic above to get the specific day (1-31).
-    let d = doy - (153 * mp + 2) / 5 + 1;
-
-    // 9. Convert Month Index back to Civil Month (1-12)
-    // If mp < 10 (March...Dec), add 3 to get standard index.
-    // If mp >= 10 (Jan...Feb), subtract 9 to wrap around.
-    let m = if mp < 10 { mp + 3 } else { mp - 9 };
-
-    // 10. Adjust Year
-    // Since we treated March as the start of the year, Jan and Feb
-    // actually belong to the *next* civil year.
-    // Example: "Month 11" in our math is actually Feb of (Year + 1).
-    let y = y + if m <= 2 { 1 } else { 0 };
-
-    (y as i32, m as u32, d as u32, hour, min, sec)
+// The check is a no-op for `u32`, but is kept so the validation path mirrors
+// the other language ports (whose shard parameter is wider than 32 bits).
+#[cfg(feature = "std")]
+#[inline(always)]
+#[allow(clippy::absurd_extreme_comparisons)]
+fn validate_shard(shard_id: u32) -> Result<(), MicroShardError> {
+    if shard_id > MAX_SHARD_ID {
+        return Err(MicroShardError::InvalidShardId(MAX_SHARD_ID));
+    }
+    Ok(())
 }
@@ -0,0 +1,522 @@
+//! Local-time rendering using the operating system's TZ database.
+//!
+//! Enabled with the `local-time` feature. Zone files are read directly from
+//! `$TZDIR` or the standard zoneinfo directories and decoded with a minimal,
+//! dependency-free TZif (RFC 8536) reader, including the POSIX TZ footer rule
+//! used for instants after the last stored transition.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::{date_to_days, is_leap, unix_to_civil, MicroShardError, MicroShardUUID};
+
+/// Directories searched (in order) when `$TZDIR` is not set or lacks the zone.
+const ZONEINFO_DIRS: [&str; 3] = [
+    "/usr/share/zoneinfo",
+    "/usr/lib/zoneinfo",
+    "/usr/share/lib/zoneinfo",
+];
+
+// ==========================================
+// Public API
+// ==========================================
+
+/// A named timezone loaded from the OS TZ database.
+///
+/// Loading parses the zone file once; reuse the value when rendering many IDs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeZone {
+    name: String,
+    /// Transition instants (Unix seconds), ascending.
+    transitions: Vec<i64>,
+    /// Index into `offsets` for each transition.
+    transition_types: Vec<u8>,
+    /// UTC offsets (seconds east of UTC) of each local time type.
+    offsets: Vec<i32>,
+    /// Rule applied after the last transition (TZif v2+ footer).
+    footer: Option<PosixRule>,
+}
+
+impl TimeZone {
+    /// Loads a zone by its IANA name (e.g. `"Asia/Kolkata"`).
+    ///
+    /// `"UTC"` always resolves, even on systems without a zoneinfo directory.
+    pub fn load(name: &str) -> Result<Self, MicroShardError> {
+        let rel = Path::new(name);
+        let safe = !name.is_empty() && rel.components().all(|c| matches!(c, Component::Normal(_)));
+        if !safe {
+            return Err(MicroShardError::InvalidTimeZone);
+        }
+
+        let mut dirs: Vec<PathBuf> = Vec::new();
+        if let Some(dir) = std::env::var_os("TZDIR") {
+            dirs.push(PathBuf::from(dir));
+        }
+        dirs.extend(ZONEINFO_DIRS.iter().map(PathBuf::from));
+
+        for dir in dirs {
+            if let Ok(data) = fs::read(dir.join(rel)) {
+                return Self::from_tzif(name, &data);
+            }
+        }
+
+        if name == "UTC" {
+            return Ok(Self::utc());
+        }
+        Err(MicroShardError::InvalidTimeZone)
+    }
+
+    /// Parses a zone from raw TZif bytes (RFC 8536, versions 1 to 4).
+    pub fn from_tzif(name: &str, data: &[u8]) -> Result<Self, MicroShardError> {
+        parse_tzif(name, data).ok_or(MicroShardError::InvalidTimeZone)
+    }
+
+    /// Returns the fixed UTC zone.
+    pub fn utc() -> Self {
+        Self {
+            name: "UTC".to_string(),
+            transitions: Vec::new(),
+            transition_types: Vec::new(),
+            offsets: vec![0],
+            footer: None,
+        }
+    }
+
+    /// Returns the name the zone was loaded with.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the UTC offset (seconds east of UTC) in effect at `unix_secs`.
+    pub fn offset_at(&self, unix_secs: i64) -> i32 {
+        // Before the first transition: RFC 8536 says use local time type 0
+        if self.transitions.is_empty() || unix_secs < self.transitions[0] {
+            return match (&self.footer, self.transitions.is_empty()) {
+                (Some(rule), true) => rule.offset_at(unix_secs),
+                _ => self.offsets[0],
+            };
+        }
+
+        let last = *self.transitions.last().unwrap();
+        if unix_secs >= last {
+            if let Some(rule) = &self.footer {
+                return rule.offset_at(unix_secs);
+            }
+        }
+
+        // Binary search for the last transition <= unix_secs
+        let idx = self.transitions.partition_point(|&t| t <= unix_secs) - 1;
+        self.offsets[self.transition_types[idx] as usize]
+    }
+}
+
+impl MicroShardUUID {
+    /// Renders the embedded timestamp in the named IANA timezone.
+    /// Format: `YYYY-MM-DDTHH:MM:SS.mmmmmm+HH:MM`
+    ///
+    /// Loads the zone file on every call; use [`MicroShardUUID::to_local_string_in`]
+    /// with a cached [`TimeZone`] for bulk exports.
+    pub fn to_local_string(&self, tz_name: &str) -> Result<String, MicroShardError> {
+        Ok(self.to_local_string_in(&TimeZone::load(tz_name)?))
+    }
+
+    /// Renders the embedded timestamp in an already loaded [`TimeZone`].
+    ///
+    /// Historical offsets with a seconds component (e.g. Local Mean Time)
+    /// are rendered as `+HH:MM:SS`.
+    pub fn to_local_string_in(&self, tz: &TimeZone) -> String {
+        let total_micros = self.timestamp_micros();
+        let utc_secs = (total_micros / 1_000_000) as i64;
+        let micros = total_micros % 1_000_000;
+
+        let offset = tz.offset_at(utc_secs);
+        let (year, month, day, hour, min, sec) = unix_to_civil(utc_secs + offset as i64);
+
+        let sign = if offset < 0 { '-' } else { '+' };
+        let abs = offset.unsigned_abs();
+        let mut out = format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:06}{}{:02}:{:02}",
+            year,
+            month,
+            day,
+            hour,
+            min,
+            sec,
+            micros,
+            sign,
+            abs / 3600,
+            (abs % 3600) / 60
+        );
+        if !abs.is_multiple_of(60) {
+            out.push_str(&format!(":{:02}", abs % 60));
+        }
+        out
+    }
+}
+
+// ==========================================
+// Internal: TZif Reader
+// ==========================================
+
+/// Internal: big-endian cursor over the TZif byte stream.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        let end = self.pos.checked_add(n)?;
+        let out = self.data.get(self.pos..end)?;
+        self.pos = end;
+        Some(out)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_be_bytes(self.take(4)?.try_into().ok()?))
+    }
+}
+
+/// Internal: the six counts from a TZif header.
+struct Header {
+    version: u8,
+    isutcnt: usize,
+    isstdcnt: usize,
+    leapcnt: usize,
+    timecnt: usize,
+    typecnt: usize,
+    charcnt: usize,
+}
+
+fn read_header(r: &mut Reader) -> Option<Header> {
+    if r.take(4)? != b"TZif" {
+        return None;
+    }
+    let version = r.take(1)?[0];
+    r.take(15)?; // reserved
+    Some(Header {
+        version,
+        isutcnt: r.u32()? as usize,
+        isstdcnt: r.u32()? as usize,
+        leapcnt: r.u32()? as usize,
+        timecnt: r.u32()? as usize,
+        typecnt: r.u32()? as usize,
+        charcnt: r.u32()? as usize,
+    })
+}
+
+/// Internal: size of a data block whose transition times are `time_size`
+/// bytes, or `None` if the header's counts overflow `usize`.
+fn block_len(h: &Header, time_size: usize) -> Option<usize> {
+    let times = h.timecnt.checked_mul(time_size + 1)?;
+    let types = h.typecnt.checked_mul(6)?;
+    let leaps = h.leapcnt.checked_mul(time_size + 4)?;
+    times
+        .checked_add(types)?
+        .checked_add(h.charcnt)?
+        .checked_add(leaps)?
+        .checked_add(h.isstdcnt)?
+        .checked_add(h.isutcnt)
+}
+
+fn parse_tzif(name: &str, data: &[u8]) -> Option<TimeZone> {
+    let mut r = Reader { data, pos: 0 };
+    let mut h = read_header(&mut r)?;
+    let mut time_size = 4;
+
+    // Version 2+: skip the legacy 32-bit block and use the 64-bit one
+    if h.version >= b'2' {
+        r.take(block_len(&h, 4)?)?;
+        h = read_header(&mut r)?;
+        time_size = 8;
+    }
+    if h.typecnt == 0 {
+        return None;
+    }
+    // The counts come from the input: check the block is really there
+    // before sizing any allocation by them
+    if data.len() - r.pos < block_len(&h, time_size)? {
+        return None;
+    }
+
+    let mut transitions = Vec::with_capacity(h.timecnt);
+    for _ in 0..h.timecnt {
+        let raw = r.take(time_size)?;
+        let t = if time_size == 8 {
+            i64::from_be_bytes(raw.try_into().ok()?)
+        } else {
+            i32::from_be_bytes(raw.try_into().ok()?) as i64
+        };
+        transitions.push(t);
+    }
+
+    let transition_types = r.take(h.timecnt)?.to_vec();
+    if transition_types.iter().any(|&t| t as usize >= h.typecnt) {
+        return None;
+    }
+
+    let mut offsets = Vec::with_capacity(h.typecnt);
+    for _ in 0..h.typecnt {
+        let rec = r.take(6)?;
+        offsets.push(i32::from_be_bytes(rec[0..4].try_into().ok()?));
+    }
+
+    r.take(h.charcnt + h.leapcnt * (time_size + 4) + h.isstdcnt + h.isutcnt)?;
+
+    // Footer: "\n<POSIX TZ string>\n" (version 2+ only)
+    let mut footer = None;
+    if h.version >= b'2' {
+        let rest = &data[r.pos..];
+        if rest.first() == Some(&b'\n') {
+            let body = &rest[1..];
+            let end = body.iter().position(|&b| b == b'\n')?;
+            let tz = std::str::from_utf8(&body[..end]).ok()?;
+            if !tz.is_empty() {
+                footer = Some(PosixRule::parse(tz)?);
+            }
+        }
+    }
+
+    Some(TimeZone {
+        name: name.to_string(),
+        transitions,
+        transition_types,
+        offsets,
+        footer,
+    })
+}
+
+// ==========================================
+// Internal: POSIX TZ Rule (e.g. "EST5EDT,M3.2.0,M11.1.0")
+// ==========================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DateRule {
+    /// `Jn`: Julian day 1..=365, Feb 29 never counted.
+    Julian1(u16),
+    /// `n`: zero-based day 0..=365, Feb 29 counted in leap years.
+    Julian0(u16),
+    /// `Mm.w.d`: weekday `d` (0 = Sunday) of week `w` (5 = last) in month `m`.
+    MonthWeekDay(u8, u8, u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Transition {
+    rule: DateRule,
+    /// Local wall-clock seconds after midnight (may be negative or > 24h).
+    time: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PosixRule {
+    std_offset: i32,
+    /// DST offset plus its (start, end) transitions, if the zone observes DST.
+    dst: Option<(i32, Transition, Transition)>,
+}
+
+impl PosixRule {
+    fn parse(s: &str) -> Option<Self> {
+        let mut p = s.as_bytes();
+
+        skip_name(&mut p)?;
+        // POSIX offsets are west-positive; TZif offsets are east-positive
+        let std_offset = -parse_hms(&mut p, MAX_OFFSET_HOURS)?;
+        if p.is_empty() {
+            return Some(Self {
+                std_offset,
+                dst: None,
+            });
+        }
+
+        skip_name(&mut p)?;
+        let dst_offset = if p.first().is_some_and(|&c| c != b',') {
+            -parse_hms(&mut p, MAX_OFFSET_HOURS)?
+        } else {
+            std_offset + 3600
+        };
+
+        // Rules are mandatory in TZif footers that declare a DST name
+        let start = parse_transition(&mut p)?;
+        let end = parse_transition(&mut p)?;
+        if !p.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            std_offset,
+            dst: Some((dst_offset, start, end)),
+        })
+    }
+
+    fn offset_at(&self, unix_secs: i64) -> i32 {
+        let Some((dst_offset, start, end)) = self.dst else {
+            return self.std_offset;
+        };
+
+        // Year of the instant in local standard time
+        let (year, ..) = unix_to_civil(unix_secs + self.std_offset as i64);
+
+        // Start is given in standard time, end in daylight time
+        let dst_start = transition_utc(year, &start, self.std_offset);
+        let dst_end = transition_utc(year, &end, dst_offset);
+
+        let in_dst = if dst_start < dst_end {
+            unix_secs >= dst_start && unix_secs < dst_end
+        } else {
+            // Southern hemisphere: DST spans the year boundary
+            !(unix_secs >= dst_end && unix_secs < dst_start)
+        };
+
+        if in_dst {
+            dst_offset
+        } else {
+            self.std_offset
+        }
+    }
+}
+
+/// Internal: converts a rule to a UTC instant for `year`.
+fn transition_utc(year: i32, t: &Transition, offset: i32) -> i64 {
+    let days = match t.rule {
+        DateRule::Julian1(n) => {
+            let n = n as i64;
+            let leap_shift = if is_leap(year) && n >= 60 { 1 } else { 0 };
+            date_to_days(year, 1, 1) + n - 1 + leap_shift
+        }
+        DateRule::Julian0(n) => date_to_days(year, 1, 1) + n as i64,
+        DateRule::MonthWeekDay(m, w, d) => {
+            let first = date_to_days(year, m as u32, 1);
+            // 1970-01-01 was a Thursday (weekday 4)
+            let first_wday = (first + 4).rem_euclid(7);
+            let mut day = (d as i64 - first_wday).rem_euclid(7) + (w as i64 - 1) * 7;
+            let month_len = days_in_month(year, m as u32) as i64;
+            while day >= month_len {
+                day -= 7;
+            }
+            first + day
+        }
+    };
+    days * 86400 + t.time as i64 - offset as i64
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap(year) => 29,
+        2 => 28,
+        _ => 31,
+    }
+}
+
+/// Internal: skips a zone abbreviation (`EST` or quoted `<+0530>`).
+fn skip_name(p: &mut &[u8]) -> Option<()> {
+    if p.first() == Some(&b'<') {
+        let end = p.iter().position(|&c| c == b'>')?;
+        *p = &p[end + 1..];
+    } else {
+        let len = p.iter().take_while(|c| c.is_ascii_alphabetic()).count();
+        if len < 3 {
+            return None;
+        }
+        *p = &p[len..];
+    }
+    Some(())
+}
+
+/// Largest hour in a POSIX UTC offset.
+const MAX_OFFSET_HOURS: u32 = 24;
+/// Largest hour in a rule's transition time (RFC 8536 extends POSIX's 24).
+const MAX_RULE_TIME_HOURS: u32 = 167;
+
+/// Internal: parses `[+-]hh[:mm[:ss]]` as seconds, rejecting hours above
+/// `max_hours` and minutes or seconds above 59.
+fn parse_hms(p: &mut &[u8], max_hours: u32) -> Option<i32> {
+    let mut sign = 1;
+    match p.first() {
+        Some(b'-') => {
+            sign = -1;
+            *p = &p[1..];
+        }
+        Some(b'+') => *p = &p[1..],
+        _ => {}
+    }
+
+    let mut total: i32 = 0;
+    for (i, (unit, max)) in [(3600, max_hours), (60, 59), (1, 59)]
+        .into_iter()
+        .enumerate()
+    {
+        if i > 0 {
+            if p.first() != Some(&b':') {
+                break;
+            }
+            *p = &p[1..];
+        }
+        let n = parse_num(p).filter(|&n| n <= max)?;
+        total = total.checked_add((n as i32).checked_mul(unit)?)?;
+    }
+    Some(sign * total)
+}
+
+fn parse_num(p: &mut &[u8]) -> Option<u32> {
+    let len = p.iter().take_while(|c| c.is_ascii_digit()).count();
+    if len == 0 {
+        return None;
+    }
+    let n = std::str::from_utf8(&p[..len]).ok()?.parse().ok()?;
+    *p = &p[len..];
+    Some(n)
+}
+
+/// Internal: parses `,rule[/time]`. Time defaults to 02:00:00.
+fn parse_transition(p: &mut &[u8]) -> Option<Transition> {
+    if p.first() != Some(&b',') {
+        return None;
+    }
+    *p = &p[1..];
+
+    let rule = match p.first()? {
+        b'J' => {
+            *p = &p[1..];
+            let n = parse_num(p)?;
+            if !(1..=365).contains(&n) {
+                return None;
+            }
+            DateRule::Julian1(n as u16)
+        }
+        b'M' => {
+            *p = &p[1..];
+            let m = parse_num(p)?;
+            if p.first() != Some(&b'.') {
+                return None;
+            }
+            *p = &p[1..];
+            let w = parse_num(p)?;
+            if p.first() != Some(&b'.') {
+                return None;
+            }
+            *p = &p[1..];
+            let d = parse_num(p)?;
+            if !(1..=12).contains(&m) || !(1..=5).contains(&w) || d > 6 {
+                return None;
+            }
+            DateRule::MonthWeekDay(m as u8, w as u8, d as u8)
+        }
+        _ => {
+            let n = parse_num(p)?;
+            if n > 365 {
+                return None;
+            }
+            DateRule::Julian0(n as u16)
+        }
+    };
+
+    let time = if p.first() == Some(&b'/') {
+        *p = &p[1..];
+        parse_hms(p, MAX_RULE_TIME_HOURS)?
+    } else {
+        7200
+    };
+
+    Some(Transition { rule, time })
+}
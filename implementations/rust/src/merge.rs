@@ -0,0 +1,143 @@
+//! Streaming merge utilities for sorted ID streams.
+//!
+//! Inputs must be sorted ascending. All functions are lazy and hold O(1)
+//! items per input stream, so they scale to billions of rows.
+
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+use core::iter::Peekable;
+
+use crate::MicroShardUUID;
+
+// ==========================================
+// Merge-Join
+// ==========================================
+
+/// One output row of [`join`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum JoinItem {
+    /// The ID is present in both streams.
+    Both(MicroShardUUID),
+    /// The ID is only present in the left stream.
+    LeftOnly(MicroShardUUID),
+    /// The ID is only present in the right stream.
+    RightOnly(MicroShardUUID),
+}
+
+impl JoinItem {
+    /// Returns the ID regardless of which side(s) it came from.
+    pub fn id(&self) -> MicroShardUUID {
+        match *self {
+            Self::Both(id) | Self::LeftOnly(id) | Self::RightOnly(id) => id,
+        }
+    }
+}
+
+/// Iterator returned by [`join`].
+#[derive(Debug)]
+pub struct Join<A, B>
+where
+    A: Iterator<Item = MicroShardUUID>,
+    B: Iterator<Item = MicroShardUUID>,
+{
+    left: Peekable<A>,
+    right: Peekable<B>,
+}
+
+impl<A, B> Iterator for Join<A, B>
+where
+    A: Iterator<Item = MicroShardUUID>,
+    B: Iterator<Item = MicroShardUUID>,
+{
+    type Item = JoinItem;
+
+    fn next(&mut self) -> Option<JoinItem> {
+        match (self.left.peek(), self.right.peek()) {
+            (Some(l), Some(r)) if l == r => {
+                self.right.next();
+                self.left.next().map(JoinItem::Both)
+            }
+            (Some(l), Some(r)) if l < r => self.left.next().map(JoinItem::LeftOnly),
+            (Some(_), Some(_)) | (None, Some(_)) => self.right.next().map(JoinItem::RightOnly),
+            (Some(_), None) => self.left.next().map(JoinItem::LeftOnly),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Full outer merge-join of two ascending ID streams.
+///
+/// Yields every distinct ID once, in ascending order, tagged with the side(s)
+/// it appeared on. Intended for reconciliation jobs diffing two systems' ID
+/// sets. If a stream contains duplicates, each occurrence is matched at most
+/// once against the other stream.
+pub fn join<A, B>(a: A, b: B) -> Join<A::IntoIter, B::IntoIter>
+where
+    A: IntoIterator<Item = MicroShardUUID>,
+    B: IntoIterator<Item = MicroShardUUID>,
+{
+    Join {
+        left: a.into_iter().peekable(),
+        right: b.into_iter().peekable(),
+    }
+}
+
+// ==========================================
+// K-Way Merge
+// ==========================================
+
+/// Iterator returned by [`kway`].
+#[derive(Debug)]
+pub struct KWay<I>
+where
+    I: Iterator<Item = MicroShardUUID>,
+{
+    streams: Vec<I>,
+    /// Min-heap of (head ID, stream index).
+    heap: BinaryHeap<Reverse<(MicroShardUUID, usize)>>,
+}
+
+impl<I> Iterator for KWay<I>
+where
+    I: Iterator<Item = MicroShardUUID>,
+{
+    type Item = MicroShardUUID;
+
+    fn next(&mut self) -> Option<MicroShardUUID> {
+        let Reverse((id, idx)) = self.heap.pop()?;
+        if let Some(next) = self.streams[idx].next() {
+            self.heap.push(Reverse((next, idx)));
+        }
+        Some(id)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let mut lo = self.heap.len();
+        let mut hi = Some(self.heap.len());
+        for s in &self.streams {
+            let (s_lo, s_hi) = s.size_hint();
+            lo = lo.saturating_add(s_lo);
+            hi = hi.zip(s_hi).and_then(|(a, b)| a.checked_add(b));
+        }
+        (lo, hi)
+    }
+}
+
+/// Merges per-shard ascending streams into one globally ordered stream.
+///
+/// Uses a binary heap holding one head item per stream: O(log k) per item.
+/// Duplicates are preserved; equal IDs are emitted in stream order.
+pub fn kway<I>(streams: Vec<I>) -> KWay<I::IntoIter>
+where
+    I: IntoIterator<Item = MicroShardUUID>,
+{
+    let mut streams: Vec<_> = streams.into_iter().map(IntoIterator::into_iter).collect();
+    let mut heap = BinaryHeap::with_capacity(streams.len());
+    for (idx, s) in streams.iter_mut().enumerate() {
+        if let Some(id) = s.next() {
+            heap.push(Reverse((id, idx)));
+        }
+    }
+    KWay { streams, heap }
+}
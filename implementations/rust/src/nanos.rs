@@ -0,0 +1,222 @@
+//! Nanosecond-precision sibling of [`MicroShardUUID`].
+//!
+//! [`MicroShardUUIDNanos`] trades 10 random bits for nanosecond resolution,
+//! for trading and telemetry workloads where many events share a
+//! microsecond and their order matters.
+//!
+//! **Layout (UUIDv8):**
+//! - **High 64 bits:** `[TimeHigh (48 bits)] [Ver=8 (4 bits)] [TimeMid (12 bits)]`
+//! - **Low 64 bits:**  `[Var (2 bits)] [TimeLow (4 bits)] [Shard (32 bits)] [Random (26 bits)]`
+//!
+//! The 64-bit nanosecond field covers 1970 to 2554 and sorts first, so the
+//! `u128` order is chronological to the nanosecond. Both types are valid v8
+//! UUIDs with the same version and variant bits; which layout a column holds
+//! is a schema decision, not something the bits can tell.
+
+use core::fmt;
+#[cfg(feature = "parse")]
+use core::str::FromStr;
+use core::time::Duration;
+
+#[cfg(feature = "parse")]
+use crate::parse_hyphenated;
+#[cfg(feature = "gen")]
+use crate::{now_nanos, validate_shard, Xoshiro256StarStar};
+use crate::{write_hyphenated, MicroShardError, MicroShardUUID, MAX_TIME_MICROS};
+
+const MAX_NANOS_RANDOM: u64 = (1 << 26) - 1;
+/// Random bits dropped when narrowing the 36-bit microsecond field to 26 bits.
+const NARROW_SHIFT: u32 = 10;
+const SHARD_SHIFT: u32 = 26;
+const TIME_LOW_SHIFT: u32 = 58;
+
+/// A sortable, sharded UUIDv8 with a 64-bit nanosecond timestamp.
+///
+/// Shares the accessors and conversions of [`MicroShardUUID`]; converting
+/// between the two keeps time (truncated to the microsecond) and shard.
+///
+/// ```
+/// use microshard_uuid::{MicroShardUUID, MicroShardUUIDNanos};
+///
+/// let id = MicroShardUUIDNanos::from_parts(1_700_000_000_123_456_789, 42, 7).unwrap();
+/// assert_eq!(id.timestamp_nanos(), 1_700_000_000_123_456_789);
+/// assert_eq!(id.timestamp_micros(), 1_700_000_000_123_456);
+/// assert_eq!(id.shard_id(), 42);
+///
+/// let micro = MicroShardUUID::try_from(id).unwrap();
+/// assert_eq!(micro.timestamp_micros(), 1_700_000_000_123_456);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
+pub struct MicroShardUUIDNanos(u128);
+
+impl MicroShardUUIDNanos {
+    // -------------------------------------------------------------------------
+    // Constructors
+    // -------------------------------------------------------------------------
+
+    /// Generates a new ID using the current system time.
+    ///
+    /// Honours [`test_support::freeze_time`](crate::test_support::freeze_time)
+    /// (at whole microseconds).
+    #[cfg(feature = "gen")]
+    pub fn generate(shard_id: u32) -> Result<Self, MicroShardError> {
+        Self::from_nanos(now_nanos()?, shard_id)
+    }
+
+    /// Generates an ID stamped with `nanos` (nanoseconds since the Unix Epoch).
+    #[cfg(feature = "gen")]
+    pub fn from_nanos(nanos: u64, shard_id: u32) -> Result<Self, MicroShardError> {
+        validate_shard(shard_id)?;
+        Self::from_parts(
+            nanos,
+            shard_id,
+            Xoshiro256StarStar::next_36() & MAX_NANOS_RANDOM,
+        )
+    }
+
+    /// Constructs an ID from explicit field values, with no PRNG involved.
+    /// Every `u64` nanosecond value fits.
+    ///
+    /// # Errors
+    /// [`MicroShardError::InvalidRandom`] if `random` exceeds 2^26 - 1.
+    pub fn from_parts(nanos: u64, shard_id: u32, random: u64) -> Result<Self, MicroShardError> {
+        if random > MAX_NANOS_RANDOM {
+            return Err(MicroShardError::InvalidRandom(random));
+        }
+        let high = (nanos & !0xFFFF) | (8 << 12) | ((nanos >> 4) & 0xFFF);
+        let low = (2 << 62)
+            | ((nanos & 0xF) << TIME_LOW_SHIFT)
+            | ((shard_id as u64) << SHARD_SHIFT)
+            | random;
+        Ok(Self(((high as u128) << 64) | low as u128))
+    }
+
+    /// Constructs an ID from a raw `u128`, checking for version 8, variant 2.
+    pub const fn from_u128(v: u128) -> Result<Self, MicroShardError> {
+        let version = ((v >> 76) & 0xF) as u8;
+        if version != 8 {
+            return Err(MicroShardError::InvalidVersion(version));
+        }
+        let variant = ((v >> 62) & 0x3) as u8;
+        if variant != 2 {
+            return Err(MicroShardError::InvalidVariant(variant));
+        }
+        Ok(Self(v))
+    }
+
+    /// Constructs an ID from a 16-byte array (Big Endian).
+    pub fn from_bytes(bytes: [u8; 16]) -> Result<Self, MicroShardError> {
+        Self::from_u128(u128::from_be_bytes(bytes))
+    }
+
+    /// Parses the canonical hyphenated form (optionally `urn:uuid:`-prefixed).
+    #[cfg(feature = "parse")]
+    pub fn parse(s: &str) -> Result<Self, MicroShardError> {
+        Self::from_u128(parse_hyphenated(s)?)
+    }
+
+    // -------------------------------------------------------------------------
+    // Accessors
+    // -------------------------------------------------------------------------
+
+    /// Returns the raw `u128` value.
+    pub fn as_u128(&self) -> u128 {
+        self.0
+    }
+
+    /// Returns the ID as a 16-byte array (Big Endian).
+    pub fn as_bytes(&self) -> [u8; 16] {
+        self.0.to_be_bytes()
+    }
+
+    /// Extracts the creation time as nanoseconds since the Unix Epoch.
+    pub fn timestamp_nanos(&self) -> u64 {
+        let high = (self.0 >> 64) as u64;
+        let low = self.0 as u64;
+        (high & !0xFFFF) | ((high & 0xFFF) << 4) | ((low >> TIME_LOW_SHIFT) & 0xF)
+    }
+
+    /// Extracts the creation time as microseconds since the Unix Epoch
+    /// (truncated).
+    pub fn timestamp_micros(&self) -> u64 {
+        self.timestamp_nanos() / 1_000
+    }
+
+    /// The creation time as a `Duration` since the Unix Epoch (ns precision).
+    pub fn since_epoch(&self) -> Duration {
+        Duration::from_nanos(self.timestamp_nanos())
+    }
+
+    /// The creation time as a `SystemTime` (ns precision where the platform
+    /// clock has it).
+    #[cfg(feature = "std")]
+    pub fn timestamp(&self) -> std::time::SystemTime {
+        std::time::UNIX_EPOCH + self.since_epoch()
+    }
+
+    /// Extracts the 32-bit Shard ID.
+    pub fn shard_id(&self) -> u32 {
+        (self.0 >> SHARD_SHIFT) as u32
+    }
+
+    /// Extracts the 26-bit random field.
+    pub fn random(&self) -> u64 {
+        (self.0 as u64) & MAX_NANOS_RANDOM
+    }
+}
+
+impl fmt::Display for MicroShardUUIDNanos {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hyphenated(self.0, f)
+    }
+}
+
+#[cfg(feature = "parse")]
+impl FromStr for MicroShardUUIDNanos {
+    type Err = MicroShardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Conversions to and from the microsecond layout
+// -----------------------------------------------------------------------------
+
+/// Time and shard carry over exactly; the **lowest** 10 random bits are
+/// dropped, which keeps the sequence of monotonic generators (and so the
+/// order between IDs, ties aside).
+impl From<MicroShardUUID> for MicroShardUUIDNanos {
+    fn from(id: MicroShardUUID) -> Self {
+        // 54-bit microseconds (year 2541) always fit 64-bit nanoseconds.
+        Self::from_parts(
+            id.timestamp_micros() * 1_000,
+            id.shard_id(),
+            id.random() >> NARROW_SHIFT,
+        )
+        .expect("26-bit random always fits")
+    }
+}
+
+/// Truncates time to the microsecond and keeps the shard; the low 10
+/// random bits are zero.
+///
+/// # Errors
+/// [`MicroShardError::TimeOverflow`] after the year 2541 (the nanosecond
+/// field runs to 2554).
+impl TryFrom<MicroShardUUIDNanos> for MicroShardUUID {
+    type Error = MicroShardError;
+
+    fn try_from(id: MicroShardUUIDNanos) -> Result<Self, Self::Error> {
+        let micros = id.timestamp_micros();
+        if micros > MAX_TIME_MICROS {
+            return Err(MicroShardError::TimeOverflow);
+        }
+        Ok(Self::compose(
+            micros,
+            id.shard_id(),
+            id.random() << NARROW_SHIFT,
+        ))
+    }
+}
@@ -0,0 +1,37 @@
+//! Importing MongoDB ObjectIds.
+//!
+//! An ObjectId is 12 bytes: `[Seconds (4 bytes)] [Process (5 bytes)] [Counter (3 bytes)]`,
+//! where the process value is the per-process random value (machine id +
+//! pid in drivers predating MongoDB 3.4). [`MicroShardUUID::from_object_id`]
+//! maps it losslessly:
+//!
+//! - **Time:** the seconds, as whole-second microseconds.
+//! - **Shard:** the top 32 bits of the process value.
+//! - **Random:** `[Zero (4 bits)] [Process low byte (8 bits)] [Counter (24 bits)]`.
+//!
+//! ObjectIds sort by (seconds, process, counter) and so do the resulting IDs,
+//! so migrated primary keys keep their original order.
+
+use crate::MicroShardUUID;
+
+impl MicroShardUUID {
+    /// Re-encodes a 12-byte MongoDB ObjectId as a MicroShard ID.
+    ///
+    /// ```
+    /// use microshard_uuid::MicroShardUUID;
+    ///
+    /// // ObjectId("65a1b2c3" "d4e5f60718" "293a4b")
+    /// let oid = [0x65, 0xa1, 0xb2, 0xc3, 0xd4, 0xe5, 0xf6, 0x07, 0x18, 0x29, 0x3a, 0x4b];
+    /// let id = MicroShardUUID::from_object_id(oid);
+    /// assert_eq!(id.timestamp_micros(), 0x65a1b2c3 * 1_000_000);
+    /// assert_eq!(id.shard_id(), 0xd4e5f607);
+    /// assert_eq!(id.random(), 0x18_293a4b);
+    /// ```
+    pub fn from_object_id(bytes: [u8; 12]) -> Self {
+        let secs = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let shard_id = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+        let random = u32::from_be_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]);
+        // u32 seconds end in 2106, well inside the 54-bit range.
+        Self::compose(secs as u64 * 1_000_000, shard_id, random as u64)
+    }
+}
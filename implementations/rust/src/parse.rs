@@ -0,0 +1,363 @@
+//! String parsing: the canonical hyphenated form, ISO 8601 timestamps, and
+//! the `msuuid!` literal macro (enabled with the `parse` feature).
+
+use core::str::FromStr;
+
+use crate::calendar::{date_to_days, is_leap};
+#[cfg(feature = "gen")]
+use crate::validate_shard;
+use crate::{hex, MicroShardError, MicroShardUUID};
+
+impl MicroShardUUID {
+    /// Parses the canonical hyphenated form (`xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx`),
+    /// optionally wrapped as an RFC 9562 URN (`urn:uuid:xxxxxxxx-...`).
+    /// Upper- and lowercase hex digits are accepted; version and variant are validated.
+    ///
+    /// This is a `const fn`; see [`msuuid!`](crate::msuuid!) for compile-time literals.
+    /// It decodes with scalar code; `str::parse` (`FromStr`) parses the same
+    /// input with the SIMD kernels, so prefer it for bulk runtime parsing.
+    pub const fn parse(s: &str) -> Result<Self, MicroShardError> {
+        match const_parse_hyphenated(s) {
+            Ok(v) => Self::from_u128(v),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Parses the 32-character "simple" form (hex digits, no hyphens), as
+    /// produced by `to_simple_string`. Either case is accepted; version and
+    /// variant are validated.
+    pub const fn parse_simple(s: &str) -> Result<Self, MicroShardError> {
+        match const_parse_simple_hex(s) {
+            Ok(v) => Self::from_u128(v),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Parses the common textual variants found in the wild, for ingesting IDs
+    /// from .NET services, log scrapes and the like:
+    ///
+    /// - canonical `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx` and `urn:uuid:` forms
+    /// - braced GUIDs `{xxxxxxxx-...}` and parenthesized `(xxxxxxxx-...)`
+    /// - 32 hex digits without hyphens, bare or braced
+    ///
+    /// [`parse`](Self::parse) (and `FromStr`) remain strict; use this only at
+    /// ingestion boundaries. Version and variant are still validated.
+    pub fn parse_lenient(s: &str) -> Result<Self, MicroShardError> {
+        let v = match s.as_bytes() {
+            [b'{', .., b'}'] | [b'(', .., b')'] => {
+                let inner = &s[1..s.len() - 1];
+                match inner.len() {
+                    32 => parse_simple_hex(inner)?,
+                    36 => parse_hyphenated(inner)?,
+                    _ => return Err(MicroShardError::InvalidUuidFormat),
+                }
+            }
+            _ if s.len() == 32 => parse_simple_hex(s)?,
+            _ => parse_hyphenated(s)?,
+        };
+        Self::from_u128(v)
+    }
+
+    #[cfg(feature = "gen")]
+    /// Generates a `MicroShardUUID` from an ISO 8601 string.
+    ///
+    /// # Format
+    /// Expected format: `YYYY-MM-DDTHH:MM:SS.mmmmmmZ`. A numeric offset
+    /// (`2024-05-01T10:00:00.123456+05:30`) may replace the `Z`; the time is
+    /// normalized to UTC before encoding.
+    ///
+    /// # Errors
+    /// - [`MicroShardError::InvalidIsoFormat`] for a malformed timestamp.
+    /// - [`MicroShardError::InvalidUtcOffset`] for a malformed offset.
+    pub fn from_iso(iso_str: &str, shard_id: u32) -> Result<Self, MicroShardError> {
+        validate_shard(shard_id)?;
+        let micros = parse_iso_strict(iso_str)?;
+        Self::build(micros, shard_id)
+    }
+
+    #[cfg(feature = "gen")]
+    /// Like [`from_iso`](Self::from_iso), with the relaxed syntax of
+    /// [`iso_to_micros_lenient`] (space separator, lowercase `t`/`z`, ...).
+    pub fn from_iso_lenient(iso_str: &str, shard_id: u32) -> Result<Self, MicroShardError> {
+        validate_shard(shard_id)?;
+        let micros = parse_iso(iso_str, true)?;
+        Self::build(micros, shard_id)
+    }
+}
+
+impl FromStr for MicroShardUUID {
+    type Err = MicroShardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_u128(parse_hyphenated(s)?)
+    }
+}
+
+/// The RFC 9562 URN prefix (matched case-insensitively when parsing).
+const URN_PREFIX: &[u8; 9] = b"urn:uuid:";
+
+/// Internal: parses the 36-char 8-4-4-4-12 form, optionally behind a
+/// `urn:uuid:` prefix, into a raw `u128` (no version/variant checks).
+pub(crate) fn parse_hyphenated(s: &str) -> Result<u128, MicroShardError> {
+    let b = strip_urn_prefix(s.as_bytes());
+    let b36 = b
+        .try_into()
+        .map_err(|_| MicroShardError::InvalidUuidFormat)?;
+    hex::decode_hyphenated(b36).ok_or(MicroShardError::InvalidUuidFormat)
+}
+
+/// Internal: parses 32 hex digits with no separators into a raw `u128`.
+pub(crate) fn parse_simple_hex(s: &str) -> Result<u128, MicroShardError> {
+    let b32 = s
+        .as_bytes()
+        .try_into()
+        .map_err(|_| MicroShardError::InvalidUuidFormat)?;
+    hex::decode_simple(b32).ok_or(MicroShardError::InvalidUuidFormat)
+}
+
+/// Internal: `const` (scalar) [`parse_hyphenated`].
+const fn const_parse_hyphenated(s: &str) -> Result<u128, MicroShardError> {
+    let b = strip_urn_prefix(s.as_bytes());
+    match b.first_chunk::<36>() {
+        Some(b36) if b.len() == 36 => match hex::const_decode_hyphenated(b36) {
+            Some(v) => Ok(v),
+            None => Err(MicroShardError::InvalidUuidFormat),
+        },
+        _ => Err(MicroShardError::InvalidUuidFormat),
+    }
+}
+
+/// Internal: `const` (scalar) [`parse_simple_hex`].
+const fn const_parse_simple_hex(s: &str) -> Result<u128, MicroShardError> {
+    let b = s.as_bytes();
+    match b.first_chunk::<32>() {
+        Some(b32) if b.len() == 32 => match hex::const_decode_simple(b32) {
+            Some(v) => Ok(v),
+            None => Err(MicroShardError::InvalidUuidFormat),
+        },
+        _ => Err(MicroShardError::InvalidUuidFormat),
+    }
+}
+
+/// Internal: drops a leading `urn:uuid:` (any case) from a 45-byte input.
+const fn strip_urn_prefix(b: &[u8]) -> &[u8] {
+    if b.len() != URN_PREFIX.len() + 36 {
+        return b;
+    }
+    let mut i = 0;
+    while i < URN_PREFIX.len() {
+        if b[i].to_ascii_lowercase() != URN_PREFIX[i] {
+            return b;
+        }
+        i += 1;
+    }
+    b.split_at(URN_PREFIX.len()).1
+}
+
+/// Creates a validated [`MicroShardUUID`] from a string literal at compile time.
+///
+/// Malformed literals, and well-formed UUIDs that are not MicroShard IDs, are
+/// rejected when the crate is compiled rather than at runtime, which makes the
+/// macro suitable for well-known IDs declared as constants.
+///
+/// ```
+/// use microshard_uuid::{msuuid, MicroShardUUID};
+///
+/// const SYSTEM_ROOT: MicroShardUUID = msuuid!("00000000-0000-8000-8000-000000000000");
+/// assert_eq!(SYSTEM_ROOT.shard_id(), 0);
+/// ```
+///
+/// ```compile_fail
+/// // Version 4: fails to compile
+/// const BAD: microshard_uuid::MicroShardUUID =
+///     microshard_uuid::msuuid!("550e8400-e29b-41d4-a716-446655440000");
+/// ```
+#[macro_export]
+macro_rules! msuuid {
+    ($s:literal) => {{
+        const ID: $crate::MicroShardUUID = match $crate::MicroShardUUID::parse($s) {
+            Ok(id) => id,
+            Err(_) => panic!(concat!("invalid MicroShard UUID literal: ", $s)),
+        };
+        ID
+    }};
+}
+
+/// Parses a strict ISO 8601 timestamp (`YYYY-MM-DDTHH:MM:SS[.ffffff]Z`, or
+/// with a `±HH:MM` offset instead of `Z`) into microseconds since the Unix
+/// Epoch, without minting an ID. Offsets are normalized to UTC.
+///
+/// This is the same parser `from_iso` uses; it is available without `gen`
+/// (and under `no_std`) for building time-range predicates.
+pub fn iso_to_micros(iso_str: &str) -> Result<u64, MicroShardError> {
+    parse_iso_strict(iso_str)
+}
+
+/// Like [`iso_to_micros`], but accepts the RFC 3339 variants emitted by
+/// Postgres, journald and common loggers. The strict parser remains the
+/// default everywhere else.
+///
+/// - a space (or lowercase `t`) between date and time, lowercase `z`
+/// - a `,` before the fraction, or fraction digits with no separator
+///   (Postgres `to_char(ts, 'YYYY-MM-DD HH24:MI:SSUS')`)
+/// - no zone designator (read as UTC), optionally one space before the zone,
+///   and `±HHMM` / `±HH` offsets besides `±HH:MM`
+///
+/// ```
+/// use microshard_uuid::{iso_to_micros, iso_to_micros_lenient};
+///
+/// let strict = iso_to_micros("2024-05-01T10:00:00.123456Z");
+/// assert_eq!(iso_to_micros_lenient("2024-05-01 10:00:00.123456+00"), strict);
+/// assert_eq!(iso_to_micros_lenient("2024-05-01 10:00:00123456"), strict);
+/// ```
+pub fn iso_to_micros_lenient(iso_str: &str) -> Result<u64, MicroShardError> {
+    parse_iso(iso_str, true)
+}
+
+/// Internal helper: Parses ISO string to microseconds.
+/// Contains all the strict validation logic (Zero-Dep).
+pub(crate) fn parse_iso_strict(iso_str: &str) -> Result<u64, MicroShardError> {
+    parse_iso(iso_str, false)
+}
+
+/// Internal helper: The shared ISO parser. `lenient` relaxes the separators,
+/// case and zone designator (see [`iso_to_micros_lenient`]); field ranges
+/// are validated the same way in both modes.
+fn parse_iso(iso_str: &str, lenient: bool) -> Result<u64, MicroShardError> {
+    // 1. Basic Length Check
+    // minimal: "2023-01-01T00:00:00Z" (20 chars), or 19 without the `Z` when lenient
+    let min_len = if lenient { 19 } else { 20 };
+    let b = iso_str.as_bytes();
+    if b.len() < min_len || !b[..19].is_ascii() {
+        return Err(MicroShardError::InvalidIsoFormat);
+    }
+
+    // 2. Separator Check (Strict ISO 8601)
+    // Expect: YYYY-MM-DDTHH:MM:SS...
+    let date_time_sep = match b[10] {
+        b'T' => true,
+        b't' | b' ' => lenient,
+        _ => false,
+    };
+    if b[4] != b'-' || b[7] != b'-' || !date_time_sep || b[13] != b':' || b[16] != b':' {
+        return Err(MicroShardError::InvalidIsoFormat);
+    }
+
+    // 3. Parse Numbers
+    let parse_chunk = |s: &str| -> Result<u32, MicroShardError> {
+        s.parse::<u32>()
+            .map_err(|_| MicroShardError::InvalidIsoFormat)
+    };
+
+    let year = iso_str[0..4]
+        .parse::<i32>()
+        .map_err(|_| MicroShardError::InvalidIsoFormat)?;
+    let month = parse_chunk(&iso_str[5..7])?;
+    let day = parse_chunk(&iso_str[8..10])?;
+    let hour = parse_chunk(&iso_str[11..13])?;
+    let min = parse_chunk(&iso_str[14..16])?;
+    let sec = parse_chunk(&iso_str[17..19])?;
+
+    // 4. Logical Range Validation
+    if !(1..=12).contains(&month) {
+        return Err(MicroShardError::InvalidIsoFormat);
+    }
+    if hour > 23 || min > 59 || sec > 60 {
+        // 60 allowed for leap seconds
+        return Err(MicroShardError::InvalidIsoFormat);
+    }
+
+    // Days in Month Check (Handles Feb 29)
+    let days_in_month = match month {
+        4 | 6 | 9 | 11 => 30,
+        2 => {
+            if is_leap(year) {
+                29
+            } else {
+                28
+            }
+        }
+        _ => 31,
+    };
+
+    if day < 1 || day > days_in_month {
+        return Err(MicroShardError::InvalidIsoFormat);
+    }
+
+    // 5. Parse Microseconds (Optional)
+    let mut micros = 0;
+    let mut rest = &b[19..];
+    let frac = match rest {
+        [b'.', frac @ ..] => Some(frac),
+        // `,` is the ISO 8601 alternative; Postgres `to_char(.., 'SSUS')`
+        // emits the digits with no separator at all.
+        [b',', frac @ ..] if lenient => Some(frac),
+        [c, ..] if lenient && c.is_ascii_digit() => Some(rest),
+        _ => None,
+    };
+    if let Some(frac) = frac {
+        let digits = frac.iter().take_while(|c| c.is_ascii_digit()).count();
+        let mut multiplier = 100_000;
+        for &c in &frac[..digits] {
+            if multiplier >= 1 {
+                micros += (c - b'0') as u32 * multiplier;
+                multiplier /= 10;
+            }
+        }
+        rest = &frac[digits..];
+    }
+
+    // 6. Time Zone Designator (`Z`, or a numeric offset shifted to UTC)
+    let offset_secs = parse_utc_offset(rest, lenient)?;
+
+    // 7. Convert to Unix Epoch
+    let days_since_epoch = date_to_days(year, month, day);
+    if days_since_epoch < 0 {
+        return Err(MicroShardError::InvalidIsoFormat);
+    }
+
+    let local_seconds =
+        (days_since_epoch as u64 * 86400) + (hour as u64 * 3600) + (min as u64 * 60) + sec as u64;
+    // A positive offset is ahead of UTC: subtract it to normalize.
+    let seconds = (local_seconds as i64)
+        .checked_sub(offset_secs)
+        .filter(|&s| s >= 0)
+        .ok_or(MicroShardError::InvalidIsoFormat)? as u64;
+
+    Ok(seconds * 1_000_000 + micros as u64)
+}
+
+/// Internal helper: Parses the zone designator after the seconds into an
+/// offset from UTC in seconds. `Z` (or no designator) is UTC. `lenient`
+/// also accepts `z`, one leading space, and `±HHMM` / `±HH` offsets.
+fn parse_utc_offset(rest: &[u8], lenient: bool) -> Result<i64, MicroShardError> {
+    let two_digits = |hi: u8, lo: u8| -> Option<i64> {
+        if hi.is_ascii_digit() && lo.is_ascii_digit() {
+            Some(((hi - b'0') * 10 + (lo - b'0')) as i64)
+        } else {
+            None
+        }
+    };
+    let rest = match rest {
+        [b' ', zone @ ..] if lenient => zone,
+        _ => rest,
+    };
+    let (sign, h1, h2, m1, m2) = match *rest {
+        [] | [b'Z'] => return Ok(0),
+        [b'z'] if lenient => return Ok(0),
+        [sign @ (b'+' | b'-'), h1, h2, b':', m1, m2] => (sign, h1, h2, m1, m2),
+        [sign @ (b'+' | b'-'), h1, h2, m1, m2] if lenient => (sign, h1, h2, m1, m2),
+        [sign @ (b'+' | b'-'), h1, h2] if lenient => (sign, h1, h2, b'0', b'0'),
+        [b'+' | b'-', ..] => return Err(MicroShardError::InvalidUtcOffset),
+        _ => return Err(MicroShardError::InvalidIsoFormat),
+    };
+    let hours = two_digits(h1, h2).filter(|&h| h <= 23);
+    let minutes = two_digits(m1, m2).filter(|&m| m <= 59);
+    match (hours, minutes) {
+        (Some(h), Some(m)) => {
+            let secs = h * 3600 + m * 60;
+            Ok(if sign == b'-' { -secs } else { secs })
+        }
+        _ => Err(MicroShardError::InvalidUtcOffset),
+    }
+}
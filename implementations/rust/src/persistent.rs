@@ -0,0 +1,187 @@
+//! Crash-safe monotonic generation backed by a small state file.
+//!
+//! A [`PersistentGenerator`] never issues a timestamp at or below one it
+//! issued before, even across process restarts and crashes. Instead of
+//! syncing every ID to disk, it *reserves* a window of time ahead: the state
+//! file records a high-water mark `H` that is always above every issued
+//! timestamp, and is rewritten (and fsynced) only when issuing reaches `H`.
+//! After a restart, generation resumes at `H` (above every previously issued
+//! timestamp).
+//!
+//! **File Layout (16 bytes):** high-water mark in Unix microseconds
+//! (u64 LE), format version (u32 LE), magic `"MSHW"`.
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::{now_micros, MicroShardGenerator, MicroShardUUID};
+
+/// Magic suffix identifying a state file.
+const MAGIC: &[u8; 4] = b"MSHW";
+/// Current on-disk format version.
+const FORMAT_VERSION: u32 = 1;
+/// Size of the state file in bytes.
+const STATE_LEN: usize = 16;
+
+/// Default reservation window: at most one fsync per second of issued time.
+pub const DEFAULT_RESERVE_AHEAD: Duration = Duration::from_secs(1);
+
+/// A monotonic generator whose ordering survives restarts.
+///
+/// IDs are strictly increasing for the lifetime of the state file, so their
+/// order can serve as an authoritative event order. Each shard needs its own
+/// state file, used by one generator at a time (this type does not lock it).
+///
+/// **Trade-off:** after a crash, up to one reservation window
+/// ([`DEFAULT_RESERVE_AHEAD`] unless changed) of timestamps is skipped, and
+/// if the clock restarts behind the mark, IDs are stamped at the mark (ahead
+/// of the clock) until it catches up.
+///
+/// ```no_run
+/// use microshard_uuid::PersistentGenerator;
+///
+/// let mut ids = PersistentGenerator::open("/var/lib/app/shard-7.state", 7)?;
+/// let id = ids.next()?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct PersistentGenerator {
+    inner: MicroShardGenerator,
+    path: PathBuf,
+    /// Persisted mark: every issued timestamp is below it.
+    reserved: u64,
+    /// Lowest timestamp this process may issue (the mark found on open).
+    floor: u64,
+    reserve_ahead: u64,
+}
+
+impl PersistentGenerator {
+    /// Opens (or starts) the state file at `path` for `shard_id`.
+    ///
+    /// A missing file starts from scratch; it is created on the first `next`.
+    ///
+    /// # Errors
+    /// - `ErrorKind::InvalidInput` for an invalid shard.
+    /// - `ErrorKind::InvalidData` if the file is not a state file.
+    /// - Any I/O error from reading the file.
+    pub fn open<P: AsRef<Path>>(path: P, shard_id: u32) -> io::Result<Self> {
+        let inner = MicroShardGenerator::monotonic(shard_id)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let path = path.as_ref().to_path_buf();
+        let mark = match fs::read(&path) {
+            Ok(bytes) => decode_state(&bytes)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(e),
+        };
+        Ok(Self {
+            inner,
+            path,
+            reserved: mark,
+            floor: mark,
+            reserve_ahead: DEFAULT_RESERVE_AHEAD.as_micros() as u64,
+        })
+    }
+
+    /// Sets how much time each checkpoint reserves ahead (minimum 1µs).
+    ///
+    /// Larger windows mean fewer fsyncs but more skipped time after a crash.
+    pub fn with_reserve_ahead(mut self, window: Duration) -> Self {
+        self.reserve_ahead = (window.as_micros().min(u64::MAX as u128) as u64).max(1);
+        self
+    }
+
+    /// The shard every ID from this generator is bound to.
+    pub fn shard_id(&self) -> u32 {
+        self.inner.shard_id()
+    }
+
+    /// The persisted high-water mark (Unix microseconds): every ID issued
+    /// so far, by this or any earlier process, is stamped below it.
+    pub fn high_water_mark(&self) -> u64 {
+        self.reserved
+    }
+
+    /// Generates the next ID, checkpointing first if it would reach the mark.
+    ///
+    /// Honours [`test_support::freeze_time`](crate::test_support::freeze_time).
+    /// An ID is only returned once a mark above it is on disk.
+    ///
+    /// # Errors
+    /// I/O errors from writing the state file, or `ErrorKind::Other` wrapping
+    /// a [`MicroShardError`](crate::MicroShardError) (e.g. `TimeOverflow`).
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> io::Result<MicroShardUUID> {
+        let now = now_micros().map_err(io::Error::other)?;
+        let id = self
+            .inner
+            .next_at(now.max(self.floor))
+            .map_err(io::Error::other)?;
+        let stamped = id.timestamp_micros();
+        if stamped >= self.reserved {
+            self.checkpoint(stamped.saturating_add(self.reserve_ahead))?;
+        }
+        Ok(id)
+    }
+
+    /// Internal: durably replaces the state file with `mark`.
+    fn checkpoint(&mut self, mark: u64) -> io::Result<()> {
+        // Write a sibling file and rename it over the old one, so a crash
+        // mid-write leaves either the old or the new mark, never a torn one.
+        let mut tmp_name = self.path.clone().into_os_string();
+        tmp_name.push(".tmp");
+        let tmp = PathBuf::from(tmp_name);
+
+        let mut file = File::create(&tmp)?;
+        file.write_all(&encode_state(mark))?;
+        file.sync_all()?;
+        fs::rename(&tmp, &self.path)?;
+        sync_parent_dir(&self.path)?;
+
+        self.reserved = mark;
+        Ok(())
+    }
+}
+
+/// Internal: the 16-byte state record.
+fn encode_state(mark: u64) -> [u8; STATE_LEN] {
+    let mut out = [0u8; STATE_LEN];
+    out[..8].copy_from_slice(&mark.to_le_bytes());
+    out[8..12].copy_from_slice(&FORMAT_VERSION.to_le_bytes());
+    out[12..].copy_from_slice(MAGIC);
+    out
+}
+
+/// Internal: parses and validates a state record.
+fn decode_state(bytes: &[u8]) -> io::Result<u64> {
+    let invalid = |msg| io::Error::new(io::ErrorKind::InvalidData, msg);
+    let record: &[u8; STATE_LEN] = bytes
+        .try_into()
+        .map_err(|_| invalid("state file has the wrong size"))?;
+    if &record[12..] != MAGIC {
+        return Err(invalid("not a MicroShard state file"));
+    }
+    let version = u32::from_le_bytes([record[8], record[9], record[10], record[11]]);
+    if version != FORMAT_VERSION {
+        return Err(invalid("unsupported state file version"));
+    }
+    let mut mark = [0u8; 8];
+    mark.copy_from_slice(&record[..8]);
+    Ok(u64::from_le_bytes(mark))
+}
+
+/// Internal: makes the rename itself durable (directory entry) on Unix.
+#[cfg(unix)]
+fn sync_parent_dir(path: &Path) -> io::Result<()> {
+    match path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        Some(dir) => File::open(dir)?.sync_all(),
+        None => File::open(".")?.sync_all(),
+    }
+}
+
+/// Internal: other platforms persist the rename with the file itself.
+#[cfg(not(unix))]
+fn sync_parent_dir(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
@@ -0,0 +1,84 @@
+//! Polars column helpers (enabled with the `polars` feature).
+//!
+//! IDs are held in `Binary` Series of the 16 big-endian bytes from
+//! [`MicroShardUUID::as_bytes`], which sort like the IDs themselves and are
+//! written to Parquet as plain byte arrays. The extractors decode a whole
+//! column in one pass, propagate nulls and keep the input's name, so a frame
+//! can be exploded into time and shard columns without a per-row UDF:
+//!
+//! ```
+//! use microshard_uuid::{polars as ms, MicroShardUUID};
+//! use polars_core::prelude::*;
+//!
+//! let ids = [MicroShardUUID::from_parts(1_714_557_600_000_000, 7, 42).unwrap()];
+//! let col = ms::to_series("id", &ids);
+//!
+//! let df = DataFrame::new_infer_height(vec![
+//!     ms::timestamp_micros(&col).unwrap().with_name("created_at".into()).into(),
+//!     ms::shard_id(&col).unwrap().with_name("shard".into()).into(),
+//!     col.into(),
+//! ])
+//! .unwrap();
+//! assert_eq!(df.column("shard").unwrap().u32().unwrap().get(0), Some(7));
+//! ```
+
+use polars_core::prelude::*;
+
+use crate::MicroShardUUID;
+
+/// Builds a non-null `Binary` Series named `name` from `ids`.
+pub fn to_series(name: &str, ids: &[MicroShardUUID]) -> Series {
+    let ca: BinaryChunked = ids.iter().map(|id| Some(id.as_bytes())).collect();
+    ca.with_name(name.into()).into_series()
+}
+
+/// Builds a nullable `Binary` Series named `name`; `None` becomes null.
+pub fn to_nullable_series(name: &str, ids: &[Option<MicroShardUUID>]) -> Series {
+    let ca: BinaryChunked = ids.iter().map(|id| id.map(|id| id.as_bytes())).collect();
+    ca.with_name(name.into()).into_series()
+}
+
+/// Decodes a `Binary` Series back into IDs, with `None` for nulls.
+///
+/// Fails on the same inputs as the extractors.
+pub fn from_series(ids: &Series) -> PolarsResult<Vec<Option<MicroShardUUID>>> {
+    decode_column(ids, |id| id)
+}
+
+/// Extracts creation times as a UTC `Datetime(Microseconds)` Series.
+pub fn timestamp_micros(ids: &Series) -> PolarsResult<Series> {
+    let values = decode_column(ids, |id| id.timestamp_micros() as i64)?;
+    let ca: Int64Chunked = values.into_iter().collect();
+    Ok(ca
+        .with_name(ids.name().clone())
+        .into_datetime(TimeUnit::Microseconds, Some(TimeZone::UTC))
+        .into_series())
+}
+
+/// Extracts the 32-bit Shard IDs as a `UInt32` Series.
+pub fn shard_id(ids: &Series) -> PolarsResult<Series> {
+    let values = decode_column(ids, |id| id.shard_id())?;
+    let ca: UInt32Chunked = values.into_iter().collect();
+    Ok(ca.with_name(ids.name().clone()).into_series())
+}
+
+/// Internal: validates each non-null value and maps it through `f`.
+///
+/// Fails on a Series that is not `Binary`, or holds a value that is not a
+/// 16-byte MicroShard ID.
+fn decode_column<T>(ids: &Series, f: impl Fn(MicroShardUUID) -> T) -> PolarsResult<Vec<Option<T>>> {
+    let mut values = Vec::with_capacity(ids.len());
+    for (i, value) in ids.binary()?.iter().enumerate() {
+        let Some(value) = value else {
+            values.push(None);
+            continue;
+        };
+        let bytes: [u8; 16] = value.try_into().map_err(
+            |_| polars_err!(ComputeError: "row {}: expected 16 bytes, got {}", i, value.len()),
+        )?;
+        let id = MicroShardUUID::from_bytes(bytes)
+            .map_err(|e| polars_err!(ComputeError: "row {}: {}", i, e))?;
+        values.push(Some(f(id)));
+    }
+    Ok(values)
+}
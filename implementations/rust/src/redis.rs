@@ -0,0 +1,78 @@
+//! Redis integration via the `redis` crate (enabled with the `redis` feature).
+//!
+//! `MicroShardUUID` implements `ToRedisArgs` and `FromRedisValue`, so IDs can
+//! be keys, values, hash fields and set members without a `to_string()` /
+//! `parse` round trip. They are written in the crate-wide [`STORAGE`] format:
+//!
+//! - [`Storage::Bytes`] (default): the 16 big-endian bytes, the most compact
+//!   key. Sorted sets with equal scores order them by creation time.
+//! - [`Storage::Text`] (the `redis-text` feature): the canonical
+//!   36-character string, readable in `redis-cli` and by other languages.
+//!
+//! Reads accept both formats. Keys are matched byte for byte, so every
+//! writer of a given key space must use the same format.
+//!
+//! ```
+//! use microshard_uuid::MicroShardUUID;
+//! use redis::{FromRedisValue, ToRedisArgs, Value};
+//!
+//! let id = MicroShardUUID::from_parts(1_714_557_600_000_000, 7, 42).unwrap();
+//! let reply = Value::BulkString(id.to_redis_args().remove(0));
+//! assert_eq!(MicroShardUUID::from_redis_value(reply).unwrap(), id);
+//! ```
+
+use ::redis::{FromRedisValue, ParsingError, RedisWrite, ToRedisArgs, ToSingleRedisArg, Value};
+
+use crate::MicroShardUUID;
+
+/// How `ToRedisArgs` writes a [`MicroShardUUID`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Storage {
+    /// 16 raw bytes (big-endian).
+    Bytes,
+    /// The canonical 36-character string.
+    Text,
+}
+
+/// The format `ToRedisArgs` writes in this build: [`Storage::Text`] with the
+/// `redis-text` feature, [`Storage::Bytes`] otherwise.
+pub const STORAGE: Storage = if cfg!(feature = "redis-text") {
+    Storage::Text
+} else {
+    Storage::Bytes
+};
+
+impl ToRedisArgs for MicroShardUUID {
+    fn write_redis_args<W>(&self, out: &mut W)
+    where
+        W: ?Sized + RedisWrite,
+    {
+        match STORAGE {
+            Storage::Bytes => out.write_arg(&self.as_bytes()),
+            Storage::Text => out.write_arg_fmt(self),
+        }
+    }
+}
+
+impl ToSingleRedisArg for MicroShardUUID {}
+
+/// Reads 16 raw bytes or a UUID string, whatever [`STORAGE`] is.
+impl FromRedisValue for MicroShardUUID {
+    fn from_redis_value_ref(v: &Value) -> Result<Self, ParsingError> {
+        let id = match v {
+            Value::BulkString(bytes) => match <[u8; 16]>::try_from(bytes.as_slice()) {
+                Ok(raw) => MicroShardUUID::from_bytes(raw),
+                Err(_) => MicroShardUUID::parse(core::str::from_utf8(bytes)?),
+            },
+            Value::SimpleString(text) => MicroShardUUID::parse(text),
+            _ => {
+                return Err(format!("Response type not MicroShardUUID compatible: {:?}", v).into())
+            }
+        };
+        id.map_err(|e| format!("{} (value was {:?})", e, v).into())
+    }
+
+    fn from_redis_value(v: Value) -> Result<Self, ParsingError> {
+        Self::from_redis_value_ref(&v)
+    }
+}
@@ -0,0 +1,288 @@
+//! Routing table mapping shard ranges to destinations.
+//!
+//! Typical use: sending each ID to the database, cluster, or region that
+//! owns its shard. Routes are inclusive `[first, last]` shard ranges that
+//! must cover every shard ID exactly once, so [`ShardRoutingTable::route`]
+//! always has an answer and a config with a hole or a double assignment is
+//! rejected when loaded rather than misrouting at runtime.
+//!
+//! With the `serde` feature the table loads from config as a list of
+//! routes, validated on deserialization:
+//!
+//! ```json
+//! [
+//!   { "first": 0,    "last": 1023,       "destination": "pg-eu" },
+//!   { "first": 1024, "last": 4294967295, "destination": "pg-us" }
+//! ]
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::MicroShardUUID;
+
+/// An inclusive shard range `[first, last]` and where it routes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Route<D> {
+    pub first: u32,
+    pub last: u32,
+    pub destination: D,
+}
+
+impl<D> Route<D> {
+    /// Creates a route. Validation happens in [`ShardRoutingTable::new`].
+    pub fn new(first: u32, last: u32, destination: D) -> Self {
+        Self {
+            first,
+            last,
+            destination,
+        }
+    }
+
+    /// Returns `true` if `shard` falls inside the route.
+    pub fn contains(&self, shard: u32) -> bool {
+        (self.first..=self.last).contains(&shard)
+    }
+}
+
+/// Why a set of routes does not form a valid [`ShardRoutingTable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoutingError {
+    /// No routes were given.
+    Empty,
+    /// A route's `first` is above its `last`.
+    InvertedRange { first: u32, last: u32 },
+    /// Two routes both claim shards starting at `first` (through `last`).
+    Overlap { first: u32, last: u32 },
+    /// No route covers shards `first..=last`.
+    Gap { first: u32, last: u32 },
+}
+
+impl fmt::Display for RoutingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Routing table has no routes"),
+            Self::InvertedRange { first, last } => {
+                write!(f, "Route {}..={} is inverted", first, last)
+            }
+            Self::Overlap { first, last } => {
+                write!(f, "Shards {}..={} are routed more than once", first, last)
+            }
+            Self::Gap { first, last } => write!(f, "Shards {}..={} have no route", first, last),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RoutingError {}
+
+/// A sorted set of [`Route`]s covering every shard ID exactly once.
+///
+/// ```
+/// use microshard_uuid::routing::{Route, ShardRoutingTable};
+/// use microshard_uuid::MicroShardUUID;
+///
+/// let table = ShardRoutingTable::new(vec![
+///     Route::new(1024, u32::MAX, "pg-us"),
+///     Route::new(0, 1023, "pg-eu"),
+/// ])
+/// .unwrap();
+/// let id = MicroShardUUID::from_micros(1_700_000_000_000_000, 7).unwrap();
+/// assert_eq!(*table.route(&id), "pg-eu");
+/// assert_eq!(*table.route_shard(5000), "pg-us");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShardRoutingTable<D> {
+    routes: Vec<Route<D>>,
+}
+
+impl<D> ShardRoutingTable<D> {
+    /// Builds the table, sorting routes by their first shard.
+    ///
+    /// # Errors
+    /// A [`RoutingError`] naming the first problem found, in shard order:
+    /// an inverted range, shards routed twice, or shards left unrouted.
+    pub fn new(mut routes: Vec<Route<D>>) -> Result<Self, RoutingError> {
+        routes.sort_by_key(|r| r.first);
+
+        if let Some(r) = routes.iter().find(|r| r.first > r.last) {
+            return Err(RoutingError::InvertedRange {
+                first: r.first,
+                last: r.last,
+            });
+        }
+        let (head, tail) = match (routes.first(), routes.last()) {
+            (Some(head), Some(tail)) => (head.first, tail.last),
+            _ => return Err(RoutingError::Empty),
+        };
+        if head > 0 {
+            return Err(RoutingError::Gap {
+                first: 0,
+                last: head - 1,
+            });
+        }
+        for pair in routes.windows(2) {
+            let (prev, next) = (&pair[0], &pair[1]);
+            if next.first <= prev.last {
+                return Err(RoutingError::Overlap {
+                    first: next.first,
+                    last: next.last.min(prev.last),
+                });
+            }
+            if next.first - prev.last > 1 {
+                return Err(RoutingError::Gap {
+                    first: prev.last + 1,
+                    last: next.first - 1,
+                });
+            }
+        }
+        if tail < u32::MAX {
+            return Err(RoutingError::Gap {
+                first: tail + 1,
+                last: u32::MAX,
+            });
+        }
+
+        Ok(Self { routes })
+    }
+
+    /// Returns the destination owning the ID's shard.
+    pub fn route(&self, id: &MicroShardUUID) -> &D {
+        self.route_shard(id.shard_id())
+    }
+
+    /// Returns the destination owning `shard`.
+    pub fn route_shard(&self, shard: u32) -> &D {
+        // Last route starting at or before `shard`; the first starts at 0
+        let idx = self.routes.partition_point(|r| r.first <= shard);
+        &self.routes[idx - 1].destination
+    }
+
+    /// Returns the routes in ascending shard order.
+    pub fn routes(&self) -> &[Route<D>] {
+        &self.routes
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use core::fmt;
+    use core::marker::PhantomData;
+
+    use alloc::vec::Vec;
+    use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+    use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+    use super::{Route, ShardRoutingTable};
+
+    const FIELDS: &[&str] = &["first", "last", "destination"];
+
+    impl<D: Serialize> Serialize for Route<D> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut route = serializer.serialize_struct("Route", 3)?;
+            route.serialize_field("first", &self.first)?;
+            route.serialize_field("last", &self.last)?;
+            route.serialize_field("destination", &self.destination)?;
+            route.end()
+        }
+    }
+
+    /// Internal: a field name of [`Route`].
+    enum Field {
+        First,
+        Last,
+        Destination,
+    }
+
+    impl<'de> Deserialize<'de> for Field {
+        fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+            struct FieldVisitor;
+
+            impl Visitor<'_> for FieldVisitor {
+                type Value = Field;
+
+                fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.write_str("`first`, `last` or `destination`")
+                }
+
+                fn visit_str<E: de::Error>(self, v: &str) -> Result<Field, E> {
+                    match v {
+                        "first" => Ok(Field::First),
+                        "last" => Ok(Field::Last),
+                        "destination" => Ok(Field::Destination),
+                        _ => Err(E::unknown_field(v, FIELDS)),
+                    }
+                }
+            }
+
+            deserializer.deserialize_identifier(FieldVisitor)
+        }
+    }
+
+    impl<'de, D: Deserialize<'de>> Deserialize<'de> for Route<D> {
+        fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+            struct RouteVisitor<D>(PhantomData<D>);
+
+            impl<'de, D: Deserialize<'de>> Visitor<'de> for RouteVisitor<D> {
+                type Value = Route<D>;
+
+                fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    f.write_str("a route with `first`, `last` and `destination`")
+                }
+
+                fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Route<D>, A::Error> {
+                    let first = seq
+                        .next_element()?
+                        .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+                    let last = seq
+                        .next_element()?
+                        .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+                    let destination = seq
+                        .next_element()?
+                        .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+                    Ok(Route::new(first, last, destination))
+                }
+
+                fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Route<D>, A::Error> {
+                    let (mut first, mut last, mut destination) = (None, None, None);
+                    while let Some(key) = map.next_key()? {
+                        match key {
+                            Field::First if first.is_none() => first = Some(map.next_value()?),
+                            Field::Last if last.is_none() => last = Some(map.next_value()?),
+                            Field::Destination if destination.is_none() => {
+                                destination = Some(map.next_value()?)
+                            }
+                            Field::First => return Err(de::Error::duplicate_field("first")),
+                            Field::Last => return Err(de::Error::duplicate_field("last")),
+                            Field::Destination => {
+                                return Err(de::Error::duplicate_field("destination"))
+                            }
+                        }
+                    }
+                    Ok(Route::new(
+                        first.ok_or_else(|| de::Error::missing_field("first"))?,
+                        last.ok_or_else(|| de::Error::missing_field("last"))?,
+                        destination.ok_or_else(|| de::Error::missing_field("destination"))?,
+                    ))
+                }
+            }
+
+            deserializer.deserialize_struct("Route", FIELDS, RouteVisitor(PhantomData))
+        }
+    }
+
+    /// Serialized as its list of routes.
+    impl<D: Serialize> Serialize for ShardRoutingTable<D> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.routes.serialize(serializer)
+        }
+    }
+
+    /// Deserialized from a list of routes, rejecting gaps and overlaps.
+    impl<'de, D: Deserialize<'de>> Deserialize<'de> for ShardRoutingTable<D> {
+        fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+            let routes = Vec::<Route<D>>::deserialize(deserializer)?;
+            ShardRoutingTable::new(routes).map_err(de::Error::custom)
+        }
+    }
+}
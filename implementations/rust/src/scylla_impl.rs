@@ -0,0 +1,67 @@
+//! ScyllaDB / Cassandra integration (enabled with the `scylla` feature).
+//!
+//! `MicroShardUUID` implements the `scylla` driver's `SerializeValue` and
+//! `DeserializeValue` (the successors of the 0.x `Value` / `FromCqlVal`
+//! traits), so it can be bound to and read from a CQL `uuid` column as its
+//! 16 big-endian bytes. The traits come from `scylla-cql-core`, which the
+//! `scylla` crate re-exports.
+//!
+//! A `uuid` clustering column orders by version first and the remaining
+//! bytes after that, which keeps MicroShard IDs in creation order. For a
+//! `timeuuid` column convert explicitly: `CqlTimeuuid::from(id)` uses
+//! [`MicroShardUUID::to_timeuuid`] and `MicroShardUUID::try_from(timeuuid)`
+//! uses [`MicroShardUUID::from_timeuuid`].
+//!
+//! Values read back must be valid MicroShard IDs; another UUID in the
+//! column is a deserialization error.
+
+use scylla_cql_core::deserialize::value::DeserializeValue;
+use scylla_cql_core::deserialize::{DeserializationError, FrameSlice, TypeCheckError};
+use scylla_cql_core::frame::response::result::ColumnType;
+use scylla_cql_core::serialize::value::SerializeValue;
+use scylla_cql_core::serialize::writers::{CellWriter, WrittenCellProof};
+use scylla_cql_core::serialize::SerializationError;
+use scylla_cql_core::value::CqlTimeuuid;
+use uuid::Uuid;
+
+use crate::{MicroShardError, MicroShardUUID};
+
+/// Written as a CQL `uuid`; other column types are rejected.
+impl SerializeValue for MicroShardUUID {
+    fn serialize<'b>(
+        &self,
+        typ: &ColumnType,
+        writer: CellWriter<'b>,
+    ) -> Result<WrittenCellProof<'b>, SerializationError> {
+        Uuid::from_bytes(self.as_bytes()).serialize(typ, writer)
+    }
+}
+
+/// Reads a CQL `uuid` column.
+impl<'frame, 'metadata> DeserializeValue<'frame, 'metadata> for MicroShardUUID {
+    fn type_check(typ: &ColumnType) -> Result<(), TypeCheckError> {
+        <Uuid as DeserializeValue>::type_check(typ)
+    }
+
+    fn deserialize(
+        typ: &'metadata ColumnType<'metadata>,
+        v: Option<FrameSlice<'frame>>,
+    ) -> Result<Self, DeserializationError> {
+        let uuid = <Uuid as DeserializeValue>::deserialize(typ, v)?;
+        MicroShardUUID::from_bytes(uuid.into_bytes()).map_err(DeserializationError::new)
+    }
+}
+
+impl From<MicroShardUUID> for CqlTimeuuid {
+    fn from(id: MicroShardUUID) -> Self {
+        CqlTimeuuid::from_u128(id.to_timeuuid())
+    }
+}
+
+impl TryFrom<CqlTimeuuid> for MicroShardUUID {
+    type Error = MicroShardError;
+
+    fn try_from(timeuuid: CqlTimeuuid) -> Result<Self, Self::Error> {
+        MicroShardUUID::from_timeuuid(timeuuid.as_u128())
+    }
+}
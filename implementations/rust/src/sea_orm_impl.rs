@@ -0,0 +1,65 @@
+//! SeaORM integration (enabled with the `sea-orm` feature).
+//!
+//! `MicroShardUUID` maps to SeaORM's `Uuid` column type, so it can be an
+//! entity field (including the primary key) and a query-builder value
+//! without a newtype. The driver stores it as PostgreSQL `uuid`, MySQL
+//! `binary(16)` or a SQLite `BLOB`, all in creation order.
+//!
+//! Values read back must be valid MicroShard IDs; another UUID in the
+//! column is a decoding error.
+
+use sea_orm::prelude::Uuid;
+use sea_orm::sea_query::{ArrayType, ColumnType, Nullable, Value, ValueType, ValueTypeErr};
+use sea_orm::{ColIdx, DbErr, QueryResult, TryFromU64, TryGetError, TryGetable};
+
+use crate::MicroShardUUID;
+
+impl From<MicroShardUUID> for Value {
+    fn from(id: MicroShardUUID) -> Self {
+        Value::Uuid(Some(Uuid::from_bytes(id.as_bytes())))
+    }
+}
+
+impl Nullable for MicroShardUUID {
+    fn null() -> Value {
+        Value::Uuid(None)
+    }
+}
+
+impl ValueType for MicroShardUUID {
+    fn try_from(v: Value) -> Result<Self, ValueTypeErr> {
+        match v {
+            Value::Uuid(Some(uuid)) => {
+                MicroShardUUID::from_bytes(uuid.into_bytes()).map_err(|_| ValueTypeErr)
+            }
+            _ => Err(ValueTypeErr),
+        }
+    }
+
+    fn type_name() -> String {
+        "MicroShardUUID".to_owned()
+    }
+
+    fn array_type() -> ArrayType {
+        ArrayType::Uuid
+    }
+
+    fn column_type() -> ColumnType {
+        ColumnType::Uuid
+    }
+}
+
+impl TryGetable for MicroShardUUID {
+    fn try_get_by<I: ColIdx>(res: &QueryResult, index: I) -> Result<Self, TryGetError> {
+        let uuid = Uuid::try_get_by(res, index)?;
+        MicroShardUUID::from_bytes(uuid.into_bytes())
+            .map_err(|e| TryGetError::DbErr(DbErr::Type(format!("{}: {}", uuid, e))))
+    }
+}
+
+/// IDs are never auto-increment keys; insert them explicitly.
+impl TryFromU64 for MicroShardUUID {
+    fn try_from_u64(_: u64) -> Result<Self, DbErr> {
+        Err(DbErr::ConvertFromU64("MicroShardUUID"))
+    }
+}
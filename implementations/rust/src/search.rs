@@ -0,0 +1,147 @@
+//! Binary search and range-scan helpers over sorted ID slices.
+//!
+//! All functions require `sorted` to be in ascending order (as produced by
+//! `slice::sort`). Because IDs sort by timestamp first, time-range lookups
+//! partition on the embedded timestamp directly instead of constructing
+//! boundary IDs by hand.
+//!
+//! For ordered containers (`BTreeMap`, `BTreeSet`, ordered KV stores),
+//! [`TimeRange`] and [`MicroShardUUID::range_for_micros`] build the boundary
+//! IDs for a wall-clock interval instead.
+
+use core::ops::{Bound, Range, RangeBounds};
+
+use crate::{MicroShardUUID, MAX_RANDOM, MAX_TIME_MICROS};
+
+/// Returns the position of `id` in `sorted`, if present.
+pub fn find(sorted: &[MicroShardUUID], id: &MicroShardUUID) -> Option<usize> {
+    sorted.binary_search(id).ok()
+}
+
+/// Returns the index of the first element `>= id`.
+pub fn lower_bound(sorted: &[MicroShardUUID], id: &MicroShardUUID) -> usize {
+    sorted.partition_point(|x| x < id)
+}
+
+/// Returns the index of the first element `> id`.
+pub fn upper_bound(sorted: &[MicroShardUUID], id: &MicroShardUUID) -> usize {
+    sorted.partition_point(|x| x <= id)
+}
+
+/// Returns the sub-slice of IDs whose timestamps fall in the half-open
+/// interval `[start_micros, end_micros)`.
+///
+/// Returns an empty slice when `end_micros <= start_micros`.
+pub fn slice_for_time_range(
+    sorted: &[MicroShardUUID],
+    start_micros: u64,
+    end_micros: u64,
+) -> &[MicroShardUUID] {
+    let lo = sorted.partition_point(|x| x.timestamp_micros() < start_micros);
+    let hi = sorted.partition_point(|x| x.timestamp_micros() < end_micros);
+    &sorted[lo..hi.max(lo)]
+}
+
+/// The IDs stamped within a microsecond interval, as a
+/// [`RangeBounds<MicroShardUUID>`] for ordered containers.
+///
+/// ```
+/// use microshard_uuid::search::TimeRange;
+/// use microshard_uuid::MicroShardUUID;
+/// use std::collections::BTreeMap;
+///
+/// let mut events = BTreeMap::new();
+/// for t in [100, 200, 300] {
+///     events.insert(MicroShardUUID::from_micros(t, 1).unwrap(), t);
+/// }
+/// let hits: Vec<_> = events.range(TimeRange::new(150..=300)).map(|(_, t)| *t).collect();
+/// assert_eq!(hits, [200, 300]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimeRange {
+    /// Inclusive bounds, or `None` if no valid ID falls in the interval.
+    ids: Option<(MicroShardUUID, MicroShardUUID)>,
+}
+
+impl TimeRange {
+    /// The range of IDs stamped within `micros` (any range syntax: `a..b`,
+    /// `a..=b`, `a..`, `..b`, `..`).
+    ///
+    /// Times past the 54-bit limit are clamped; an empty or inverted
+    /// interval gives an empty range (never a panicking one).
+    pub fn new<R: RangeBounds<u64>>(micros: R) -> Self {
+        let start = match micros.start_bound() {
+            Bound::Included(&t) => Some(t),
+            Bound::Excluded(&t) => t.checked_add(1),
+            Bound::Unbounded => Some(0),
+        };
+        let end = match micros.end_bound() {
+            Bound::Included(&t) => Some(t.min(MAX_TIME_MICROS)),
+            Bound::Excluded(&t) => t.checked_sub(1).map(|t| t.min(MAX_TIME_MICROS)),
+            Bound::Unbounded => Some(MAX_TIME_MICROS),
+        };
+        let ids = match (start, end) {
+            (Some(start), Some(end)) if start <= end => Some((
+                MicroShardUUID::compose(start, 0, 0),
+                MicroShardUUID::compose(end, u32::MAX, MAX_RANDOM),
+            )),
+            _ => None,
+        };
+        Self { ids }
+    }
+
+    /// The smallest and largest IDs in the range (inclusive), or `None` if
+    /// it is empty.
+    pub fn bounds(&self) -> Option<(MicroShardUUID, MicroShardUUID)> {
+        self.ids
+    }
+
+    /// Returns `true` if no ID falls in the range.
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_none()
+    }
+}
+
+impl RangeBounds<MicroShardUUID> for TimeRange {
+    fn start_bound(&self) -> Bound<&MicroShardUUID> {
+        match &self.ids {
+            Some((start, _)) => Bound::Included(start),
+            None => Bound::Included(&EMPTY_AT),
+        }
+    }
+
+    fn end_bound(&self) -> Bound<&MicroShardUUID> {
+        match &self.ids {
+            Some((_, end)) => Bound::Included(end),
+            // `[x, x)`: empty, and accepted by `BTreeMap::range`
+            None => Bound::Excluded(&EMPTY_AT),
+        }
+    }
+}
+
+/// Internal: the anchor of the empty range `[x, x)` (the smallest valid ID:
+/// only the version and variant bits set).
+const EMPTY_AT: MicroShardUUID = MicroShardUUID::from_u128_unchecked((0x8000 << 64) | (1 << 63));
+
+impl MicroShardUUID {
+    /// The inclusive ID bounds of the half-open interval
+    /// `[micros.start, micros.end)`, for `BETWEEN` predicates and ordered
+    /// store scans.
+    ///
+    /// An empty interval returns a pair with `lo > hi`, which matches
+    /// nothing. Times past the 54-bit limit are clamped.
+    ///
+    /// ```
+    /// use microshard_uuid::MicroShardUUID;
+    ///
+    /// let (lo, hi) = MicroShardUUID::range_for_micros(1_000..2_000);
+    /// assert_eq!(lo, MicroShardUUID::min_for_micros(1_000).unwrap());
+    /// assert_eq!(hi, MicroShardUUID::max_for_micros(1_999).unwrap());
+    /// ```
+    pub fn range_for_micros(micros: Range<u64>) -> (Self, Self) {
+        TimeRange::new(micros).bounds().unwrap_or((
+            Self::compose(MAX_TIME_MICROS, u32::MAX, MAX_RANDOM),
+            Self::compose(0, 0, 0),
+        ))
+    }
+}
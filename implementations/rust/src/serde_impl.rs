@@ -0,0 +1,94 @@
+//! `serde` support (enabled with the `serde` feature).
+//!
+//! Human-readable formats (JSON, YAML, TOML) use the canonical hyphenated
+//! string; binary formats (bincode, CBOR, MessagePack) use the 16 raw bytes.
+
+use core::fmt;
+
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use crate::any::AnyUuid;
+use crate::{MicroShardError, MicroShardUUID};
+
+impl Serialize for MicroShardUUID {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_u128(self.as_u128(), self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for MicroShardUUID {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_with(
+            deserializer,
+            MicroShardUUID::parse,
+            MicroShardUUID::from_bytes,
+        )
+    }
+}
+
+impl Serialize for AnyUuid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_u128(self.as_u128(), self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for AnyUuid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_with(deserializer, AnyUuid::parse, |b| Ok(AnyUuid::from_bytes(b)))
+    }
+}
+
+/// Internal: string form for human-readable formats, raw bytes otherwise.
+fn serialize_u128<S: Serializer>(
+    v: u128,
+    text: &dyn fmt::Display,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.collect_str(text)
+    } else {
+        serializer.serialize_bytes(&v.to_be_bytes())
+    }
+}
+
+/// Internal: accepts either representation regardless of the format.
+fn deserialize_with<'de, D, T>(
+    deserializer: D,
+    parse: fn(&str) -> Result<T, MicroShardError>,
+    from_bytes: fn([u8; 16]) -> Result<T, MicroShardError>,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct UuidVisitor<T> {
+        parse: fn(&str) -> Result<T, MicroShardError>,
+        from_bytes: fn([u8; 16]) -> Result<T, MicroShardError>,
+    }
+
+    impl<'de, T> Visitor<'de> for UuidVisitor<T> {
+        type Value = T;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a hyphenated UUID string or 16 bytes")
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> {
+            (self.parse)(v).map_err(E::custom)
+        }
+
+        fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<T, E> {
+            let bytes: [u8; 16] = v
+                .try_into()
+                .map_err(|_| E::invalid_length(v.len(), &self))?;
+            (self.from_bytes)(bytes).map_err(E::custom)
+        }
+    }
+
+    let visitor = UuidVisitor { parse, from_bytes };
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(visitor)
+    } else {
+        deserializer.deserialize_bytes(visitor)
+    }
+}
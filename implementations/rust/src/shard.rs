@@ -0,0 +1,259 @@
+//! Picking a process's shard ID from its environment.
+//!
+//! Every generator needs a shard ID that no other live process uses. These
+//! helpers derive one from facts that are already unique in a deployment,
+//! so fleets don't need hand-maintained per-host configuration.
+//! [`ShardLease`] hands out distinct shards to processes sharing a host.
+
+use std::env::{self, VarError};
+use std::fmt;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{self, Write};
+use std::net::{IpAddr, Ipv4Addr, UdpSocket};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+use crate::{validate_shard, MicroShardError, MAX_SHARD_ID};
+
+/// A validated shard ID.
+///
+/// ```no_run
+/// use microshard_uuid::{MicroShardUUID, ShardId};
+///
+/// // MICROSHARD_ID=42 ./service
+/// let shard = ShardId::from_env("MICROSHARD_ID").unwrap_or_else(|e| panic!("{}", e));
+/// let id = MicroShardUUID::generate(shard.get()).unwrap();
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
+pub struct ShardId(u32);
+
+impl ShardId {
+    /// Validates `shard_id`.
+    ///
+    /// # Errors
+    /// [`MicroShardError::InvalidShardId`] outside the valid range.
+    pub fn new(shard_id: u32) -> Result<Self, MicroShardError> {
+        validate_shard(shard_id)?;
+        Ok(Self(shard_id))
+    }
+
+    /// Reads, parses and range-checks the shard ID in environment variable
+    /// `var` (a decimal integer; surrounding whitespace is ignored).
+    ///
+    /// # Errors
+    /// A [`ShardIdError`] naming the variable and what was wrong with it.
+    pub fn from_env(var: &str) -> Result<Self, ShardIdError> {
+        let value = match env::var(var) {
+            Ok(value) => value,
+            Err(VarError::NotPresent) => return Err(ShardIdError::Missing(var.to_owned())),
+            Err(VarError::NotUnicode(raw)) => {
+                return Err(ShardIdError::Invalid {
+                    var: var.to_owned(),
+                    value: raw.to_string_lossy().into_owned(),
+                })
+            }
+        };
+        let invalid = |value: &str| ShardIdError::Invalid {
+            var: var.to_owned(),
+            value: value.to_owned(),
+        };
+        // Parse wider than u32 so an oversized number is reported as such.
+        let number = value.trim().parse::<u64>().map_err(|_| invalid(&value))?;
+        u32::try_from(number)
+            .ok()
+            .and_then(|id| Self::new(id).ok())
+            .ok_or(ShardIdError::OutOfRange {
+                var: var.to_owned(),
+                value: number,
+            })
+    }
+
+    /// The raw shard ID.
+    pub fn get(self) -> u32 {
+        self.0
+    }
+}
+
+impl From<ShardId> for u32 {
+    fn from(shard: ShardId) -> Self {
+        shard.0
+    }
+}
+
+impl fmt::Display for ShardId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Why [`ShardId::from_env`] could not produce a shard ID.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ShardIdError {
+    /// The variable is not set.
+    Missing(String),
+    /// The value is not a decimal integer.
+    Invalid { var: String, value: String },
+    /// The value is a number outside the valid shard range.
+    OutOfRange { var: String, value: u64 },
+}
+
+impl fmt::Display for ShardIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing(var) => write!(f, "environment variable {} is not set", var),
+            Self::Invalid { var, value } => write!(
+                f,
+                "{}={:?} is not a shard ID (expected an integer 0..={})",
+                var, value, MAX_SHARD_ID
+            ),
+            Self::OutOfRange { var, value } => write!(
+                f,
+                "{}={} is out of range (expected 0..={})",
+                var, value, MAX_SHARD_ID
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ShardIdError {}
+
+/// Derives a shard ID from an IP address, as many Snowflake deployments
+/// derive the worker ID.
+///
+/// - **IPv4:** the whole 32-bit address, so every host in a VPC gets a
+///   distinct shard.
+/// - **IPv6:** the low 32 bits (the end of the interface identifier).
+///
+/// ```
+/// use microshard_uuid::shard;
+/// use std::net::Ipv4Addr;
+///
+/// assert_eq!(shard::from_ip(Ipv4Addr::new(10, 0, 3, 7).into()), 0x0A00_0307);
+/// ```
+pub fn from_ip(addr: IpAddr) -> u32 {
+    match addr {
+        IpAddr::V4(v4) => u32::from(v4),
+        IpAddr::V6(v6) => u128::from(v6) as u32,
+    }
+}
+
+/// The primary non-loopback IPv4 address: the one the OS would use for
+/// outbound traffic.
+///
+/// Found by asking the routing table for a route to a TEST-NET address
+/// (`connect` on a UDP socket); no packet is sent.
+///
+/// # Errors
+/// Any I/O error from the lookup, or `ErrorKind::AddrNotAvailable` if the
+/// host has no route or only a loopback address.
+pub fn local_ipv4() -> io::Result<Ipv4Addr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+    socket.connect((Ipv4Addr::new(192, 0, 2, 1), 9))?;
+    match socket.local_addr()?.ip() {
+        IpAddr::V4(ip) if !ip.is_loopback() && !ip.is_unspecified() => Ok(ip),
+        _ => Err(io::Error::new(
+            io::ErrorKind::AddrNotAvailable,
+            "no non-loopback IPv4 address",
+        )),
+    }
+}
+
+/// The shard ID of this host: [`from_ip`] of [`local_ipv4`].
+///
+/// Unique as long as hosts have distinct IPv4 addresses and run one
+/// generating process each; stable for as long as the address is.
+///
+/// # Errors
+/// See [`local_ipv4`].
+pub fn from_local_ip() -> io::Result<u32> {
+    local_ipv4().map(|ip| from_ip(ip.into()))
+}
+
+/// An exclusive claim on one shard ID among the processes of a host.
+///
+/// Each shard in the range has a lock file, `shard-<id>.lock`, in a shared
+/// directory; [`acquire`](Self::acquire) takes the first one no other
+/// process holds. The OS lock is released when the lease is dropped, and
+/// also when the process dies, so a crashed worker never strands its shard.
+/// Lock files are left in place (removing them would race with a process
+/// that has just opened one); they hold the PID of the last holder.
+///
+/// ```no_run
+/// use microshard_uuid::{shard::ShardLease, MicroShardGenerator};
+///
+/// // Up to 64 workers on this host get shards 0..=63.
+/// let lease = ShardLease::acquire("/run/microshard", 0..=63)?;
+/// let mut ids = MicroShardGenerator::new(lease.shard_id()).unwrap();
+/// // ... keep `lease` alive as long as `ids` is in use.
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct ShardLease {
+    shard: ShardId,
+    path: PathBuf,
+    /// Holds the lock; closing it releases the shard.
+    _file: File,
+}
+
+impl ShardLease {
+    /// Claims the lowest shard in `range` not leased by another process,
+    /// creating `dir` if needed.
+    ///
+    /// # Errors
+    /// - `ErrorKind::InvalidInput` for an empty range.
+    /// - `ErrorKind::ResourceBusy` if every shard in the range is leased.
+    /// - Any I/O error from creating or locking the lock files.
+    pub fn acquire<P: AsRef<Path>>(dir: P, range: RangeInclusive<u32>) -> io::Result<Self> {
+        if range.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "empty shard range",
+            ));
+        }
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+        for id in range.clone() {
+            let shard =
+                ShardId::new(id).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            let path = dir.join(format!("shard-{}.lock", id));
+            let mut file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(&path)?;
+            match file.try_lock() {
+                Ok(()) => {}
+                Err(TryLockError::WouldBlock) => continue,
+                Err(TryLockError::Error(e)) => return Err(e),
+            }
+            // Best-effort breadcrumb for operators; the lock is what counts.
+            file.set_len(0)?;
+            writeln!(file, "{}", std::process::id())?;
+            return Ok(Self {
+                shard,
+                path,
+                _file: file,
+            });
+        }
+        Err(io::Error::new(
+            io::ErrorKind::ResourceBusy,
+            format!("all shards {}..={} are leased", range.start(), range.end()),
+        ))
+    }
+
+    /// The leased shard ID.
+    pub fn shard_id(&self) -> u32 {
+        self.shard.get()
+    }
+
+    /// The leased shard as a [`ShardId`].
+    pub fn shard(&self) -> ShardId {
+        self.shard
+    }
+
+    /// The lock file backing this lease.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
@@ -0,0 +1,197 @@
+//! Compile-time configurable bit layouts.
+//!
+//! [`ShardedUuid<TIME_BITS, SHARD_BITS>`](ShardedUuid) keeps the MicroShard
+//! structure (time, then shard, then random, around the fixed version and
+//! variant bits) but lets a deployment pick the split:
+//!
+//! - **Payload (122 bits):** `[Time (TIME_BITS)] [Shard (SHARD_BITS)] [Random (122 - TIME_BITS - SHARD_BITS)]`
+//! - **Placement:** the top 48 payload bits, `Ver=8`, the next 12, `Var=2`,
+//!   the last 62 (the same split as RFC 9562 UUIDv8).
+//!
+//! `ShardedUuid<54, 32>` is bit-for-bit a [`MicroShardUUID`]. An invalid
+//! split fails to compile as soon as the type is used:
+//!
+//! ```compile_fail
+//! // 64 + 64 leaves no room for random bits
+//! let _ = microshard_uuid::ShardedUuid::<64, 64>::from_parts(0, 0, 0);
+//! ```
+
+use core::fmt;
+#[cfg(feature = "parse")]
+use core::str::FromStr;
+
+use crate::layout::{self, Layout};
+#[cfg(feature = "parse")]
+use crate::parse_hyphenated;
+#[cfg(feature = "gen")]
+use crate::{now_micros, Xoshiro256StarStar};
+use crate::{write_hyphenated, MicroShardError, MicroShardUUID};
+
+/// Bits available for time, shard and random (128 minus version and variant).
+const PAYLOAD_BITS: u32 = 122;
+
+/// A sortable, sharded UUIDv8 with a compile-time `TIME_BITS`/`SHARD_BITS`
+/// split; the remaining `122 - TIME_BITS - SHARD_BITS` bits are random.
+///
+/// Time is microseconds since the Unix Epoch, so `TIME_BITS` sets the range
+/// (54 bits: until 2541; each bit fewer halves it). Requirements, checked at
+/// compile time: `1 <= TIME_BITS <= 64`, `SHARD_BITS <= 64`, and between 0
+/// and 64 random bits.
+///
+/// ```
+/// use microshard_uuid::ShardedUuid;
+///
+/// // Few tenants, more entropy: 8-bit shards, 60 random bits
+/// type TenantId = ShardedUuid<54, 8>;
+/// let id = TenantId::from_parts(1_700_000_000_000_000, 200, 7).unwrap();
+/// assert_eq!(id.shard_id(), 200);
+/// assert_eq!(TenantId::RANDOM_BITS, 60);
+///
+/// // Huge fleets: 40-bit shards, 28 random bits
+/// type DeviceId = ShardedUuid<54, 40>;
+/// let id = DeviceId::from_parts(1_700_000_000_000_000, 1 << 39, 7).unwrap();
+/// assert_eq!(id.shard_id(), 1 << 39);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
+pub struct ShardedUuid<const TIME_BITS: u32, const SHARD_BITS: u32>(u128);
+
+impl<const TIME_BITS: u32, const SHARD_BITS: u32> ShardedUuid<TIME_BITS, SHARD_BITS> {
+    /// Width of the random field.
+    pub const RANDOM_BITS: u32 = PAYLOAD_BITS - TIME_BITS - SHARD_BITS;
+    /// The same split as a runtime [`Layout`], for [`layout::encode`] /
+    /// [`layout::decode`]. Every constructor evaluates it, so an invalid
+    /// split is a compile error rather than a runtime one.
+    pub const LAYOUT: Layout = match Layout::new(TIME_BITS, SHARD_BITS, Self::RANDOM_BITS) {
+        Ok(layout) => layout,
+        Err(_) => panic!("invalid ShardedUuid split"),
+    };
+    /// Largest timestamp (µs since the Unix Epoch).
+    pub const MAX_TIME: u64 = Self::LAYOUT.max_time();
+    /// Largest shard id.
+    pub const MAX_SHARD: u64 = Self::LAYOUT.max_shard();
+    /// Largest random value.
+    pub const MAX_RANDOM: u64 = Self::LAYOUT.max_random();
+
+    // -------------------------------------------------------------------------
+    // Constructors
+    // -------------------------------------------------------------------------
+
+    /// Generates a new ID using the current system time.
+    #[cfg(feature = "gen")]
+    pub fn generate(shard_id: u64) -> Result<Self, MicroShardError> {
+        Self::from_micros(now_micros()?, shard_id)
+    }
+
+    /// Generates an ID stamped with `micros` (microseconds since the Unix Epoch).
+    #[cfg(feature = "gen")]
+    pub fn from_micros(micros: u64, shard_id: u64) -> Result<Self, MicroShardError> {
+        let random = if Self::RANDOM_BITS <= 36 {
+            Xoshiro256StarStar::next_36()
+        } else {
+            (Xoshiro256StarStar::next_36() << 36) | Xoshiro256StarStar::next_36()
+        };
+        Self::from_parts(micros, shard_id, random & Self::MAX_RANDOM)
+    }
+
+    /// Constructs an ID from explicit field values, with no PRNG involved.
+    ///
+    /// # Errors
+    /// - [`MicroShardError::TimeOverflow`] if `micros` exceeds [`MAX_TIME`](Self::MAX_TIME).
+    /// - [`MicroShardError::InvalidShardId`] if `shard_id` exceeds
+    ///   [`MAX_SHARD`](Self::MAX_SHARD) (carrying the maximum, saturated to `u32`).
+    /// - [`MicroShardError::InvalidRandom`] if `random` exceeds [`MAX_RANDOM`](Self::MAX_RANDOM).
+    pub const fn from_parts(
+        micros: u64,
+        shard_id: u64,
+        random: u64,
+    ) -> Result<Self, MicroShardError> {
+        match layout::encode(Self::LAYOUT, micros, shard_id, random) {
+            Ok(v) => Ok(Self(v)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Constructs an ID from a raw `u128`, checking for version 8, variant 2.
+    pub const fn from_u128(v: u128) -> Result<Self, MicroShardError> {
+        let _ = Self::LAYOUT;
+        let version = ((v >> 76) & 0xF) as u8;
+        if version != 8 {
+            return Err(MicroShardError::InvalidVersion(version));
+        }
+        let variant = ((v >> 62) & 0x3) as u8;
+        if variant != 2 {
+            return Err(MicroShardError::InvalidVariant(variant));
+        }
+        Ok(Self(v))
+    }
+
+    /// Constructs an ID from a 16-byte array (Big Endian).
+    pub fn from_bytes(bytes: [u8; 16]) -> Result<Self, MicroShardError> {
+        Self::from_u128(u128::from_be_bytes(bytes))
+    }
+
+    /// Parses the canonical hyphenated form (optionally `urn:uuid:`-prefixed).
+    #[cfg(feature = "parse")]
+    pub fn parse(s: &str) -> Result<Self, MicroShardError> {
+        Self::from_u128(parse_hyphenated(s)?)
+    }
+
+    // -------------------------------------------------------------------------
+    // Accessors
+    // -------------------------------------------------------------------------
+
+    /// Returns the raw `u128` value.
+    pub fn as_u128(&self) -> u128 {
+        self.0
+    }
+
+    /// Returns the ID as a 16-byte array (Big Endian).
+    pub fn as_bytes(&self) -> [u8; 16] {
+        self.0.to_be_bytes()
+    }
+
+    /// Extracts the creation time as microseconds since the Unix Epoch.
+    pub fn timestamp_micros(&self) -> u64 {
+        layout::decode_fields(Self::LAYOUT, self.0).micros
+    }
+
+    /// Extracts the shard id.
+    pub fn shard_id(&self) -> u64 {
+        layout::decode_fields(Self::LAYOUT, self.0).shard
+    }
+
+    /// Extracts the random field.
+    pub fn random(&self) -> u64 {
+        layout::decode_fields(Self::LAYOUT, self.0).random
+    }
+}
+
+impl<const TIME_BITS: u32, const SHARD_BITS: u32> fmt::Display
+    for ShardedUuid<TIME_BITS, SHARD_BITS>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hyphenated(self.0, f)
+    }
+}
+
+#[cfg(feature = "parse")]
+impl<const TIME_BITS: u32, const SHARD_BITS: u32> FromStr for ShardedUuid<TIME_BITS, SHARD_BITS> {
+    type Err = MicroShardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+// The default split is the MicroShard layout itself.
+impl From<MicroShardUUID> for ShardedUuid<54, 32> {
+    fn from(id: MicroShardUUID) -> Self {
+        Self(id.as_u128())
+    }
+}
+
+impl From<ShardedUuid<54, 32>> for MicroShardUUID {
+    fn from(id: ShardedUuid<54, 32>) -> Self {
+        Self(id.0)
+    }
+}
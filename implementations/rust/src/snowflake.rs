@@ -0,0 +1,118 @@
+//! Importing 64-bit Twitter Snowflake and Sonyflake IDs.
+//!
+//! A Snowflake is `[Sign (1 bit)] [Millis since epoch (41 bits)] [Worker (10 bits)] [Sequence (12 bits)]`.
+//! [`MicroShardUUID::from_snowflake`] maps it field by field:
+//!
+//! - **Time:** `(epoch + millis) × 1000` microseconds.
+//! - **Shard:** the 10-bit worker id (datacenter + worker in Twitter's
+//!   original split).
+//! - **Random:** the sequence in the top 12 bits (the same slot a monotonic
+//!   `MicroShardGenerator` uses), the low 24 bits zero.
+//!
+//! Snowflakes sort by (time, worker, sequence) and so do the resulting IDs,
+//! so a backfilled table keeps its key order. The mapping is deterministic:
+//! importing the same Snowflake twice gives the same ID.
+//!
+//! A Sonyflake is `[Sign (1 bit)] [10ms ticks since start (39 bits)] [Sequence (8 bits)] [Machine (16 bits)]`.
+//! [`MicroShardUUID::from_sonyflake`] maps it the same way: ticks to time,
+//! machine id to shard, sequence to the top 8 random bits. Order across
+//! 10ms ticks is kept; within a tick IDs group by machine rather than by
+//! sequence (Sonyflake puts the sequence first).
+
+use crate::{MicroShardError, MicroShardUUID, MAX_TIME_MICROS};
+
+/// Twitter's Snowflake epoch (2010-11-04T01:42:54.657Z) in Unix milliseconds.
+pub const TWITTER_EPOCH_MILLIS: u64 = 1_288_834_974_657;
+
+/// Discord's Snowflake epoch (2015-01-01T00:00:00Z) in Unix milliseconds.
+pub const DISCORD_EPOCH_MILLIS: u64 = 1_420_070_400_000;
+
+/// Sonyflake's default start time (2014-09-01T00:00:00Z) in Unix milliseconds.
+pub const SONYFLAKE_EPOCH_MILLIS: u64 = 1_409_529_600_000;
+
+const WORKER_SHIFT: u32 = 12;
+const TIME_SHIFT: u32 = 22;
+/// The sequence lands in the top 12 of the 36 random bits.
+const SEQUENCE_SHIFT: u32 = 24;
+const SONYFLAKE_TIME_SHIFT: u32 = 24;
+const SONYFLAKE_SEQUENCE_SHIFT: u32 = 16;
+/// The 8-bit Sonyflake sequence lands in the top 8 random bits.
+const SONYFLAKE_RANDOM_SHIFT: u32 = 28;
+/// Microseconds per Sonyflake tick (10ms).
+const SONYFLAKE_TICK_MICROS: u64 = 10_000;
+
+impl MicroShardUUID {
+    /// Re-encodes a Snowflake minted against `epoch_millis` (Unix
+    /// milliseconds, e.g. [`TWITTER_EPOCH_MILLIS`]) as a MicroShard ID.
+    ///
+    /// ```
+    /// use microshard_uuid::snowflake::TWITTER_EPOCH_MILLIS;
+    /// use microshard_uuid::MicroShardUUID;
+    ///
+    /// let id = MicroShardUUID::from_snowflake(1_541_815_603_606_036_480, TWITTER_EPOCH_MILLIS).unwrap();
+    /// assert_eq!(id.timestamp_micros(), 1_656_432_460_105_000);
+    /// assert_eq!(id.shard_id(), 378);
+    /// ```
+    ///
+    /// # Errors
+    /// - [`MicroShardError::InvalidEncoding`] if the sign bit is set.
+    /// - [`MicroShardError::TimeOverflow`] if the time is past the year 2541.
+    pub fn from_snowflake(id: u64, epoch_millis: u64) -> Result<Self, MicroShardError> {
+        if id >> 63 != 0 {
+            return Err(MicroShardError::InvalidEncoding);
+        }
+        let micros = epoch_millis
+            .checked_add(id >> TIME_SHIFT)
+            .and_then(|millis| millis.checked_mul(1_000))
+            .filter(|&micros| micros <= MAX_TIME_MICROS)
+            .ok_or(MicroShardError::TimeOverflow)?;
+        let worker = ((id >> WORKER_SHIFT) & 0x3FF) as u32;
+        let sequence = id & 0xFFF;
+        Ok(Self::compose(micros, worker, sequence << SEQUENCE_SHIFT))
+    }
+
+    /// Re-encodes a Sonyflake minted with the default start time
+    /// ([`SONYFLAKE_EPOCH_MILLIS`]) as a MicroShard ID.
+    ///
+    /// ```
+    /// use microshard_uuid::snowflake::SONYFLAKE_EPOCH_MILLIS;
+    /// use microshard_uuid::MicroShardUUID;
+    ///
+    /// let sonyflake = (100 << 24) | (3 << 16) | 0xBEEF; // 1s in, sequence 3, machine 0xBEEF
+    /// let id = MicroShardUUID::from_sonyflake(sonyflake).unwrap();
+    /// assert_eq!(id.timestamp_micros(), (SONYFLAKE_EPOCH_MILLIS + 1_000) * 1_000);
+    /// assert_eq!(id.shard_id(), 0xBEEF);
+    /// ```
+    ///
+    /// # Errors
+    /// - [`MicroShardError::InvalidEncoding`] if the sign bit is set.
+    pub fn from_sonyflake(id: u64) -> Result<Self, MicroShardError> {
+        Self::from_sonyflake_with_epoch(id, SONYFLAKE_EPOCH_MILLIS)
+    }
+
+    /// Like [`from_sonyflake`](Self::from_sonyflake), for producers configured
+    /// with a custom `StartTime` (`epoch_millis`, in Unix milliseconds).
+    ///
+    /// # Errors
+    /// - [`MicroShardError::InvalidEncoding`] if the sign bit is set.
+    /// - [`MicroShardError::TimeOverflow`] if the time is past the year 2541.
+    pub fn from_sonyflake_with_epoch(id: u64, epoch_millis: u64) -> Result<Self, MicroShardError> {
+        if id >> 63 != 0 {
+            return Err(MicroShardError::InvalidEncoding);
+        }
+        let micros = epoch_millis
+            .checked_mul(1_000)
+            .and_then(|epoch| {
+                epoch.checked_add((id >> SONYFLAKE_TIME_SHIFT) * SONYFLAKE_TICK_MICROS)
+            })
+            .filter(|&micros| micros <= MAX_TIME_MICROS)
+            .ok_or(MicroShardError::TimeOverflow)?;
+        let machine = (id & 0xFFFF) as u32;
+        let sequence = (id >> SONYFLAKE_SEQUENCE_SHIFT) & 0xFF;
+        Ok(Self::compose(
+            micros,
+            machine,
+            sequence << SONYFLAKE_RANDOM_SHIFT,
+        ))
+    }
+}
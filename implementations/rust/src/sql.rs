@@ -0,0 +1,310 @@
+//! SQL text generation for bulk backfills and in-database decoding.
+//!
+//! Emits correctly encoded literals for each supported database, matching the
+//! storage types used by the `db-extensions/` in this repository, and plain
+//! SQL expressions that extract the shard and creation time from an ID
+//! column without installing those extensions:
+//!
+//! ```
+//! use microshard_uuid::sql;
+//!
+//! let filter = format!(
+//!     "SELECT count(*) FROM events WHERE {} = 42",
+//!     sql::postgres_extract_shard_expr("id"),
+//! );
+//! assert!(filter.contains("(get_byte(uuid_send(id), 7)::bigint & 63) << 26"));
+//! ```
+//!
+//! The column is written verbatim, several times; pass a (quoted) column
+//! name rather than an expensive expression. The expressions only decode
+//! bits, so they return garbage for non-MicroShard UUIDs instead of failing.
+
+use alloc::format;
+use alloc::string::String;
+use core::fmt::{self, Write};
+use core::ops::Range;
+
+use crate::MicroShardUUID;
+
+/// Target SQL dialect and column storage type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dialect {
+    /// PostgreSQL `uuid`: `'xxxxxxxx-xxxx-...'::uuid`
+    Postgres,
+    /// PostgreSQL `bytea`: `decode('<32 hex>', 'hex')`
+    PostgresBytea,
+    /// MySQL `BINARY(16)`: `UNHEX('<32 hex>')`
+    MySql,
+    /// SQLite `BLOB`: `X'<32 hex>'`
+    Sqlite,
+    /// ClickHouse `UUID`: `toUUID('xxxxxxxx-xxxx-...')`
+    ClickHouse,
+    /// DuckDB `UUID`: `'xxxxxxxx-xxxx-...'::UUID`
+    DuckDb,
+}
+
+impl Dialect {
+    /// Writes the literal encoding of `id` for this dialect.
+    pub fn write_literal<W: Write>(&self, out: &mut W, id: &MicroShardUUID) -> fmt::Result {
+        let raw = id.as_u128();
+        match self {
+            Self::Postgres => write!(out, "'{}'::uuid", id),
+            Self::PostgresBytea => write!(out, "decode('{:032x}', 'hex')", raw),
+            Self::MySql => write!(out, "UNHEX('{:032x}')", raw),
+            Self::Sqlite => write!(out, "X'{:032x}'", raw),
+            Self::ClickHouse => write!(out, "toUUID('{}')", id),
+            Self::DuckDb => write!(out, "'{}'::UUID", id),
+        }
+    }
+
+    /// Returns the literal encoding of `id` for this dialect.
+    pub fn literal(&self, id: &MicroShardUUID) -> String {
+        let mut s = String::new();
+        // Writing to a String cannot fail
+        let _ = self.write_literal(&mut s, id);
+        s
+    }
+}
+
+/// One term of a bit extraction: byte index (0-based), mask, shift (positive
+/// is left, negative is right).
+type Term = (usize, u8, i8);
+
+/// `[time48][ver][time_low6]` in bytes 0-7.
+const MICROS_TERMS: [Term; 8] = [
+    (0, 0xFF, 46),
+    (1, 0xFF, 38),
+    (2, 0xFF, 30),
+    (3, 0xFF, 22),
+    (4, 0xFF, 14),
+    (5, 0xFF, 6),
+    (6, 0x0F, 2),
+    (7, 0xFF, -6),
+];
+
+/// `[shard_high6]` in byte 7, `[var][shard_low26]` in bytes 8-11.
+const SHARD_TERMS: [Term; 5] = [
+    (7, 0x3F, 26),
+    (8, 0x3F, 20),
+    (9, 0xFF, 12),
+    (10, 0xFF, 4),
+    (11, 0xFF, -4),
+];
+
+/// PostgreSQL expression (`bigint`) for the 32-bit shard of a `uuid` column.
+pub fn postgres_extract_shard_expr(column: &str) -> String {
+    bits_expr(&SHARD_TERMS, |out, i| {
+        write!(out, "get_byte(uuid_send({}), {})::bigint", column, i)
+    })
+}
+
+/// PostgreSQL expression (`bigint`) for the creation time of a `uuid`
+/// column in microseconds since the Unix Epoch.
+pub fn postgres_extract_micros_expr(column: &str) -> String {
+    bits_expr(&MICROS_TERMS, |out, i| {
+        write!(out, "get_byte(uuid_send({}), {})::bigint", column, i)
+    })
+}
+
+/// PostgreSQL expression (`timestamptz`) for the creation time of a `uuid`
+/// column, exact to the microsecond.
+pub fn postgres_extract_timestamp_expr(column: &str) -> String {
+    let micros = postgres_extract_micros_expr(column);
+    format!(
+        "(timestamptz 'epoch' + ({m} / 1000000) * interval '1 second' + ({m} % 1000000) * interval '1 microsecond')",
+        m = micros
+    )
+}
+
+/// MySQL expression (`BIGINT UNSIGNED`) for the 32-bit shard of a
+/// `BINARY(16)` column.
+pub fn mysql_extract_shard_expr(column: &str) -> String {
+    bits_expr(&SHARD_TERMS, |out, i| {
+        write!(out, "ORD(SUBSTRING({}, {}, 1))", column, i + 1)
+    })
+}
+
+/// MySQL expression (`BIGINT UNSIGNED`) for the creation time of a
+/// `BINARY(16)` column in microseconds since the Unix Epoch.
+pub fn mysql_extract_micros_expr(column: &str) -> String {
+    bits_expr(&MICROS_TERMS, |out, i| {
+        write!(out, "ORD(SUBSTRING({}, {}, 1))", column, i + 1)
+    })
+}
+
+/// MySQL expression (`DATETIME(6)`) for the creation time of a
+/// `BINARY(16)` column, in the session time zone like `FROM_UNIXTIME`.
+///
+/// Uses integer `DIV`/`MOD` like `microshard_get_timestamp` in
+/// `db-extensions/mysql`, avoiding `FROM_UNIXTIME`'s decimal rounding.
+pub fn mysql_extract_timestamp_expr(column: &str) -> String {
+    let micros = mysql_extract_micros_expr(column);
+    format!(
+        "DATE_ADD(DATE_ADD(FROM_UNIXTIME(0), INTERVAL ({m} DIV 1000000) SECOND), INTERVAL ({m} MOD 1000000) MICROSECOND)",
+        m = micros
+    )
+}
+
+/// ClickHouse expression (`UInt128`) for the time-ordered sort key of a
+/// `UUID` column.
+///
+/// ClickHouse compares `UUID`s by their second half first, so `ORDER BY id`
+/// and `id BETWEEN ...` do not follow creation time. Swapping the 64-bit
+/// halves of `reinterpretAsUInt128(id)` gives back the ID's big-endian
+/// value. This is the expansion of `microshard_sort_key` in
+/// `db-extensions/clickhouse`, so it matches a table's `ORDER BY` key and
+/// the primary index serves filters on it.
+pub fn clickhouse_sort_key_expr(column: &str) -> String {
+    format!(
+        "bitOr(bitShiftLeft(reinterpretAsUInt128({c}), 64), bitShiftRight(reinterpretAsUInt128({c}), 64))",
+        c = column
+    )
+}
+
+/// ClickHouse predicate matching IDs from `lo` to `hi` (inclusive) in
+/// creation order, comparing the [sort key](clickhouse_sort_key_expr) with
+/// `UInt128` literals.
+pub fn clickhouse_between(column: &str, lo: &MicroShardUUID, hi: &MicroShardUUID) -> String {
+    format!(
+        "{} BETWEEN toUInt128('{}') AND toUInt128('{}')",
+        clickhouse_sort_key_expr(column),
+        lo.as_u128(),
+        hi.as_u128()
+    )
+}
+
+/// ClickHouse predicate matching IDs created in `[micros.start, micros.end)`
+/// (see [`MicroShardUUID::range_for_micros`]).
+///
+/// ```
+/// use microshard_uuid::sql;
+///
+/// let filter = sql::clickhouse_time_range("id", 1_714_521_600_000_000..1_714_608_000_000_000);
+/// assert!(filter.ends_with(
+///     "BETWEEN toUInt128('32386397351984751285006810685842128896') \
+///      AND toUInt128('32388029401840097652601717253308678143')"
+/// ));
+/// ```
+pub fn clickhouse_time_range(column: &str, micros: Range<u64>) -> String {
+    let (lo, hi) = MicroShardUUID::range_for_micros(micros);
+    clickhouse_between(column, &lo, &hi)
+}
+
+/// Internal: ORs the masked and shifted bytes of `terms` together. `byte`
+/// writes the SQL for one byte of the column, as an integer.
+fn bits_expr<F>(terms: &[Term], byte: F) -> String
+where
+    F: Fn(&mut String, usize) -> fmt::Result,
+{
+    let mut out = String::from("(");
+    for (n, &(index, mask, shift)) in terms.iter().enumerate() {
+        if n > 0 {
+            out.push_str(" | ");
+        }
+        // Every operation is parenthesized: PostgreSQL gives `&`, `|` and
+        // the shifts equal precedence, MySQL does not.
+        out.push('(');
+        // Writing to a String cannot fail
+        if mask != 0xFF {
+            out.push('(');
+            let _ = byte(&mut out, index);
+            let _ = write!(out, " & {})", mask);
+        } else {
+            let _ = byte(&mut out, index);
+        }
+        let _ = match shift {
+            0 => Ok(()),
+            s if s > 0 => write!(out, " << {}", s),
+            s => write!(out, " >> {}", -s),
+        };
+        out.push(')');
+    }
+    out.push(')');
+    out
+}
+
+/// Writes multi-row `VALUES` clauses, one per chunk of at most `chunk_size`
+/// rows, each on its own line:
+///
+/// ```text
+/// VALUES (X'...'), (X'...'), (X'...')
+/// VALUES (X'...')
+/// ```
+///
+/// A `chunk_size` of 0 writes a single clause. Nothing is written for an
+/// empty input.
+pub fn write_values<W, I>(out: &mut W, ids: I, dialect: Dialect, chunk_size: usize) -> fmt::Result
+where
+    W: Write,
+    I: IntoIterator<Item = MicroShardUUID>,
+{
+    write_chunks(out, ids, dialect, chunk_size, "", |out| {
+        out.write_str("VALUES ")
+    })
+}
+
+/// Writes complete `INSERT INTO <table> (<column>) VALUES ...;` statements,
+/// one per chunk of at most `chunk_size` rows.
+///
+/// `table` and `column` are written verbatim; quote them yourself if needed.
+pub fn write_inserts<W, I>(
+    out: &mut W,
+    table: &str,
+    column: &str,
+    ids: I,
+    dialect: Dialect,
+    chunk_size: usize,
+) -> fmt::Result
+where
+    W: Write,
+    I: IntoIterator<Item = MicroShardUUID>,
+{
+    write_chunks(out, ids, dialect, chunk_size, ";", |out| {
+        write!(out, "INSERT INTO {} ({}) VALUES ", table, column)
+    })
+}
+
+/// Internal: shared chunking loop. `open` writes each chunk's prefix and
+/// `terminator` is appended to each chunk before its newline.
+fn write_chunks<W, I, F>(
+    out: &mut W,
+    ids: I,
+    dialect: Dialect,
+    chunk_size: usize,
+    terminator: &str,
+    open: F,
+) -> fmt::Result
+where
+    W: Write,
+    I: IntoIterator<Item = MicroShardUUID>,
+    F: Fn(&mut W) -> fmt::Result,
+{
+    let limit = if chunk_size == 0 {
+        usize::MAX
+    } else {
+        chunk_size
+    };
+
+    let mut in_chunk = 0;
+    for id in ids {
+        if in_chunk == limit {
+            out.write_str(terminator)?;
+            out.write_str("\n")?;
+            in_chunk = 0;
+        }
+        if in_chunk == 0 {
+            open(out)?;
+        } else {
+            out.write_str(", ")?;
+        }
+        out.write_char('(')?;
+        dialect.write_literal(out, &id)?;
+        out.write_char(')')?;
+        in_chunk += 1;
+    }
+    if in_chunk > 0 {
+        out.write_str(terminator)?;
+        out.write_str("\n")?;
+    }
+    Ok(())
+}
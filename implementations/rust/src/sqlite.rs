@@ -0,0 +1,157 @@
+//! SQLite integration via `rusqlite` (enabled with the `rusqlite` feature).
+//!
+//! `MicroShardUUID` implements `ToSql` and `FromSql`, so IDs bind and read
+//! directly. They are written in the crate-wide [`STORAGE`] format:
+//!
+//! - [`Storage::Blob`] (default): the 16 big-endian bytes. Compact, and
+//!   `ORDER BY id` is creation order.
+//! - [`Storage::Text`] (the `rusqlite-text` feature): the canonical
+//!   36-character string. Readable in the `sqlite3` shell, and still sorts
+//!   by creation time.
+//!
+//! Reads accept both formats, so a database can be migrated in place. Keep
+//! one format per column all the same: SQLite never considers a `BLOB`
+//! equal to a `TEXT`, so `WHERE id = ?` only matches IDs stored the same way.
+//!
+//! [`register_functions`] installs SQL functions for ad-hoc analysis of ID
+//! columns, as 16-byte `BLOB`s (the storage used by `db-extensions/sqlite`)
+//! or canonical `TEXT`:
+//!
+//! | Function | Arguments | Return | Description |
+//! | :--- | :--- | :--- | :--- |
+//! | `microshard_time` | `id` | `INT` | Creation time as Unix microseconds. |
+//! | `microshard_shard` | `id` | `INT` | The 32-bit Shard ID. |
+//! | `microshard_range_start` | `start_iso` | ID | The smallest ID created at `start`. |
+//! | `microshard_range_end` | `end_iso` | ID | The smallest ID created at `end` (an exclusive bound). |
+//! | `microshard_range` | `id`, `start_iso`, `end_iso` | `INT` | `1` if the creation time is in `[start, end)`, else `0`. |
+//!
+//! The bounds are strict ISO 8601 strings as accepted by `from_iso`. To
+//! select a time window, compare the column against the range bounds, which
+//! SQLite can answer from an index on `id`:
+//!
+//! ```sql
+//! SELECT * FROM events
+//! WHERE id >= microshard_range_start('2024-05-01T00:00:00Z')
+//!   AND id <  microshard_range_end('2024-06-01T00:00:00Z');
+//! ```
+//!
+//! The bounds are written in the [`STORAGE`] format, so they compare
+//! correctly against columns written by this build. `microshard_range` is
+//! the same test as a per-row predicate; SQLite cannot use an index through
+//! it, so it scans the table. All decoders return `NULL` for a `NULL` ID.
+
+use std::os::raw::c_int;
+use std::sync::Arc;
+
+use rusqlite::functions::{Context, FunctionFlags};
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, Value, ValueRef};
+use rusqlite::{Connection, Error, Result};
+
+use crate::{parse_iso_strict, MicroShardError, MicroShardUUID};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// How `ToSql` writes a [`MicroShardUUID`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Storage {
+    /// A 16-byte `BLOB` (big-endian).
+    Blob,
+    /// The canonical 36-character `TEXT` form.
+    Text,
+}
+
+/// The format `ToSql` writes in this build: [`Storage::Text`] with the
+/// `rusqlite-text` feature, [`Storage::Blob`] otherwise.
+pub const STORAGE: Storage = if cfg!(feature = "rusqlite-text") {
+    Storage::Text
+} else {
+    Storage::Blob
+};
+
+impl ToSql for MicroShardUUID {
+    fn to_sql(&self) -> Result<ToSqlOutput<'_>> {
+        let value = match STORAGE {
+            Storage::Blob => Value::Blob(self.as_bytes().to_vec()),
+            Storage::Text => Value::Text(self.to_string()),
+        };
+        Ok(ToSqlOutput::Owned(value))
+    }
+}
+
+/// Reads a 16-byte `BLOB` or a UUID string, whatever [`STORAGE`] is.
+impl FromSql for MicroShardUUID {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        match value {
+            ValueRef::Blob(blob) => {
+                let bytes: [u8; 16] =
+                    blob.try_into().map_err(|_| FromSqlError::InvalidBlobSize {
+                        expected_size: 16,
+                        blob_size: blob.len(),
+                    })?;
+                MicroShardUUID::from_bytes(bytes).map_err(FromSqlError::other)
+            }
+            ValueRef::Text(text) => {
+                let text = core::str::from_utf8(text).map_err(FromSqlError::Utf8Error)?;
+                MicroShardUUID::parse(text).map_err(FromSqlError::other)
+            }
+            _ => Err(FromSqlError::InvalidType),
+        }
+    }
+}
+
+/// Registers the `microshard_*` SQL functions on `conn`.
+pub fn register_functions(conn: &Connection) -> Result<()> {
+    let flags = FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC;
+
+    conn.create_scalar_function("microshard_time", 1, flags, |ctx| {
+        Ok(id_arg(ctx, 0)?.map(|id| id.timestamp_micros() as i64))
+    })?;
+
+    conn.create_scalar_function("microshard_shard", 1, flags, |ctx| {
+        Ok(id_arg(ctx, 0)?.map(|id| id.shard_id() as i64))
+    })?;
+
+    conn.create_scalar_function("microshard_range_start", 1, flags, |ctx| {
+        MicroShardUUID::min_for_micros(iso_arg(ctx, 0)?).map_err(user_error)
+    })?;
+
+    // `id < min_for_micros(end)` is exactly `timestamp < end`
+    conn.create_scalar_function("microshard_range_end", 1, flags, |ctx| {
+        MicroShardUUID::min_for_micros(iso_arg(ctx, 0)?).map_err(user_error)
+    })?;
+
+    conn.create_scalar_function("microshard_range", 3, flags, |ctx| {
+        let Some(id) = id_arg(ctx, 0)? else {
+            return Ok(None);
+        };
+        let start = *iso_aux(ctx, 1)?;
+        let end = *iso_aux(ctx, 2)?;
+        let ts = id.timestamp_micros();
+        Ok(Some((ts >= start && ts < end) as i64))
+    })?;
+
+    Ok(())
+}
+
+/// Internal: reads a nullable ID argument (BLOB or TEXT) as a validated ID.
+fn id_arg(ctx: &Context, idx: usize) -> Result<Option<MicroShardUUID>> {
+    ctx.get(idx)
+}
+
+/// Internal: reads an ISO 8601 TEXT argument as Unix microseconds.
+fn iso_arg(ctx: &Context, idx: usize) -> Result<u64> {
+    let iso: String = ctx.get(idx)?;
+    parse_iso_strict(&iso).map_err(user_error)
+}
+
+/// Internal: like [`iso_arg`], but parsed once per statement when the
+/// argument is a constant (SQLite keeps the result as auxiliary data).
+fn iso_aux(ctx: &Context, idx: usize) -> Result<Arc<u64>> {
+    ctx.get_or_create_aux(idx as c_int, |value| -> Result<u64, BoxError> {
+        Ok(parse_iso_strict(value.as_str()?)?)
+    })
+}
+
+fn user_error(e: MicroShardError) -> Error {
+    Error::UserFunctionError(Box::new(e))
+}
@@ -0,0 +1,149 @@
+//! `sqlx` integration (enabled with the `sqlx-postgres`, `sqlx-mysql` and
+//! `sqlx-sqlite` features, or all three with `sqlx`).
+//!
+//! | Database | Column type | Wire format |
+//! | :--- | :--- | :--- |
+//! | PostgreSQL | `uuid` | 16 bytes (binary) or canonical text |
+//! | MySQL | `BINARY(16)` | 16 bytes |
+//! | SQLite | `BLOB` | 16 bytes |
+//!
+//! IDs are always written as their 16 big-endian bytes, which keeps index
+//! order equal to creation order. MySQL and SQLite also decode the canonical
+//! 36-character string, for columns that were filled as text.
+
+/// Internal: decodes 16 raw bytes, or a UUID string stored as text.
+#[cfg(any(feature = "sqlx-mysql", feature = "sqlx-sqlite"))]
+fn from_column(bytes: &[u8]) -> Result<crate::MicroShardUUID, sqlx_core::error::BoxDynError> {
+    use crate::MicroShardUUID;
+
+    match <[u8; 16]>::try_from(bytes) {
+        Ok(raw) => Ok(MicroShardUUID::from_bytes(raw)?),
+        Err(_) => Ok(MicroShardUUID::parse(core::str::from_utf8(bytes)?)?),
+    }
+}
+
+#[cfg(feature = "sqlx-postgres")]
+mod postgres {
+    use sqlx_core::decode::Decode;
+    use sqlx_core::encode::{Encode, IsNull};
+    use sqlx_core::error::BoxDynError;
+    use sqlx_core::types::Type;
+    use sqlx_postgres::types::Oid;
+    use sqlx_postgres::{
+        PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres,
+    };
+
+    use crate::MicroShardUUID;
+
+    /// Built-in OIDs of `uuid` and `uuid[]` (stable across servers).
+    const UUID_OID: Oid = Oid(2950);
+    const UUID_ARRAY_OID: Oid = Oid(2951);
+
+    impl Type<Postgres> for MicroShardUUID {
+        fn type_info() -> PgTypeInfo {
+            PgTypeInfo::with_oid(UUID_OID)
+        }
+    }
+
+    impl PgHasArrayType for MicroShardUUID {
+        fn array_type_info() -> PgTypeInfo {
+            PgTypeInfo::with_oid(UUID_ARRAY_OID)
+        }
+    }
+
+    impl Encode<'_, Postgres> for MicroShardUUID {
+        fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+            buf.extend_from_slice(&self.as_bytes());
+            Ok(IsNull::No)
+        }
+
+        fn size_hint(&self) -> usize {
+            16
+        }
+    }
+
+    impl Decode<'_, Postgres> for MicroShardUUID {
+        fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+            let id = match value.format() {
+                PgValueFormat::Binary => {
+                    let bytes: [u8; 16] = value.as_bytes()?.try_into()?;
+                    MicroShardUUID::from_bytes(bytes)?
+                }
+                PgValueFormat::Text => MicroShardUUID::parse(value.as_str()?)?,
+            };
+            Ok(id)
+        }
+    }
+}
+
+#[cfg(feature = "sqlx-mysql")]
+mod mysql {
+    use sqlx_core::decode::Decode;
+    use sqlx_core::encode::{Encode, IsNull};
+    use sqlx_core::error::BoxDynError;
+    use sqlx_core::types::Type;
+    use sqlx_mysql::{MySql, MySqlTypeInfo, MySqlValueRef};
+
+    use super::from_column;
+    use crate::MicroShardUUID;
+
+    impl Type<MySql> for MicroShardUUID {
+        fn type_info() -> MySqlTypeInfo {
+            <[u8] as Type<MySql>>::type_info()
+        }
+
+        fn compatible(ty: &MySqlTypeInfo) -> bool {
+            <[u8] as Type<MySql>>::compatible(ty)
+        }
+    }
+
+    impl Encode<'_, MySql> for MicroShardUUID {
+        fn encode_by_ref(&self, buf: &mut Vec<u8>) -> Result<IsNull, BoxDynError> {
+            <&[u8] as Encode<MySql>>::encode(&self.as_bytes(), buf)
+        }
+
+        fn size_hint(&self) -> usize {
+            17
+        }
+    }
+
+    impl<'r> Decode<'r, MySql> for MicroShardUUID {
+        fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
+            from_column(<&[u8] as Decode<MySql>>::decode(value)?)
+        }
+    }
+}
+
+#[cfg(feature = "sqlx-sqlite")]
+mod sqlite {
+    use sqlx_core::decode::Decode;
+    use sqlx_core::encode::{Encode, IsNull};
+    use sqlx_core::error::BoxDynError;
+    use sqlx_core::types::Type;
+    use sqlx_sqlite::{Sqlite, SqliteArgumentsBuffer, SqliteTypeInfo, SqliteValueRef};
+
+    use super::from_column;
+    use crate::MicroShardUUID;
+
+    impl Type<Sqlite> for MicroShardUUID {
+        fn type_info() -> SqliteTypeInfo {
+            <[u8] as Type<Sqlite>>::type_info()
+        }
+
+        fn compatible(ty: &SqliteTypeInfo) -> bool {
+            <[u8] as Type<Sqlite>>::compatible(ty)
+        }
+    }
+
+    impl Encode<'_, Sqlite> for MicroShardUUID {
+        fn encode_by_ref(&self, buf: &mut SqliteArgumentsBuffer) -> Result<IsNull, BoxDynError> {
+            <&[u8] as Encode<Sqlite>>::encode(&self.as_bytes(), buf)
+        }
+    }
+
+    impl<'r> Decode<'r, Sqlite> for MicroShardUUID {
+        fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+            from_column(<&[u8] as Decode<Sqlite>>::decode(value)?)
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! Multi-threaded collision stress test.
+//!
+//! [`run`] hammers a generator configuration from many threads for a fixed
+//! time and reports duplicates, ordering violations, errors, and throughput.
+//! It is the executable answer to "is this configuration safe at our load?"
+//! and is meant to be run in CI and on target hardware:
+//!
+//! ```no_run
+//! use microshard_uuid::stress::{self, GeneratorConfig};
+//! use std::time::Duration;
+//!
+//! let report = stress::run(0, Duration::from_secs(5), GeneratorConfig::Shared { shard_id: 1 });
+//! assert!(report.is_clean(), "{}", report);
+//! ```
+//!
+//! Every ID is retained until the run ends (16 bytes each), so size the
+//! duration to the memory available.
+
+use std::fmt;
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{MicroShardError, MicroShardUUID, UniqueGenerator};
+
+/// How often (in IDs) each worker checks the deadline.
+const CLOCK_CHECK_INTERVAL: u64 = 1024;
+
+/// The generator configuration under test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeneratorConfig {
+    /// Every thread calls [`MicroShardUUID::generate`] independently.
+    /// Uniqueness rests on the 36-bit random field alone, and IDs minted
+    /// within the same microsecond are not ordered.
+    Stateless { shard_id: u32 },
+    /// All threads share one [`UniqueGenerator`]. Expected to report zero
+    /// duplicates and zero ordering violations.
+    Shared { shard_id: u32 },
+}
+
+/// Outcome of a [`run`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StressReport {
+    /// Number of worker threads used.
+    pub threads: usize,
+    /// Wall time actually spent generating.
+    pub elapsed: Duration,
+    /// Number of IDs successfully generated.
+    pub generated: u64,
+    /// Number of IDs equal to an earlier one (across all threads).
+    pub duplicates: u64,
+    /// Number of IDs not greater than the previous ID on the same thread.
+    pub ordering_violations: u64,
+    /// Number of generation calls that returned an error.
+    pub errors: u64,
+}
+
+impl StressReport {
+    /// IDs generated per second across all threads.
+    pub fn throughput(&self) -> f64 {
+        self.generated as f64 / self.elapsed.as_secs_f64().max(f64::MIN_POSITIVE)
+    }
+
+    /// Returns `true` if no duplicates, ordering violations, or errors occurred.
+    pub fn is_clean(&self) -> bool {
+        self.duplicates == 0 && self.ordering_violations == 0 && self.errors == 0
+    }
+}
+
+impl fmt::Display for StressReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} IDs on {} threads in {:.3}s ({:.0}/s): {} duplicates, {} ordering violations, {} errors",
+            self.generated,
+            self.threads,
+            self.elapsed.as_secs_f64(),
+            self.throughput(),
+            self.duplicates,
+            self.ordering_violations,
+            self.errors
+        )
+    }
+}
+
+/// Internal: what one worker thread collected.
+struct WorkerResult {
+    ids: Vec<u128>,
+    ordering_violations: u64,
+    errors: u64,
+}
+
+/// Runs `config` on `threads` threads for `duration`.
+///
+/// `threads == 0` uses one thread per available core.
+///
+/// # Panics
+/// Panics if the shard ID in `config` is invalid or a worker thread panics.
+pub fn run(threads: usize, duration: Duration, config: GeneratorConfig) -> StressReport {
+    let threads = if threads == 0 {
+        thread::available_parallelism().map_or(1, |n| n.get())
+    } else {
+        threads
+    };
+
+    let shard_id = match config {
+        GeneratorConfig::Stateless { shard_id } | GeneratorConfig::Shared { shard_id } => shard_id,
+    };
+    let shared = match config {
+        GeneratorConfig::Shared { .. } => Some(Arc::new(
+            UniqueGenerator::new(shard_id).expect("invalid shard ID"),
+        )),
+        GeneratorConfig::Stateless { .. } => None,
+    };
+
+    // Start all workers together so contention is real from the first ID
+    let barrier = Arc::new(Barrier::new(threads + 1));
+    let handles: Vec<_> = (0..threads)
+        .map(|_| {
+            let barrier = Arc::clone(&barrier);
+            let shared = shared.clone();
+            thread::spawn(move || {
+                let next = || match &shared {
+                    Some(generator) => generator.next(),
+                    None => MicroShardUUID::generate(shard_id),
+                };
+                barrier.wait();
+                worker(duration, next)
+            })
+        })
+        .collect();
+
+    barrier.wait();
+    let start = Instant::now();
+    let results: Vec<WorkerResult> = handles
+        .into_iter()
+        .map(|h| h.join().expect("stress worker panicked"))
+        .collect();
+    let elapsed = start.elapsed();
+
+    let mut all: Vec<u128> = Vec::with_capacity(results.iter().map(|r| r.ids.len()).sum());
+    let mut ordering_violations = 0;
+    let mut errors = 0;
+    for r in results {
+        all.extend_from_slice(&r.ids);
+        ordering_violations += r.ordering_violations;
+        errors += r.errors;
+    }
+
+    let generated = all.len() as u64;
+    all.sort_unstable();
+    let duplicates = all.windows(2).filter(|w| w[0] == w[1]).count() as u64;
+
+    StressReport {
+        threads,
+        elapsed,
+        generated,
+        duplicates,
+        ordering_violations,
+        errors,
+    }
+}
+
+/// Internal: generates until `duration` has passed.
+fn worker<F>(duration: Duration, next: F) -> WorkerResult
+where
+    F: Fn() -> Result<MicroShardUUID, MicroShardError>,
+{
+    let deadline = Instant::now() + duration;
+    let mut result = WorkerResult {
+        ids: Vec::new(),
+        ordering_violations: 0,
+        errors: 0,
+    };
+    let mut last: Option<u128> = None;
+    let mut calls: u64 = 0;
+
+    loop {
+        if calls.is_multiple_of(CLOCK_CHECK_INTERVAL) && Instant::now() >= deadline {
+            break;
+        }
+        calls += 1;
+
+        match next() {
+            Ok(id) => {
+                let v = id.as_u128();
+                if last.is_some_and(|prev| v <= prev) {
+                    result.ordering_violations += 1;
+                }
+                last = Some(v);
+                result.ids.push(v);
+            }
+            Err(_) => result.errors += 1,
+        }
+    }
+    result
+}
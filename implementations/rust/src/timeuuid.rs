@@ -0,0 +1,91 @@
+//! Conversion to and from Cassandra / ScyllaDB `timeuuid` (RFC 9562 UUIDv1).
+//!
+//! **Layout:**
+//! - **High 64 bits:** `[TimeLow (32)] [TimeMid (16)] [Ver=1 (4)] [TimeHigh (12)]`,
+//!   a 60-bit count of 100 ns ticks since 1582-10-15.
+//! - **Low 64 bits:** `[Var (2 bits)] [Shard (32 bits)] [Random (30 bits)]`
+//!   in the `clock_seq` and `node` fields, every byte except the first
+//!   XOR-ed with `0x80`.
+//!
+//! Cassandra orders `timeuuid` by the reassembled timestamp and then by the
+//! low 8 bytes compared as *signed* bytes. The `0x80` flip turns that signed
+//! comparison back into the unsigned order of shard and random, so a
+//! `timeuuid` clustering column sorts converted IDs exactly like the
+//! originals, ties in the dropped random bits aside.
+
+use crate::{MicroShardError, MicroShardUUID, MAX_TIME_MICROS};
+
+/// 100 ns ticks between the Gregorian epoch (1582-10-15) and the Unix Epoch.
+const GREGORIAN_OFFSET: u64 = 0x01B2_1DD2_1381_4000;
+/// Makes Cassandra's signed byte-wise comparison of the low 8 bytes agree
+/// with the unsigned order; the variant byte is already negative.
+const SIGNED_FLIP: u64 = 0x0080_8080_8080_8080;
+const MAX_TIMEUUID_RANDOM: u64 = (1 << 30) - 1;
+/// Random bits dropped when narrowing the 36-bit field to 30 bits.
+const NARROW_SHIFT: u32 = 6;
+
+impl MicroShardUUID {
+    /// Converts to a version 1 UUID for a Cassandra / ScyllaDB `timeuuid`
+    /// column.
+    ///
+    /// The timestamp is the creation microsecond in 100 ns ticks, so the
+    /// database's `toTimestamp()` / `dateOf()` read the right time, and the
+    /// shard fills the top of `clock_seq` and `node`. The lowest 6 random
+    /// bits are dropped; ordering between IDs is kept (ties aside).
+    ///
+    /// ```
+    /// use microshard_uuid::MicroShardUUID;
+    ///
+    /// let id = MicroShardUUID::from_parts(1_714_557_600_000_000, 7, 42 << 6).unwrap();
+    /// let v1 = id.to_timeuuid();
+    /// assert_eq!((v1 >> 76) & 0xF, 1);
+    /// assert_eq!(MicroShardUUID::from_timeuuid(v1).unwrap(), id);
+    /// ```
+    pub fn to_timeuuid(&self) -> u128 {
+        let ticks = GREGORIAN_OFFSET + self.timestamp_micros() * 10;
+        let high = ((ticks & 0xFFFF_FFFF) << 32)
+            | (((ticks >> 32) & 0xFFFF) << 16)
+            | (1 << 12)
+            | (ticks >> 48);
+        let low = (2 << 62) | ((self.shard_id() as u64) << 30) | (self.random() >> NARROW_SHIFT);
+        ((high as u128) << 64) | (low ^ SIGNED_FLIP) as u128
+    }
+
+    /// Converts a version 1 UUID back, the inverse of
+    /// [`to_timeuuid`](Self::to_timeuuid).
+    ///
+    /// Time and shard are restored exactly and the low 6 random bits are
+    /// zero. A `timeuuid` minted by another writer (e.g. CQL `now()`) still
+    /// decodes deterministically and in the same order: its time is
+    /// truncated to the microsecond and the shard and random fields are read
+    /// from its `clock_seq` and `node`.
+    ///
+    /// # Errors
+    /// - [`MicroShardError::InvalidVersion`] / [`MicroShardError::InvalidVariant`]
+    ///   if `v` is not an RFC 9562 v1 UUID.
+    /// - [`MicroShardError::TimeUnderflow`] if the time is before the Unix Epoch.
+    /// - [`MicroShardError::TimeOverflow`] if the time is past the year 2541.
+    pub fn from_timeuuid(v: u128) -> Result<Self, MicroShardError> {
+        let version = ((v >> 76) & 0xF) as u8;
+        if version != 1 {
+            return Err(MicroShardError::InvalidVersion(version));
+        }
+        let variant = ((v >> 62) & 0x3) as u8;
+        if variant != 2 {
+            return Err(MicroShardError::InvalidVariant(variant));
+        }
+        let high = (v >> 64) as u64;
+        let ticks = ((high & 0xFFF) << 48) | (((high >> 16) & 0xFFFF) << 32) | (high >> 32);
+        let since_epoch = ticks
+            .checked_sub(GREGORIAN_OFFSET)
+            .ok_or(MicroShardError::TimeUnderflow)?;
+        let micros = since_epoch / 10;
+        if micros > MAX_TIME_MICROS {
+            return Err(MicroShardError::TimeOverflow);
+        }
+        let low = (v as u64) ^ SIGNED_FLIP;
+        let shard_id = ((low >> 30) & 0xFFFF_FFFF) as u32;
+        let random = (low & MAX_TIMEUUID_RANDOM) << NARROW_SHIFT;
+        Ok(Self::compose(micros, shard_id, random))
+    }
+}
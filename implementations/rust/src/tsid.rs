@@ -0,0 +1,93 @@
+//! 64-bit TSID interop (time-sorted IDs for `BIGINT` columns).
+//!
+//! A TSID is `[Millis since 2020-01-01 (42 bits)] [Node (n bits)] [Counter (22 - n bits)]`,
+//! with `n` = [`DEFAULT_NODE_BITS`] unless the producer was configured
+//! otherwise. Both directions take `node_bits` so they match the producer.
+//!
+//! - **TSID → MicroShard** ([`MicroShardUUID::from_tsid`]) is lossless: the
+//!   node becomes the shard and the counter the top of the random field, so
+//!   TSID order is kept.
+//! - **MicroShard → TSID** ([`MicroShardUUID::to_tsid`]) is **lossy**: the
+//!   sub-millisecond part of the time and the low random bits are dropped,
+//!   so distinct IDs from the same shard and millisecond can map to the same
+//!   TSID. Use it only to feed systems that cannot store 128-bit keys.
+
+use crate::{MicroShardError, MicroShardUUID, MAX_TIME_MICROS};
+
+/// The TSID epoch (2020-01-01T00:00:00Z) in Unix milliseconds.
+pub const TSID_EPOCH_MILLIS: u64 = 1_577_836_800_000;
+
+/// Node bits in the default TSID layout (1024 nodes, 4096 IDs per ms).
+pub const DEFAULT_NODE_BITS: u32 = 10;
+
+/// Bits shared between node and counter.
+const RANDOM_BITS: u32 = 22;
+const MAX_TSID_MILLIS: u64 = (1 << 42) - 1;
+const RANDOM_FIELD_BITS: u32 = 36;
+
+impl MicroShardUUID {
+    /// Converts a TSID from a producer using `node_bits` node bits.
+    ///
+    /// ```
+    /// use microshard_uuid::tsid::{DEFAULT_NODE_BITS, TSID_EPOCH_MILLIS};
+    /// use microshard_uuid::MicroShardUUID;
+    ///
+    /// let tsid = (1_000 << 22) | (5 << 12) | 7; // 1s after the epoch, node 5, counter 7
+    /// let id = MicroShardUUID::from_tsid(tsid, DEFAULT_NODE_BITS).unwrap();
+    /// assert_eq!(id.timestamp_micros(), (TSID_EPOCH_MILLIS + 1_000) * 1_000);
+    /// assert_eq!(id.shard_id(), 5);
+    /// assert_eq!(id.to_tsid(DEFAULT_NODE_BITS), Ok(tsid));
+    /// ```
+    ///
+    /// # Errors
+    /// [`MicroShardError::InvalidRange`] if `node_bits` exceeds 22.
+    pub fn from_tsid(tsid: u64, node_bits: u32) -> Result<Self, MicroShardError> {
+        let counter_bits = counter_bits(node_bits)?;
+        let millis = TSID_EPOCH_MILLIS + (tsid >> RANDOM_BITS);
+        let node = (tsid & ((1 << RANDOM_BITS) - 1)) >> counter_bits;
+        let counter = tsid & ((1 << counter_bits) - 1);
+        // 42-bit TSID milliseconds end in 2159, well inside the 54-bit range.
+        Ok(Self::compose(
+            millis * 1_000,
+            node as u32,
+            counter << (RANDOM_FIELD_BITS - counter_bits),
+        ))
+    }
+
+    /// Converts to a TSID with `node_bits` node bits (lossy, see the
+    /// [module docs](crate::tsid)): milliseconds, the shard as the node, and
+    /// the top random bits as the counter.
+    ///
+    /// # Errors
+    /// - [`MicroShardError::InvalidRange`] if `node_bits` exceeds 22.
+    /// - [`MicroShardError::InvalidShardId`] if the shard does not fit in
+    ///   `node_bits` (carries the largest node id that does).
+    /// - [`MicroShardError::TimeUnderflow`] before 2020-01-01.
+    /// - [`MicroShardError::TimeOverflow`] after the 42-bit range (year 2159).
+    pub fn to_tsid(&self, node_bits: u32) -> Result<u64, MicroShardError> {
+        let counter_bits = counter_bits(node_bits)?;
+        let max_node = (1u64 << node_bits) - 1;
+        let node = self.shard_id() as u64;
+        if node > max_node {
+            return Err(MicroShardError::InvalidShardId(max_node as u32));
+        }
+        let millis = (self.timestamp_micros() / 1_000)
+            .checked_sub(TSID_EPOCH_MILLIS)
+            .ok_or(MicroShardError::TimeUnderflow)?;
+        if millis > MAX_TSID_MILLIS {
+            return Err(MicroShardError::TimeOverflow);
+        }
+        let counter = self.random() >> (RANDOM_FIELD_BITS - counter_bits);
+        Ok((millis << RANDOM_BITS) | (node << counter_bits) | counter)
+    }
+}
+
+/// Internal: counter width for a node width, rejecting layouts over 22 bits.
+fn counter_bits(node_bits: u32) -> Result<u32, MicroShardError> {
+    RANDOM_BITS
+        .checked_sub(node_bits)
+        .ok_or(MicroShardError::InvalidRange)
+}
+
+// `MAX_TIME_MICROS` bounds every decoded time; keep the TSID range inside it.
+const _: () = assert!((TSID_EPOCH_MILLIS + MAX_TSID_MILLIS) * 1_000 <= MAX_TIME_MICROS);
@@ -0,0 +1,140 @@
+//! ULID interop (48-bit millisecond timestamp + 80-bit entropy).
+//!
+//! [`Ulid`] is a minimal 128-bit ULID value: enough to parse, print and
+//! convert IDs from services that emit ULIDs, without pulling in another
+//! crate. Converting a [`MicroShardUUID`] packs everything into the ULID
+//! entropy field, so the round trip is lossless:
+//!
+//! - **Time (48 bits):** Unix milliseconds, as the ULID spec requires.
+//! - **Entropy (80 bits):** `[SubMillis µs (10 bits)] [Shard (32 bits)] [Random (36 bits)] [Zero (2 bits)]`
+//!
+//! The microsecond remainder comes first, so ULID order (numeric or
+//! lexicographic) matches MicroShard order.
+
+use core::fmt;
+use core::str::FromStr;
+
+use crate::base32::{base32_digits, decode_base32};
+use crate::{MicroShardError, MicroShardUUID, MAX_RANDOM, MAX_TIME_MICROS};
+
+const ENTROPY_BITS: u32 = 80;
+const SUB_MILLIS_SHIFT: u32 = 70;
+const SHARD_SHIFT: u32 = 38;
+const RANDOM_SHIFT: u32 = 2;
+
+/// A ULID as a raw 128-bit value (big-endian, like [`MicroShardUUID`]).
+///
+/// ```
+/// use microshard_uuid::{MicroShardUUID, Ulid};
+///
+/// let id = MicroShardUUID::from_parts(1_700_000_000_123_456, 42, 7).unwrap();
+/// let ulid = Ulid::from(id);
+/// assert_eq!(ulid.timestamp_millis(), 1_700_000_000_123);
+/// assert_eq!(MicroShardUUID::try_from(ulid), Ok(id));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
+pub struct Ulid(u128);
+
+impl Ulid {
+    /// Wraps a raw `u128`. Every 128-bit value is a valid ULID.
+    pub const fn from_u128(v: u128) -> Self {
+        Self(v)
+    }
+
+    /// Constructs a ULID from a 16-byte array (Big Endian).
+    pub fn from_bytes(bytes: [u8; 16]) -> Self {
+        Self(u128::from_be_bytes(bytes))
+    }
+
+    /// Parses the 26-character Crockford Base32 form (case-insensitive).
+    ///
+    /// # Errors
+    /// [`MicroShardError::InvalidEncoding`] for a wrong length, a character
+    /// outside the alphabet, or a first digit above `7`.
+    pub fn parse(s: &str) -> Result<Self, MicroShardError> {
+        decode_base32(s).map(Self)
+    }
+
+    /// Returns the raw `u128` value.
+    pub fn as_u128(&self) -> u128 {
+        self.0
+    }
+
+    /// Returns the ULID as a 16-byte array (Big Endian).
+    pub fn as_bytes(&self) -> [u8; 16] {
+        self.0.to_be_bytes()
+    }
+
+    /// The 48-bit timestamp: milliseconds since the Unix Epoch.
+    pub fn timestamp_millis(&self) -> u64 {
+        (self.0 >> ENTROPY_BITS) as u64
+    }
+}
+
+impl From<MicroShardUUID> for Ulid {
+    fn from(id: MicroShardUUID) -> Self {
+        let micros = id.timestamp_micros();
+        Self(
+            ((micros / 1_000) as u128) << ENTROPY_BITS
+                | ((micros % 1_000) as u128) << SUB_MILLIS_SHIFT
+                | (id.shard_id() as u128) << SHARD_SHIFT
+                | (id.random() as u128) << RANDOM_SHIFT,
+        )
+    }
+}
+
+/// Reverses `From<MicroShardUUID>` exactly.
+///
+/// ULIDs minted by other producers convert too, keeping their millisecond
+/// and their order: the leading entropy bits become the sub-millisecond
+/// offset (clamped to 999µs) and the shard, the next 36 the random field,
+/// and the last 2 are dropped. Such a shard is arbitrary; re-stamp it if the
+/// value matters downstream.
+///
+/// # Errors
+/// [`MicroShardError::TimeOverflow`] past the year 2541 (the ULID
+/// millisecond range is far wider than the 54-bit microsecond field).
+impl TryFrom<Ulid> for MicroShardUUID {
+    type Error = MicroShardError;
+
+    fn try_from(ulid: Ulid) -> Result<Self, Self::Error> {
+        let v = ulid.0;
+        let sub_millis = ((v >> SUB_MILLIS_SHIFT) & 0x3FF).min(999) as u64;
+        // 48-bit milliseconds always fit a u64 as microseconds.
+        let micros = ulid.timestamp_millis() * 1_000 + sub_millis;
+        if micros > MAX_TIME_MICROS {
+            return Err(MicroShardError::TimeOverflow);
+        }
+        let shard_id = (v >> SHARD_SHIFT) as u32;
+        let random = (v >> RANDOM_SHIFT) as u64 & MAX_RANDOM;
+        Ok(Self::compose(micros, shard_id, random))
+    }
+}
+
+impl From<u128> for Ulid {
+    fn from(v: u128) -> Self {
+        Self(v)
+    }
+}
+
+impl From<Ulid> for u128 {
+    fn from(ulid: Ulid) -> Self {
+        ulid.0
+    }
+}
+
+// Canonical uppercase 26-character form
+impl fmt::Display for Ulid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = base32_digits(self.0);
+        f.write_str(core::str::from_utf8(&digits).expect("base32 digits are ASCII"))
+    }
+}
+
+impl FromStr for Ulid {
+    type Err = MicroShardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
@@ -0,0 +1,220 @@
+use std::fmt;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::time_source::TimeSource;
+use crate::{now_micros, MicroShardError, MicroShardUUID, Xoshiro256StarStar};
+use crate::{validate_shard, MAX_RANDOM, MAX_REVISION, MAX_TIME_MICROS, REVISION_SHIFT};
+
+// ==========================================
+// Process-Wide Uniqueness Guarantee
+// ==========================================
+
+/// Callback invoked with every ID minted by a generator (see `on_generate`).
+pub type GenerateHook = Box<dyn Fn(&MicroShardUUID) + Send + Sync>;
+
+/// Default tolerance for a backwards clock step before generation fails.
+pub const DEFAULT_MAX_REGRESSION: Duration = Duration::from_secs(1);
+
+/// Internal: last issued (timestamp, random) pair.
+#[derive(Debug)]
+struct UniqueState {
+    last_micros: u64,
+    last_random: u64,
+}
+
+/// A thread-safe generator that never emits the same ID twice.
+///
+/// **Guarantee:** a single process that mints every ID for its shard through
+/// one `UniqueGenerator` never emits a duplicate. IDs from the same generator
+/// are strictly increasing, so they also sort in issue order.
+///
+/// **Mechanism:**
+/// - **New microsecond:** the 36-bit random field is freshly drawn.
+/// - **Same microsecond:** the random field of the previous ID is incremented,
+///   acting as a monotonic counter.
+/// - **Clock regression:** the generator *holds* the last issued timestamp and
+///   keeps counting until the wall clock catches up.
+/// - **Counter exhausted:** the timestamp is advanced by 1µs and a fresh random
+///   value is drawn.
+///
+/// **Failure Modes:**
+/// - [`MicroShardError::ClockMovedBackwards`] if the clock steps back further
+///   than the configured tolerance ([`DEFAULT_MAX_REGRESSION`] by default).
+/// - [`MicroShardError::TimeOverflow`] if the held timestamp would pass 2^54 - 1.
+/// - [`MicroShardError::SystemTimeError`] if the system clock is before 1970.
+///
+/// Create exactly one instance per shard (e.g. in a `static OnceLock`) and share
+/// it between threads.
+pub struct UniqueGenerator {
+    shard_id: u32,
+    max_regression_micros: u64,
+    layout_revision: Option<u8>,
+    state: Mutex<UniqueState>,
+    on_generate: Option<GenerateHook>,
+    clock: Option<Box<dyn TimeSource + Send + Sync>>,
+}
+
+impl fmt::Debug for UniqueGenerator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UniqueGenerator")
+            .field("shard_id", &self.shard_id)
+            .field("max_regression_micros", &self.max_regression_micros)
+            .field("layout_revision", &self.layout_revision)
+            .field("state", &self.state)
+            .field("on_generate", &self.on_generate.is_some())
+            .field("clock", &self.clock.is_some())
+            .finish()
+    }
+}
+
+impl UniqueGenerator {
+    /// Creates a generator for `shard_id` with the default regression tolerance.
+    pub fn new(shard_id: u32) -> Result<Self, MicroShardError> {
+        validate_shard(shard_id)?;
+        Ok(Self {
+            shard_id,
+            max_regression_micros: DEFAULT_MAX_REGRESSION.as_micros() as u64,
+            layout_revision: None,
+            state: Mutex::new(UniqueState {
+                last_micros: 0,
+                last_random: 0,
+            }),
+            on_generate: None,
+            clock: None,
+        })
+    }
+
+    /// Sets how far the clock may step backwards before `next()` fails.
+    /// Within the tolerance, the generator holds the last issued timestamp.
+    pub fn with_max_regression(mut self, tolerance: Duration) -> Self {
+        self.max_regression_micros = tolerance.as_micros().min(u64::MAX as u128) as u64;
+        self
+    }
+
+    /// Opts in to writing `revision` (0..=3) into the top 2 bits of the random
+    /// field, readable via [`MicroShardUUID::layout_revision`].
+    ///
+    /// This leaves 34 random bits per ID, so the per-microsecond counter is
+    /// exhausted 4x sooner before the generator borrows the next microsecond.
+    ///
+    /// # Panics
+    /// Panics if `revision > 3`.
+    pub fn with_layout_revision(mut self, revision: u8) -> Self {
+        assert!(
+            revision <= MAX_REVISION,
+            "layout revision must fit in 2 bits"
+        );
+        self.layout_revision = Some(revision);
+        self
+    }
+
+    /// Registers an audit hook invoked for every successfully minted ID.
+    ///
+    /// The hook runs on the calling thread after the generator's lock is
+    /// released, so it may itself generate IDs. Keep it cheap (e.g. push to a
+    /// channel or sample); it sits on the generation hot path.
+    pub fn on_generate<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&MicroShardUUID) + Send + Sync + 'static,
+    {
+        self.on_generate = Some(Box::new(hook));
+        self
+    }
+
+    /// Reads time from `clock` instead of the system clock in
+    /// [`next`](Self::next). `freeze_time` is then ignored, as the caller's
+    /// clock is authoritative.
+    pub fn with_clock(mut self, clock: impl TimeSource + Send + Sync + 'static) -> Self {
+        self.clock = Some(Box::new(clock));
+        self
+    }
+
+    /// Returns the shard ID embedded in every generated UUID.
+    pub fn shard_id(&self) -> u32 {
+        self.shard_id
+    }
+
+    /// Generates the next unique ID using the current system time (or the
+    /// clock set with [`with_clock`](Self::with_clock)).
+    pub fn next(&self) -> Result<MicroShardUUID, MicroShardError> {
+        let micros = match &self.clock {
+            Some(clock) => clock.now_micros()?,
+            None => now_micros()?,
+        };
+        self.next_at(micros)
+    }
+
+    /// Generates the next unique ID as if the clock read `micros`.
+    ///
+    /// Useful for replaying clock behaviour in tests; the uniqueness and
+    /// ordering guarantees apply exactly as for [`UniqueGenerator::next`].
+    pub fn next_at(&self, micros: u64) -> Result<MicroShardUUID, MicroShardError> {
+        let uuid = self.mint(micros)?;
+        if let Some(hook) = &self.on_generate {
+            hook(&uuid);
+        }
+        Ok(uuid)
+    }
+
+    /// Internal: advances the state under the lock and composes the ID.
+    fn mint(&self, micros: u64) -> Result<MicroShardUUID, MicroShardError> {
+        if micros > MAX_TIME_MICROS {
+            return Err(MicroShardError::TimeOverflow);
+        }
+
+        // The state is updated atomically below, so a poisoned lock still
+        // holds a consistent (timestamp, random) pair.
+        let mut st = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        if micros > st.last_micros {
+            // 1. Clock advanced: fresh randomness
+            st.last_micros = micros;
+            st.last_random = self.fresh_random();
+        } else {
+            // 2. Same microsecond or regression: hold and count
+            let behind = st.last_micros - micros;
+            if behind > self.max_regression_micros {
+                return Err(MicroShardError::ClockMovedBackwards(behind));
+            }
+
+            if st.last_random < self.random_ceiling() {
+                st.last_random += 1;
+            } else {
+                // 3. Counter exhausted: borrow the next microsecond
+                if st.last_micros == MAX_TIME_MICROS {
+                    return Err(MicroShardError::TimeOverflow);
+                }
+                st.last_micros += 1;
+                st.last_random = self.fresh_random();
+            }
+        }
+
+        Ok(MicroShardUUID::compose(
+            st.last_micros,
+            self.shard_id,
+            st.last_random,
+        ))
+    }
+
+    /// Internal: draws a random field, carrying the revision tag if enabled.
+    fn fresh_random(&self) -> u64 {
+        let rnd = Xoshiro256StarStar::next_36();
+        match self.layout_revision {
+            Some(rev) => ((rev as u64) << REVISION_SHIFT) | (rnd & REVISION_MASK),
+            None => rnd,
+        }
+    }
+
+    /// Internal: largest random field the counter may reach without
+    /// disturbing the revision tag.
+    fn random_ceiling(&self) -> u64 {
+        match self.layout_revision {
+            Some(rev) => ((rev as u64) << REVISION_SHIFT) | REVISION_MASK,
+            None => MAX_RANDOM,
+        }
+    }
+}
+
+/// Internal: the 34 random bits below the revision tag.
+const REVISION_MASK: u64 = (1 << REVISION_SHIFT) - 1;
@@ -0,0 +1,243 @@
+//! UUIDv7-compatible layout with an embedded shard.
+//!
+//! [`MicroShardV7`] follows RFC 9562 UUIDv7 bit for bit, so any v7-aware
+//! system (PostgreSQL `uuid_extract_timestamp`, `uuid` crates, log tooling)
+//! reads the correct creation time, while the shard still travels inside the
+//! ID.
+//!
+//! **Layout:**
+//! - **High 64 bits:** `[UnixMillis (48 bits)] [Ver=7 (4 bits)] [SubMillis (12 bits)]`
+//! - **Low 64 bits:**  `[Var (2 bits)] [Shard (32 bits)] [Random (30 bits)]`
+//!
+//! `SubMillis` is the microsecond remainder scaled to 12 bits (RFC 9562
+//! §6.2, Method 3), so IDs sort by microsecond and decode back to the exact
+//! microsecond. Compared with [`MicroShardUUID`](crate::MicroShardUUID), the
+//! 48-bit millisecond field runs until the year 10889 but the random field
+//! shrinks from 36 to 30 bits (~1 billion per shard per microsecond).
+
+use core::fmt;
+#[cfg(feature = "parse")]
+use core::str::FromStr;
+
+#[cfg(feature = "parse")]
+use crate::parse_hyphenated;
+#[cfg(feature = "gen")]
+use crate::{now_micros, validate_shard, Xoshiro256StarStar};
+use crate::{write_hyphenated, MicroShardError, MicroShardUUID, MAX_TIME_MICROS};
+
+const MAX_MILLIS: u64 = (1 << 48) - 1;
+/// Largest representable time: the last microsecond of the last millisecond.
+const MAX_V7_MICROS: u64 = MAX_MILLIS * 1_000 + 999;
+const MAX_V7_RANDOM: u64 = (1 << 30) - 1;
+const SUB_MILLIS_BITS: u32 = 12;
+/// Random bits dropped when narrowing the 36-bit v8 field to 30 bits.
+const NARROW_SHIFT: u32 = 6;
+
+/// A UUIDv7 (RFC 9562) carrying a 32-bit shard in its random bits.
+///
+/// Like [`MicroShardUUID`](crate::MicroShardUUID), ordering follows the
+/// underlying `u128`, which is chronological to the microsecond.
+///
+/// ```
+/// use microshard_uuid::MicroShardV7;
+///
+/// let id = MicroShardV7::from_parts(1_700_000_000_123_456, 42, 7).unwrap();
+/// assert_eq!(id.timestamp_millis(), 1_700_000_000_123);
+/// assert_eq!(id.timestamp_micros(), 1_700_000_000_123_456);
+/// assert_eq!(id.shard_id(), 42);
+/// assert!(id.to_string().starts_with("018bcfe5-687b-7"));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
+pub struct MicroShardV7(u128);
+
+impl MicroShardV7 {
+    // -------------------------------------------------------------------------
+    // Constructors
+    // -------------------------------------------------------------------------
+
+    /// Generates a new ID using the current system time.
+    #[cfg(feature = "gen")]
+    pub fn generate(shard_id: u32) -> Result<Self, MicroShardError> {
+        Self::from_micros(now_micros()?, shard_id)
+    }
+
+    /// Generates an ID stamped with `micros` (microseconds since the Unix Epoch).
+    #[cfg(feature = "gen")]
+    pub fn from_micros(micros: u64, shard_id: u32) -> Result<Self, MicroShardError> {
+        validate_shard(shard_id)?;
+        Self::from_parts(
+            micros,
+            shard_id,
+            Xoshiro256StarStar::next_36() & MAX_V7_RANDOM,
+        )
+    }
+
+    /// Constructs an ID from explicit field values, with no PRNG involved.
+    ///
+    /// # Errors
+    /// - [`MicroShardError::TimeOverflow`] if `micros` is past the 48-bit
+    ///   millisecond range.
+    /// - [`MicroShardError::InvalidRandom`] if `random` exceeds 2^30 - 1.
+    pub fn from_parts(micros: u64, shard_id: u32, random: u64) -> Result<Self, MicroShardError> {
+        if micros > MAX_V7_MICROS {
+            return Err(MicroShardError::TimeOverflow);
+        }
+        if random > MAX_V7_RANDOM {
+            return Err(MicroShardError::InvalidRandom(random));
+        }
+        let millis = micros / 1_000;
+        let sub_millis = ((micros % 1_000) << SUB_MILLIS_BITS) / 1_000;
+        let high = (millis << 16) | (7 << 12) | sub_millis;
+        let low = (2 << 62) | ((shard_id as u64) << 30) | random;
+        Ok(Self(((high as u128) << 64) | low as u128))
+    }
+
+    /// Constructs an ID from a raw `u128`, checking for version 7, variant 2.
+    pub const fn from_u128(v: u128) -> Result<Self, MicroShardError> {
+        let version = ((v >> 76) & 0xF) as u8;
+        if version != 7 {
+            return Err(MicroShardError::InvalidVersion(version));
+        }
+        let variant = ((v >> 62) & 0x3) as u8;
+        if variant != 2 {
+            return Err(MicroShardError::InvalidVariant(variant));
+        }
+        Ok(Self(v))
+    }
+
+    /// Constructs an ID from a 16-byte array (Big Endian).
+    pub fn from_bytes(bytes: [u8; 16]) -> Result<Self, MicroShardError> {
+        Self::from_u128(u128::from_be_bytes(bytes))
+    }
+
+    /// Parses the canonical hyphenated form (optionally `urn:uuid:`-prefixed).
+    ///
+    /// Note that this only checks the version and variant: any UUIDv7 is
+    /// accepted, and one minted elsewhere decodes to an arbitrary shard.
+    #[cfg(feature = "parse")]
+    pub fn parse(s: &str) -> Result<Self, MicroShardError> {
+        Self::from_u128(parse_hyphenated(s)?)
+    }
+
+    // -------------------------------------------------------------------------
+    // Accessors
+    // -------------------------------------------------------------------------
+
+    /// Returns the raw `u128` value.
+    pub fn as_u128(&self) -> u128 {
+        self.0
+    }
+
+    /// Returns the ID as a 16-byte array (Big Endian).
+    pub fn as_bytes(&self) -> [u8; 16] {
+        self.0.to_be_bytes()
+    }
+
+    /// The RFC 9562 `unix_ts_ms` field: milliseconds since the Unix Epoch.
+    pub fn timestamp_millis(&self) -> u64 {
+        (self.0 >> 80) as u64
+    }
+
+    /// The creation time in microseconds, recovered exactly from the
+    /// millisecond field plus the 12-bit sub-millisecond fraction.
+    pub fn timestamp_micros(&self) -> u64 {
+        let sub_millis = ((self.0 >> 64) & 0xFFF) as u64;
+        // Inverse of the floor in `from_parts` (one µs spans ~4 fraction
+        // steps); the clamp keeps foreign v7 fractions inside the millisecond.
+        let remainder = (sub_millis * 1_000).div_ceil(1 << SUB_MILLIS_BITS).min(999);
+        self.timestamp_millis() * 1_000 + remainder
+    }
+
+    /// Extracts the 32-bit Shard ID.
+    pub fn shard_id(&self) -> u32 {
+        (self.0 >> 30) as u32
+    }
+
+    /// Extracts the 30-bit random field.
+    pub fn random(&self) -> u64 {
+        (self.0 as u64) & MAX_V7_RANDOM
+    }
+}
+
+impl fmt::Display for MicroShardV7 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_hyphenated(self.0, f)
+    }
+}
+
+#[cfg(feature = "parse")]
+impl FromStr for MicroShardV7 {
+    type Err = MicroShardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Conversions to and from UUIDv7
+// -----------------------------------------------------------------------------
+
+impl MicroShardUUID {
+    /// Converts to the UUIDv7 layout, for systems being migrated to or from v7.
+    ///
+    /// Time (to the microsecond) and shard carry over exactly. The v7 random
+    /// field is 6 bits narrower, so the **lowest** 6 random bits are dropped;
+    /// keeping the top bits preserves the sequence of monotonic generators,
+    /// so ordering between IDs is kept (ties aside).
+    pub fn to_uuid_v7(&self) -> MicroShardV7 {
+        let random = self.random() >> NARROW_SHIFT;
+        MicroShardV7::from_parts(self.timestamp_micros(), self.shard_id(), random)
+            .expect("54-bit micros and 30-bit random always fit the v7 layout")
+    }
+
+    /// Best-effort import of an arbitrary UUIDv7 (any producer) into `shard_id`.
+    ///
+    /// The millisecond timestamp is kept. `rand_a` is read as the
+    /// sub-millisecond fraction (exact for [`MicroShardV7`] and RFC 9562
+    /// Method 3 producers, an arbitrary offset within the same millisecond
+    /// otherwise) and the top 36 bits of `rand_b` become the random field.
+    /// The mapping is deterministic and order-preserving, so re-importing a
+    /// column yields the same IDs in the same order.
+    ///
+    /// To keep the shard embedded in a [`MicroShardV7`], use
+    /// `MicroShardUUID::try_from(v7)` instead.
+    ///
+    /// # Errors
+    /// - [`MicroShardError::InvalidVersion`] / [`MicroShardError::InvalidVariant`]
+    ///   if `v` is not an RFC 9562 v7 UUID.
+    /// - [`MicroShardError::TimeOverflow`] if the time is past the year 2541.
+    pub fn from_uuid_v7(v: u128, shard_id: u32) -> Result<Self, MicroShardError> {
+        let v7 = MicroShardV7::from_u128(v)?;
+        let micros = v7.timestamp_micros();
+        if micros > MAX_TIME_MICROS {
+            return Err(MicroShardError::TimeOverflow);
+        }
+        // rand_b is 62 bits; its top 36 follow rand_a in sort order.
+        let random = ((v as u64) & ((1 << 62) - 1)) >> 26;
+        Ok(Self::compose(micros, shard_id, random))
+    }
+}
+
+impl From<MicroShardUUID> for MicroShardV7 {
+    fn from(id: MicroShardUUID) -> Self {
+        id.to_uuid_v7()
+    }
+}
+
+/// Restores time and shard exactly; the low 6 random bits are zero.
+impl TryFrom<MicroShardV7> for MicroShardUUID {
+    type Error = MicroShardError;
+
+    fn try_from(v7: MicroShardV7) -> Result<Self, Self::Error> {
+        let micros = v7.timestamp_micros();
+        if micros > MAX_TIME_MICROS {
+            return Err(MicroShardError::TimeOverflow);
+        }
+        Ok(Self::compose(
+            micros,
+            v7.shard_id(),
+            v7.random() << NARROW_SHIFT,
+        ))
+    }
+}
@@ -0,0 +1,329 @@
+// File: tests/allocator.rs
+#![cfg(feature = "shard-allocator")]
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, ErrorKind, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use microshard_uuid::allocator::{RedisAllocator, ShardAllocation, ShardAllocator};
+
+/// Claims held in a shared map: shard -> claimant.
+type Claims = Arc<Mutex<HashMap<u32, usize>>>;
+
+struct MemoryAllocator {
+    claims: Claims,
+    me: usize,
+}
+
+impl ShardAllocator for MemoryAllocator {
+    fn try_claim(&mut self, shard: u32, _ttl: Duration) -> io::Result<bool> {
+        let mut claims = self.claims.lock().unwrap();
+        if claims.contains_key(&shard) {
+            return Ok(false);
+        }
+        claims.insert(shard, self.me);
+        Ok(true)
+    }
+
+    fn renew(&mut self, shard: u32, _ttl: Duration) -> io::Result<bool> {
+        Ok(self.claims.lock().unwrap().get(&shard) == Some(&self.me))
+    }
+
+    fn surrender(&mut self, shard: u32) -> io::Result<()> {
+        let mut claims = self.claims.lock().unwrap();
+        if claims.get(&shard) == Some(&self.me) {
+            claims.remove(&shard);
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_allocation_claims_distinct_shards_and_surrenders() {
+    let claims = Claims::default();
+    let pod = |me| MemoryAllocator {
+        claims: Arc::clone(&claims),
+        me,
+    };
+    let ttl = Duration::from_secs(60);
+
+    let a = ShardAllocation::acquire(pod(1), 10..=11, ttl).unwrap();
+    let b = ShardAllocation::acquire(pod(2), 10..=11, ttl).unwrap();
+    assert_eq!((a.shard_id(), b.shard_id()), (10, 11));
+    assert_eq!(a.shard().get(), 10);
+    let err = ShardAllocation::acquire(pod(3), 10..=11, ttl).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::ResourceBusy);
+
+    // Dropping surrenders at once, without waiting for the TTL
+    drop(a);
+    assert_eq!(claims.lock().unwrap().get(&10), None);
+    let c = ShardAllocation::acquire(pod(3), 10..=11, ttl).unwrap();
+    assert_eq!(c.shard_id(), 10);
+    c.surrender().unwrap();
+    assert!(!b.is_lost());
+    assert_eq!(*claims.lock().unwrap(), HashMap::from([(11, 2)]));
+
+    #[allow(clippy::reversed_empty_ranges)]
+    let empty = 11..=10;
+    let err = ShardAllocation::acquire(pod(4), empty, ttl).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    let err = ShardAllocation::acquire(pod(4), 0..=1, Duration::ZERO).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    // The claim would be lost before it was first renewed
+    let slow = UnreliableAllocator {
+        timeout: ttl,
+        panic: false,
+    };
+    let err = ShardAllocation::acquire(slow, 0..=1, ttl).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn test_allocation_detects_lost_claim() {
+    let claims = Claims::default();
+    let allocator = MemoryAllocator {
+        claims: Arc::clone(&claims),
+        me: 1,
+    };
+    let allocation = ShardAllocation::acquire(allocator, 0..=0, Duration::from_millis(30)).unwrap();
+    assert!(!allocation.is_lost());
+
+    // Simulate expiry and takeover by another pod
+    claims.lock().unwrap().insert(0, 2);
+    for _ in 0..200 {
+        if allocation.is_lost() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(5));
+    }
+    assert!(allocation.is_lost());
+
+    // Dropping a lost claim must not release the new holder's shard
+    drop(allocation);
+    assert_eq!(claims.lock().unwrap().get(&0), Some(&2));
+}
+
+/// An allocator whose claims succeed but whose renewals misbehave.
+struct UnreliableAllocator {
+    timeout: Duration,
+    panic: bool,
+}
+
+impl ShardAllocator for UnreliableAllocator {
+    fn try_claim(&mut self, _shard: u32, _ttl: Duration) -> io::Result<bool> {
+        Ok(true)
+    }
+
+    fn renew(&mut self, _shard: u32, _ttl: Duration) -> io::Result<bool> {
+        if self.panic {
+            panic!("renew blew up");
+        }
+        Err(ErrorKind::TimedOut.into())
+    }
+
+    fn surrender(&mut self, _shard: u32) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+/// Polls `is_lost` for up to `limit`; returns how long it took to turn true.
+fn time_to_lost(allocation: &ShardAllocation, limit: Duration) -> Option<Duration> {
+    let start = Instant::now();
+    while start.elapsed() < limit {
+        if allocation.is_lost() {
+            return Some(start.elapsed());
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+    None
+}
+
+#[test]
+fn test_allocation_lost_before_ttl_when_renewals_fail() {
+    let ttl = Duration::from_millis(300);
+    let allocator = UnreliableAllocator {
+        timeout: Duration::from_millis(100),
+        panic: false,
+    };
+    let claimed = Instant::now();
+    let allocation = ShardAllocation::acquire(allocator, 0..=0, ttl).unwrap();
+    assert!(!allocation.is_lost());
+
+    // Lost once ttl - timeout has passed, before the server could expire it
+    time_to_lost(&allocation, ttl).unwrap();
+    let elapsed = claimed.elapsed();
+    assert!(elapsed >= Duration::from_millis(200), "{:?}", elapsed);
+    assert!(elapsed < ttl, "{:?}", elapsed);
+}
+
+#[test]
+fn test_allocation_lost_when_heartbeat_panics() {
+    // Renewal (and so the panic) at 1s, well before the 3s deadline
+    let allocator = UnreliableAllocator {
+        timeout: Duration::ZERO,
+        panic: true,
+    };
+    let allocation = ShardAllocation::acquire(allocator, 0..=0, Duration::from_secs(3)).unwrap();
+    let took = time_to_lost(&allocation, Duration::from_millis(2500)).unwrap();
+    assert!(took < Duration::from_secs(2), "{:?}", took);
+    let err = allocation.surrender().unwrap_err();
+    assert_eq!(err.to_string(), "shard heartbeat thread panicked");
+}
+
+/// Reads one RESP command (an array of bulk strings).
+fn read_command<R: BufRead>(conn: &mut R) -> Option<Vec<String>> {
+    let mut line = String::new();
+    if conn.read_line(&mut line).ok()? == 0 {
+        return None;
+    }
+    let count: usize = line.trim_end().strip_prefix('*')?.parse().ok()?;
+    let mut args = Vec::with_capacity(count);
+    for _ in 0..count {
+        line.clear();
+        conn.read_line(&mut line).ok()?;
+        let len: usize = line.trim_end().strip_prefix('$')?.parse().ok()?;
+        let mut data = vec![0; len + 2];
+        conn.read_exact(&mut data).ok()?;
+        data.truncate(len);
+        args.push(String::from_utf8(data).ok()?);
+    }
+    Some(args)
+}
+
+/// A fake Redis keyspace: key -> (token, ttl millis).
+type Store = Arc<Mutex<HashMap<String, (String, String)>>>;
+
+/// A Redis stand-in that understands just the allocator's commands.
+/// Returns its address and its store.
+fn fake_redis() -> (String, Store) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let store = Store::default();
+    let shared = Arc::clone(&store);
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let store = Arc::clone(&shared);
+            thread::spawn(move || {
+                let mut conn = BufReader::new(stream.unwrap());
+                while let Some(args) = read_command(&mut conn) {
+                    let mut store = store.lock().unwrap();
+                    let reply = match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+                        ["SET", key, token, "NX", "PX", ttl] => {
+                            if store.contains_key(key) {
+                                "$-1\r\n".to_owned()
+                            } else {
+                                store.insert(key.into(), (token.into(), ttl.into()));
+                                "+OK\r\n".to_owned()
+                            }
+                        }
+                        ["EVAL", script, "1", key, token, ref rest @ ..] => {
+                            let held = store.get(key).is_some_and(|(t, _)| t == token);
+                            if held && script.contains("pexpire") {
+                                store.get_mut(key).unwrap().1 = rest[0].to_owned();
+                            } else if held && script.contains("del") {
+                                store.remove(key);
+                            }
+                            format!(":{}\r\n", held as u8)
+                        }
+                        _ => "-ERR unknown command\r\n".to_owned(),
+                    };
+                    conn.get_mut().write_all(reply.as_bytes()).unwrap();
+                }
+            });
+        }
+    });
+    (addr, store)
+}
+
+#[test]
+fn test_redis_allocator_protocol() {
+    let (addr, store) = fake_redis();
+    let ttl = Duration::from_millis(1500);
+    let mut a = RedisAllocator::connect(&addr)
+        .unwrap()
+        .with_key_prefix("test:")
+        .with_timeout(Duration::from_secs(2));
+    let mut b = RedisAllocator::connect(&addr)
+        .unwrap()
+        .with_key_prefix("test:")
+        .with_timeout(Duration::from_millis(500));
+    assert_ne!(a.token(), b.token());
+
+    assert!(a.try_claim(3, ttl).unwrap());
+    assert_eq!(
+        store.lock().unwrap()["test:3"],
+        (a.token().to_owned(), "1500".to_owned())
+    );
+    assert!(!b.try_claim(3, ttl).unwrap());
+
+    // Only the holder can renew or surrender
+    assert!(a.renew(3, Duration::from_secs(9)).unwrap());
+    assert_eq!(store.lock().unwrap()["test:3"].1, "9000");
+    assert!(!b.renew(3, ttl).unwrap());
+    b.surrender(3).unwrap();
+    assert!(store.lock().unwrap().contains_key("test:3"));
+    a.surrender(3).unwrap();
+    assert!(store.lock().unwrap().is_empty());
+    assert!(!a.renew(3, ttl).unwrap());
+
+    // End to end: the allocation releases its key when dropped
+    let allocation = ShardAllocation::acquire(b, 0..=7, ttl).unwrap();
+    assert_eq!(allocation.shard_id(), 0);
+    assert!(store.lock().unwrap().contains_key("test:0"));
+    drop(allocation);
+    assert!(store.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_redis_allocator_surfaces_server_errors() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut conn = BufReader::new(stream);
+        while read_command(&mut conn).is_some() {
+            conn.get_mut()
+                .write_all(b"-NOSCRIPT scripting disabled\r\n")
+                .unwrap();
+        }
+    });
+    let mut redis = RedisAllocator::connect(addr).unwrap();
+    let err = redis.renew(1, Duration::from_secs(1)).unwrap_err();
+    assert_eq!(err.to_string(), "Redis: NOSCRIPT scripting disabled");
+    // The connection survives a server error
+    let err = redis.surrender(1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Other);
+}
+
+#[test]
+fn test_redis_allocator_rejects_malformed_replies() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        let replies: [&[u8]; 2] = ["\u{e9}t\u{e9}\r\n".as_bytes(), b"$1000000000000\r\n"];
+        for reply in replies {
+            let (stream, _) = listener.accept().unwrap();
+            let mut conn = BufReader::new(stream);
+            // Each bad reply drops the connection, so every reply gets its own
+            if read_command(&mut conn).is_some() {
+                conn.get_mut().write_all(reply).unwrap();
+            }
+        }
+    });
+    let mut redis = RedisAllocator::connect(addr).unwrap();
+    // A reply starting with a multi-byte character is an error, not a panic
+    let err = redis.renew(1, Duration::from_secs(1)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    // A huge bulk length is refused before allocating
+    let err = redis.renew(1, Duration::from_secs(1)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "bad Redis length");
+}
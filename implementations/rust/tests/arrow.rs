@@ -0,0 +1,70 @@
+// File: tests/arrow.rs
+#![cfg(all(feature = "arrow", feature = "gen", feature = "parse"))]
+
+use arrow_array::{Array, FixedSizeBinaryArray};
+use microshard_uuid::{arrow, MicroShardUUID};
+
+#[test]
+fn test_arrow_kernels_decode_columns() {
+    let a = MicroShardUUID::from_iso("2024-05-01T10:00:00.123456Z", 7).unwrap();
+    let b = MicroShardUUID::from_iso("2030-01-01T00:00:00Z", u32::MAX).unwrap();
+
+    let col = arrow::to_fixed_size_binary(&[a, b]);
+    assert_eq!(col.value_length(), 16);
+    assert_eq!(col.value(1), b.as_bytes());
+
+    let ts = arrow::timestamps(&col).unwrap();
+    assert_eq!(ts.timezone(), Some("+00:00"));
+    assert_eq!(ts.value(0) as u64, a.timestamp_micros());
+    assert_eq!(ts.value(1) as u64, b.timestamp_micros());
+
+    let shards = arrow::shard_ids(&col).unwrap();
+    assert_eq!(shards.values().to_vec(), vec![7, u32::MAX]);
+}
+
+#[test]
+fn test_arrow_kernels_nulls_and_errors() {
+    let id = MicroShardUUID::from_micros(1_000_000, 3).unwrap();
+    let col = FixedSizeBinaryArray::try_from_sparse_iter_with_size(
+        vec![Some(id.as_bytes()), None].into_iter(),
+        16,
+    )
+    .unwrap();
+    let shards = arrow::shard_ids(&col).unwrap();
+    assert_eq!(shards.value(0), 3);
+    assert!(shards.is_null(1));
+
+    // A v4 UUID in the column is rejected, not silently decoded
+    let bad = FixedSizeBinaryArray::try_from_iter(vec![[0x40u8; 16]].into_iter()).unwrap();
+    assert!(arrow::timestamps(&bad).is_err());
+
+    // Wrong width
+    let narrow = FixedSizeBinaryArray::try_from_iter(vec![[0u8; 8]].into_iter()).unwrap();
+    assert!(arrow::shard_ids(&narrow).is_err());
+}
+
+#[test]
+fn test_arrow_round_trip() {
+    let a = MicroShardUUID::from_micros(1_714_557_600_123_456, 7).unwrap();
+    let b = MicroShardUUID::from_micros(1_714_557_600_123_457, u32::MAX).unwrap();
+
+    let col = arrow::to_fixed_size_binary(&[a, b]);
+    assert_eq!(col.null_count(), 0);
+    assert_eq!(
+        arrow::from_fixed_size_binary(&col).unwrap(),
+        vec![Some(a), Some(b)]
+    );
+
+    let col = arrow::to_nullable_fixed_size_binary(&[None, Some(b)]);
+    assert_eq!(col.len(), 2);
+    assert!(col.is_null(0));
+    assert_eq!(col.value(1), b.as_bytes());
+    assert_eq!(
+        arrow::from_fixed_size_binary(&col).unwrap(),
+        vec![None, Some(b)]
+    );
+    assert!(arrow::timestamps(&col).unwrap().is_null(0));
+
+    let bad = FixedSizeBinaryArray::try_from_iter(vec![[0x40u8; 16]].into_iter()).unwrap();
+    assert!(arrow::from_fixed_size_binary(&bad).is_err());
+}
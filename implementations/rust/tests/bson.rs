@@ -0,0 +1,84 @@
+// File: tests/bson.rs
+#![cfg(feature = "bson")]
+
+mod common;
+
+use bson::spec::BinarySubtype;
+use bson::{doc, Binary, Bson, Uuid};
+use common::sample;
+use microshard_uuid::{MicroShardError, MicroShardUUID};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Event {
+    #[serde(rename = "_id", with = "microshard_uuid::bson::as_binary")]
+    id: MicroShardUUID,
+    name: String,
+}
+
+#[test]
+fn test_bson_conversions() {
+    let id = sample();
+    let binary = Binary::from(id);
+    assert_eq!(binary.subtype, BinarySubtype::Uuid);
+    assert_eq!(binary.bytes, id.as_bytes());
+    assert_eq!(MicroShardUUID::try_from(binary.clone()).unwrap(), id);
+    assert_eq!(MicroShardUUID::try_from(Bson::Binary(binary)).unwrap(), id);
+    assert_eq!(Uuid::from(id).bytes(), id.as_bytes());
+    assert_eq!(MicroShardUUID::try_from(Uuid::from(id)).unwrap(), id);
+
+    // Generic and legacy (subtype 3) binaries, wrong lengths and non-binary
+    // values are rejected
+    for subtype in [BinarySubtype::Generic, BinarySubtype::UuidOld] {
+        let other = Binary {
+            subtype,
+            bytes: id.as_bytes().to_vec(),
+        };
+        assert_eq!(
+            MicroShardUUID::try_from(other),
+            Err(MicroShardError::InvalidEncoding)
+        );
+    }
+    let short = Binary {
+        subtype: BinarySubtype::Uuid,
+        bytes: vec![1, 2],
+    };
+    assert_eq!(
+        MicroShardUUID::try_from(short),
+        Err(MicroShardError::InvalidEncoding)
+    );
+    assert!(MicroShardUUID::try_from(Bson::String(id.to_string())).is_err());
+    let v4 = Uuid::parse_str("01234567-89ab-4def-8123-456789abcdef").unwrap();
+    assert_eq!(
+        MicroShardUUID::try_from(v4),
+        Err(MicroShardError::InvalidVersion(4))
+    );
+}
+
+#[test]
+fn test_bson_as_binary_id() {
+    let event = Event {
+        id: sample(),
+        name: "created".to_owned(),
+    };
+    let doc = bson::to_document(&event).unwrap();
+    assert_eq!(doc, doc! { "_id": Bson::from(sample()), "name": "created" });
+
+    // Raw bytes: element type 0x05 (binary), length 16, subtype 0x04
+    let raw = bson::to_vec(&event).unwrap();
+    let at = raw.windows(4).position(|w| w == b"_id\0").unwrap() + 4;
+    assert_eq!(raw[at - 5], 0x05);
+    assert_eq!(raw[at..at + 5], [16, 0, 0, 0, 0x04]);
+    assert_eq!(raw[at + 5..at + 21], sample().as_bytes());
+    assert_eq!(bson::from_slice::<Event>(&raw).unwrap(), event);
+    assert_eq!(bson::from_document::<Event>(doc).unwrap(), event);
+
+    // Documents written with the plain (string) Serialize impl still load
+    let legacy = doc! { "_id": sample().to_string(), "name": "created" };
+    assert_eq!(bson::from_document::<Event>(legacy).unwrap(), event);
+
+    // Other formats fall back to bson::Uuid's representation
+    let json = serde_json::to_value(&event).unwrap();
+    assert_eq!(json["_id"], sample().to_string());
+    assert_eq!(serde_json::from_value::<Event>(json).unwrap(), event);
+}
@@ -0,0 +1,48 @@
+// File: tests/chrono.rs
+#![cfg(all(feature = "chrono", feature = "gen"))]
+
+use chrono::{DateTime, TimeZone, Utc};
+use microshard_uuid::{MicroShardError, MicroShardUUID};
+
+#[test]
+fn test_datetime_round_trip() {
+    let dt = Utc.with_ymd_and_hms(2025, 12, 12, 1, 35, 0).unwrap()
+        + chrono::Duration::microseconds(123_456);
+    let id = MicroShardUUID::from_datetime(dt, 42).unwrap();
+    assert_eq!(id.shard_id(), 42);
+    assert_eq!(id.timestamp_micros(), dt.timestamp_micros() as u64);
+    assert_eq!(id.datetime(), dt);
+
+    // Sub-microsecond digits are truncated
+    let nanos = dt + chrono::Duration::nanoseconds(999);
+    assert_eq!(
+        MicroShardUUID::from_datetime(nanos, 42).unwrap().datetime(),
+        dt
+    );
+
+    // Both ends of the 54-bit range
+    let epoch = MicroShardUUID::from_datetime(DateTime::UNIX_EPOCH, 0).unwrap();
+    assert_eq!(epoch.timestamp_micros(), 0);
+    let max = MicroShardUUID::from_parts((1 << 54) - 1, 0, 0).unwrap();
+    assert_eq!(max.datetime().timestamp_micros(), (1 << 54) - 1);
+    assert_eq!(
+        MicroShardUUID::from_datetime(max.datetime(), 0)
+            .unwrap()
+            .timestamp_micros(),
+        (1 << 54) - 1
+    );
+}
+
+#[test]
+fn test_from_datetime_errors() {
+    let before = Utc.with_ymd_and_hms(1969, 12, 31, 23, 59, 59).unwrap();
+    assert_eq!(
+        MicroShardUUID::from_datetime(before, 1),
+        Err(MicroShardError::TimeUnderflow)
+    );
+    let after = Utc.with_ymd_and_hms(2542, 1, 1, 0, 0, 0).unwrap();
+    assert_eq!(
+        MicroShardUUID::from_datetime(after, 1),
+        Err(MicroShardError::TimeOverflow)
+    );
+}
@@ -0,0 +1,11 @@
+// File: tests/common/mod.rs
+//! Fixtures shared by the integration tests. Each test crate compiles its
+//! own copy and uses only part of it.
+#![allow(dead_code)]
+
+use microshard_uuid::MicroShardUUID;
+
+/// A fixed ID with distinct nibbles, so byte swaps show up in assertions.
+pub fn sample() -> MicroShardUUID {
+    MicroShardUUID::from_u128(0x0123_4567_89ab_8def_8123_4567_89ab_cdef).unwrap()
+}
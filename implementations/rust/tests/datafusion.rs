@@ -0,0 +1,73 @@
+// File: tests/datafusion.rs
+#![cfg(all(feature = "datafusion", feature = "gen", feature = "parse"))]
+
+use std::sync::Arc;
+
+use arrow_array::{Array, ArrayRef, TimestampMicrosecondArray, UInt32Array};
+use arrow_schema::{DataType, Field};
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::ScalarValue;
+use datafusion_expr::registry::{FunctionRegistry, MemoryFunctionRegistry};
+use datafusion_expr::{ColumnarValue, ScalarFunctionArgs, ScalarUDF};
+use microshard_uuid::{arrow, datafusion, MicroShardUUID};
+
+fn invoke(udf: &ScalarUDF, arg: ColumnarValue, rows: usize) -> ColumnarValue {
+    let arg_type = DataType::FixedSizeBinary(16);
+    let return_type = udf.return_type(std::slice::from_ref(&arg_type)).unwrap();
+    udf.invoke_with_args(ScalarFunctionArgs {
+        args: vec![arg],
+        arg_fields: vec![Arc::new(Field::new("id", arg_type, true))],
+        number_rows: rows,
+        return_field: Arc::new(Field::new(udf.name(), return_type, true)),
+        config_options: Arc::new(ConfigOptions::default()),
+    })
+    .unwrap()
+}
+
+#[test]
+fn test_datafusion_udfs_over_arrays() {
+    let ids = [
+        MicroShardUUID::from_iso("2024-05-01T10:00:00Z", 1).unwrap(),
+        MicroShardUUID::from_iso("2024-05-01T10:00:01Z", 2).unwrap(),
+    ];
+    let col: ArrayRef = Arc::new(arrow::to_fixed_size_binary(&ids));
+
+    let ColumnarValue::Array(ts) = invoke(
+        &datafusion::timestamp_udf(),
+        ColumnarValue::Array(col.clone()),
+        2,
+    ) else {
+        panic!("expected array output");
+    };
+    let ts = ts
+        .as_any()
+        .downcast_ref::<TimestampMicrosecondArray>()
+        .unwrap();
+    assert_eq!(ts.value(1) as u64, ids[1].timestamp_micros());
+
+    let ColumnarValue::Array(shards) =
+        invoke(&datafusion::shard_udf(), ColumnarValue::Array(col), 2)
+    else {
+        panic!("expected array output");
+    };
+    let shards = shards.as_any().downcast_ref::<UInt32Array>().unwrap();
+    assert_eq!(shards.values().to_vec(), vec![1, 2]);
+}
+
+#[test]
+fn test_datafusion_udfs_scalar_and_registry() {
+    let id = MicroShardUUID::from_micros(42, 9).unwrap();
+    let arg = ColumnarValue::Scalar(ScalarValue::FixedSizeBinary(
+        16,
+        Some(id.as_bytes().to_vec()),
+    ));
+    match invoke(&datafusion::shard_udf(), arg, 5) {
+        ColumnarValue::Scalar(ScalarValue::UInt32(Some(9))) => {}
+        other => panic!("unexpected output: {:?}", other),
+    }
+
+    let mut registry = MemoryFunctionRegistry::default();
+    datafusion::register_udfs(&mut registry).unwrap();
+    assert!(registry.udf("microshard_timestamp").is_ok());
+    assert!(registry.udf("microshard_shard").is_ok());
+}
@@ -0,0 +1,107 @@
+// File: tests/diesel.rs
+#![cfg(any(
+    feature = "diesel-postgres",
+    feature = "diesel-mysql",
+    feature = "diesel-sqlite"
+))]
+
+mod common;
+
+use common::sample;
+use diesel::prelude::*;
+#[cfg(feature = "diesel-sqlite")]
+use microshard_uuid::MicroShardUUID;
+
+diesel::table! {
+    events (id) {
+        id -> Binary,
+        name -> Text,
+    }
+}
+
+#[cfg(feature = "diesel-postgres")]
+#[test]
+fn test_postgres_binds_uuid_and_binary_columns() {
+    use diesel::pg::Pg;
+
+    diesel::table! {
+        pg_events (id) {
+            id -> Uuid,
+        }
+    }
+
+    let query = pg_events::table.filter(pg_events::id.eq(sample()));
+    let sql = diesel::debug_query::<Pg, _>(&query).to_string();
+    assert!(sql.contains("WHERE (\"pg_events\".\"id\" = $1)"), "{}", sql);
+    assert!(sql.contains(&format!("{:?}", sample())), "{}", sql);
+
+    let query = events::table.filter(events::id.gt(sample()));
+    let sql = diesel::debug_query::<Pg, _>(&query).to_string();
+    assert!(sql.contains("\"events\".\"id\" > $1"), "{}", sql);
+}
+
+#[cfg(feature = "diesel-mysql")]
+#[test]
+fn test_mysql_binds_binary_column() {
+    use diesel::mysql::Mysql;
+
+    let query = diesel::insert_into(events::table)
+        .values((events::id.eq(sample()), events::name.eq("created")));
+    let sql = diesel::debug_query::<Mysql, _>(&query).to_string();
+    assert!(
+        sql.starts_with("INSERT INTO `events` (`id`, `name`) VALUES (?, ?)"),
+        "{}",
+        sql
+    );
+}
+
+#[cfg(feature = "diesel-sqlite")]
+#[test]
+fn test_sqlite_blob_round_trip() {
+    let mut conn = SqliteConnection::establish(":memory:").unwrap();
+    diesel::sql_query("CREATE TABLE events (id BLOB PRIMARY KEY, name TEXT NOT NULL)")
+        .execute(&mut conn)
+        .unwrap();
+
+    let first = sample();
+    let second = first.successor().unwrap();
+    for (id, name) in [(second, "second"), (first, "first")] {
+        diesel::insert_into(events::table)
+            .values((events::id.eq(id), events::name.eq(name)))
+            .execute(&mut conn)
+            .unwrap();
+    }
+
+    // Stored as 16-byte blobs, so ORDER BY id is creation order
+    let rows: Vec<(MicroShardUUID, String)> =
+        events::table.order(events::id).load(&mut conn).unwrap();
+    assert_eq!(
+        rows,
+        vec![(first, "first".to_owned()), (second, "second".to_owned())]
+    );
+    let lengths: Vec<i64> =
+        diesel::dsl::sql::<diesel::sql_types::BigInt>("SELECT length(id) FROM events")
+            .load(&mut conn)
+            .unwrap();
+    assert_eq!(lengths, vec![16, 16]);
+
+    let name: String = events::table
+        .filter(events::id.eq(first))
+        .select(events::name)
+        .first(&mut conn)
+        .unwrap();
+    assert_eq!(name, "first");
+
+    // Columns filled with canonical strings decode too
+    let text: MicroShardUUID = diesel::dsl::sql::<diesel::sql_types::Binary>(
+        "SELECT '01234567-89ab-8def-8123-456789abcdef'",
+    )
+    .get_result(&mut conn)
+    .unwrap();
+    assert_eq!(text, first);
+
+    // Other blobs are rejected rather than misread
+    let bad = diesel::dsl::sql::<diesel::sql_types::Binary>("SELECT x'0102'")
+        .get_result::<MicroShardUUID>(&mut conn);
+    assert!(bad.is_err());
+}
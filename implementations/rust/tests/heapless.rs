@@ -0,0 +1,21 @@
+// File: tests/heapless.rs
+#![cfg(all(feature = "heapless", feature = "fmt-iso"))]
+
+use microshard_uuid::MicroShardUUID;
+
+#[test]
+fn test_heapless_strings_match_allocating_forms() {
+    let ids = [
+        MicroShardUUID::from_u128(0x0000_0000_0000_8000_8000_0000_0000_0000).unwrap(),
+        MicroShardUUID::from_u128(0x1916_dd14_36ed_8000_8003_0391_2345_6789).unwrap(),
+        MicroShardUUID::from_u128(0xffff_ffff_ffff_8fff_bfff_ffff_ffff_ffff).unwrap(),
+    ];
+    for id in ids {
+        assert_eq!(id.to_iso_heapless().as_str(), id.iso_display().to_string());
+        assert_eq!(id.to_hex_heapless().as_str(), id.to_string());
+    }
+    assert_eq!(
+        ids[2].to_iso_heapless().as_str(),
+        "2540-11-07T23:35:09.481983Z"
+    );
+}
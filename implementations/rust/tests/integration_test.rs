@@ -1,7 +1,34 @@
 // File: tests/integration_tests.rs
+#![cfg(all(
+    feature = "gen",
+    feature = "parse",
+    feature = "fmt-iso",
+    feature = "interop"
+))]
 
-use microshard_uuid::{MicroShardUUID, MicroShardError};
-use std::time::{SystemTime, UNIX_EPOCH};
+use microshard_uuid::affinity::{self, ShardAffinity};
+use microshard_uuid::any::AnyUuid;
+use microshard_uuid::bloom::UuidBloom;
+use microshard_uuid::envelope;
+use microshard_uuid::interval::{IntervalIndex, Window};
+use microshard_uuid::layout::{self, Layout};
+use microshard_uuid::merge::{self, JoinItem};
+use microshard_uuid::shard;
+use microshard_uuid::sql::{self, Dialect};
+use microshard_uuid::stress::{self, GeneratorConfig};
+use microshard_uuid::time_source::{SystemTimeSource, TimeSource};
+use microshard_uuid::validate::validate_batch;
+use microshard_uuid::{
+    conformance, iso_to_micros, iso_to_micros_lenient, msuuid, search, test_support, Bucket,
+    ClockRegressionPolicy, Cursor, DecodedMicroShard, Direction, FixedStr, Ksuid,
+    MicroShardBuilder, MicroShardError, MicroShardGenerator, MicroShardUUID, MicroShardUUIDNanos,
+    MicroShardV7, ShardId, ShardIdError, ShardedUuid, TimeUnit, Ulid, UniqueGenerator,
+    KSUID_EPOCH_SECS,
+};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 // Constant for 2^54 - 1 (Max supported microsecond timestamp)
 const MAX_TIME_MICROS: u64 = 18_014_398_509_481_983;
@@ -26,14 +53,14 @@ fn test_generation_and_extraction() {
         .unwrap()
         .as_micros() as u64;
     let extracted = uuid.timestamp_micros();
-    
+
     // Allow for small clock skew/execution time
     assert!((now as i64 - extracted as i64).abs() < 1_000_000);
 }
 
 #[test]
 fn test_backfill() {
-    let ts = 1000_000_000_000; // Arbitrary time
+    let ts = 1_000_000_000_000; // Arbitrary time
     let uuid = MicroShardUUID::from_micros(ts, 99).unwrap();
     assert_eq!(uuid.timestamp_micros(), ts);
     assert_eq!(uuid.shard_id(), 99);
@@ -145,7 +172,7 @@ fn test_error_handling() {
     let res = MicroShardUUID::from_micros(overflow_micros, 1);
 
     assert!(res.is_err(), "Should catch time overflow");
-    
+
     // Verify specific error formatting
     let err = res.unwrap_err();
     assert_eq!(format!("{}", err), "Time overflow (Year > 2541)");
@@ -258,3 +285,3035 @@ fn test_iso_roundtrip_consistency() {
         "Normalization roundtrip failed"
     );
 }
+
+#[test]
+fn test_unique_generator_strictly_increasing() {
+    let gen = UniqueGenerator::new(7).unwrap();
+    let mut prev = gen.next().unwrap();
+    for _ in 0..100_000 {
+        let next = gen.next().unwrap();
+        assert!(next > prev, "{} should sort after {}", next, prev);
+        assert_eq!(next.shard_id(), 7);
+        prev = next;
+    }
+}
+
+#[test]
+fn test_unique_generator_threads() {
+    let gen = Arc::new(UniqueGenerator::new(8).unwrap());
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let gen = Arc::clone(&gen);
+            thread::spawn(move || (0..25_000).map(|_| gen.next().unwrap()).collect::<Vec<_>>())
+        })
+        .collect();
+
+    let mut seen = HashSet::new();
+    for handle in handles {
+        for id in handle.join().unwrap() {
+            assert!(seen.insert(id), "Duplicate ID: {}", id);
+        }
+    }
+    assert_eq!(seen.len(), 100_000);
+}
+
+#[test]
+fn test_unique_generator_clock_regression() {
+    let gen = UniqueGenerator::new(9)
+        .unwrap()
+        .with_max_regression(Duration::from_millis(10));
+    let base = 1_700_000_000_000_000;
+
+    let first = gen.next_at(base).unwrap();
+
+    // Within tolerance: hold the last timestamp and keep counting
+    let held = gen.next_at(base - 5_000).unwrap();
+    assert!(held > first);
+    assert_eq!(held.timestamp_micros(), base);
+
+    // Beyond tolerance: explicit failure
+    assert_eq!(
+        gen.next_at(base - 20_000),
+        Err(MicroShardError::ClockMovedBackwards(20_000))
+    );
+
+    // Clock catches up: generation resumes with the new time
+    let resumed = gen.next_at(base + 1).unwrap();
+    assert!(resumed > held);
+    assert_eq!(resumed.timestamp_micros(), base + 1);
+}
+
+#[test]
+fn test_relative_offset_generation() {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_micros() as i64;
+
+    let five_min = 300_000_000i64;
+    let past = MicroShardUUID::generate_offset(3, -five_min).unwrap();
+    let future = MicroShardUUID::generate_offset(3, five_min).unwrap();
+    let ago = MicroShardUUID::from_duration_ago(Duration::from_secs(300), 3).unwrap();
+
+    for (uuid, expected) in [
+        (past, now - five_min),
+        (future, now + five_min),
+        (ago, now - five_min),
+    ] {
+        assert_eq!(uuid.shard_id(), 3);
+        assert!((uuid.timestamp_micros() as i64 - expected).abs() < 1_000_000);
+    }
+    assert!(past < future);
+
+    // Out of range in either direction
+    assert_eq!(
+        MicroShardUUID::generate_offset(3, i64::MIN),
+        Err(MicroShardError::TimeUnderflow)
+    );
+    assert_eq!(
+        MicroShardUUID::generate_offset(3, i64::MAX),
+        Err(MicroShardError::TimeOverflow)
+    );
+    assert_eq!(
+        MicroShardUUID::from_duration_ago(Duration::from_secs(u64::MAX), 3),
+        Err(MicroShardError::TimeUnderflow)
+    );
+}
+
+#[test]
+fn test_freeze_time_scoped() {
+    let base = 1_700_000_000_000_000;
+    {
+        let _outer = test_support::freeze_time(base);
+        assert_eq!(
+            MicroShardUUID::generate(1).unwrap().timestamp_micros(),
+            base
+        );
+
+        test_support::advance(Duration::from_millis(5));
+        assert_eq!(
+            MicroShardUUID::generate(1).unwrap().timestamp_micros(),
+            base + 5_000
+        );
+
+        {
+            let _inner = test_support::freeze_time(42);
+            assert_eq!(MicroShardUUID::generate(1).unwrap().timestamp_micros(), 42);
+        }
+        // Inner guard dropped: outer frozen time restored
+        assert_eq!(test_support::frozen_micros(), Some(base + 5_000));
+
+        {
+            // Advancing past u64 microseconds saturates instead of wrapping
+            let _inner = test_support::freeze_time(42);
+            test_support::advance(Duration::from_secs(1 << 60));
+            assert_eq!(test_support::frozen_micros(), Some(u64::MAX));
+        }
+
+        // Other threads keep the real clock
+        let other = thread::spawn(|| MicroShardUUID::generate(1).unwrap().timestamp_micros())
+            .join()
+            .unwrap();
+        assert!(other > base + 5_000);
+    }
+    assert_eq!(test_support::frozen_micros(), None);
+}
+
+#[test]
+fn test_conformance_self_check() {
+    assert_eq!(conformance::self_check(), Ok(()));
+}
+
+#[test]
+fn test_unique_generator_audit_hook() {
+    let audited = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&audited);
+    let gen = UniqueGenerator::new(11)
+        .unwrap()
+        .on_generate(move |id| sink.lock().unwrap().push(*id));
+
+    let minted: Vec<_> = (0..100).map(|_| gen.next().unwrap()).collect();
+
+    // Failed generations are not audited
+    assert!(gen.next_at(0).is_err());
+
+    assert_eq!(*audited.lock().unwrap(), minted);
+}
+
+#[test]
+fn test_anonymize() {
+    let uuid = MicroShardUUID::from_micros(1_700_000_000_123_456, 5_000).unwrap();
+
+    let anon = uuid.anonymize();
+    assert_eq!(anon.as_u128() & 0xF_FFFF_FFFF, 0);
+    assert_eq!(anon.timestamp_micros(), uuid.timestamp_micros());
+    assert_eq!(anon.shard_id(), 5_000);
+    assert!(MicroShardUUID::from_u128(anon.as_u128()).is_ok());
+
+    let coarse = uuid.anonymize_shard(1024);
+    assert_eq!(coarse.shard_id(), 4_096);
+    assert_eq!(coarse.timestamp_micros(), uuid.timestamp_micros());
+    assert_eq!(coarse.as_u128() & 0xF_FFFF_FFFF, 0);
+
+    // Degenerate bucket sizes keep the shard
+    assert_eq!(uuid.anonymize_shard(0), anon);
+    assert_eq!(uuid.anonymize_shard(1), anon);
+}
+
+#[test]
+fn test_truncate_time() {
+    let a = MicroShardUUID::from_iso("2024-05-01T10:17:42.123456Z", 77).unwrap();
+    let b = MicroShardUUID::from_iso("2024-05-01T10:59:01.000001Z", 77).unwrap();
+
+    let ta = a.truncate_time(Duration::from_secs(3600));
+    let tb = b.truncate_time(Duration::from_secs(3600));
+    assert_eq!(ta.to_iso_string(), "2024-05-01T10:00:00.000000Z");
+    assert_eq!(tb.to_iso_string(), "2024-05-01T10:00:00.000000Z");
+    assert_eq!(ta.shard_id(), 77);
+    assert!(MicroShardUUID::from_u128(ta.as_u128()).is_ok());
+
+    // Deterministic, and distinct inputs stay distinct
+    assert_eq!(ta, a.truncate_time(Duration::from_secs(3600)));
+    assert_ne!(ta, tb);
+
+    // Sub-microsecond granularity is a no-op
+    assert_eq!(a.truncate_time(Duration::from_nanos(500)), a);
+}
+
+#[test]
+fn test_bloom_filter() {
+    let gen = UniqueGenerator::new(21).unwrap();
+    let present: Vec<_> = (0..10_000).map(|_| gen.next().unwrap()).collect();
+    let absent: Vec<_> = (0..10_000).map(|_| gen.next().unwrap()).collect();
+
+    let mut bloom = UuidBloom::new(10_000, 0.01);
+    for id in &present {
+        bloom.insert(id);
+    }
+
+    // No false negatives
+    assert!(present.iter().all(|id| bloom.contains(id)));
+
+    // False-positive rate close to the configured target
+    let fp = absent.iter().filter(|id| bloom.contains(id)).count();
+    assert!(fp < 200, "Too many false positives: {}", fp);
+
+    // Serialization round-trip
+    let restored = UuidBloom::from_bytes(&bloom.to_bytes()).unwrap();
+    assert_eq!(restored, bloom);
+    assert!(UuidBloom::from_bytes(&bloom.to_bytes()[..30]).is_err());
+
+    // Union of summaries
+    let mut other = UuidBloom::new(10_000, 0.01);
+    other.insert(&absent[0]);
+    bloom.union(&other).unwrap();
+    assert!(bloom.contains(&absent[0]));
+    assert!(bloom.union(&UuidBloom::new(10, 0.5)).is_err());
+}
+
+#[test]
+fn test_search_helpers() {
+    let base = 1_700_000_000_000_000;
+    // Two IDs per microsecond on different shards
+    let mut sorted: Vec<_> = (0..100u64)
+        .flat_map(|i| {
+            [
+                MicroShardUUID::from_micros(base + i, 1).unwrap(),
+                MicroShardUUID::from_micros(base + i, u32::MAX).unwrap(),
+            ]
+        })
+        .collect();
+    sorted.sort();
+
+    assert_eq!(search::find(&sorted, &sorted[42]), Some(42));
+    let missing = MicroShardUUID::from_micros(base + 500, 1).unwrap();
+    assert_eq!(search::find(&sorted, &missing), None);
+
+    assert_eq!(search::lower_bound(&sorted, &sorted[10]), 10);
+    assert_eq!(search::upper_bound(&sorted, &sorted[10]), 11);
+    assert_eq!(search::lower_bound(&sorted, &missing), sorted.len());
+
+    // Half-open [base + 10, base + 20): 10 microseconds x 2 shards
+    let window = search::slice_for_time_range(&sorted, base + 10, base + 20);
+    assert_eq!(window.len(), 20);
+    assert_eq!(window.first().unwrap().timestamp_micros(), base + 10);
+    assert_eq!(window.last().unwrap().timestamp_micros(), base + 19);
+
+    assert!(search::slice_for_time_range(&sorted, base + 20, base + 10).is_empty());
+}
+
+#[test]
+fn test_merge_join() {
+    let ids: Vec<_> = (0..6)
+        .map(|i| MicroShardUUID::from_micros(1_000 + i, 1).unwrap())
+        .collect();
+    let left = vec![ids[0], ids[1], ids[3], ids[5]];
+    let right = vec![ids[1], ids[2], ids[3], ids[4]];
+
+    let joined: Vec<_> = merge::join(left, right).collect();
+    assert_eq!(
+        joined,
+        vec![
+            JoinItem::LeftOnly(ids[0]),
+            JoinItem::Both(ids[1]),
+            JoinItem::RightOnly(ids[2]),
+            JoinItem::Both(ids[3]),
+            JoinItem::RightOnly(ids[4]),
+            JoinItem::LeftOnly(ids[5]),
+        ]
+    );
+    assert_eq!(joined.iter().map(JoinItem::id).collect::<Vec<_>>(), ids);
+
+    assert_eq!(merge::join(Vec::new(), Vec::new()).count(), 0);
+}
+
+#[test]
+fn test_merge_kway() {
+    let base = 1_700_000_000_000_000;
+    let shards: Vec<Vec<_>> = (0..4u32)
+        .map(|shard| {
+            (0..250u64)
+                .map(|i| MicroShardUUID::from_micros(base + i * 4 + shard as u64, shard).unwrap())
+                .collect()
+        })
+        .collect();
+
+    let mut expected: Vec<_> = shards.iter().flatten().copied().collect();
+    expected.sort();
+
+    let merged = merge::kway(shards);
+    assert_eq!(merged.size_hint(), (1_000, Some(1_000)));
+    assert_eq!(merged.collect::<Vec<_>>(), expected);
+
+    let empty: Vec<Vec<MicroShardUUID>> = vec![vec![], vec![]];
+    assert_eq!(merge::kway(empty).count(), 0);
+}
+
+#[test]
+fn test_interval_index() {
+    let day = 86_400_000_000u64;
+    let now = 1_700_000_000_000_000u64;
+    let index = IntervalIndex::new(vec![
+        Window::new("hot", now - 7 * day, now + day),
+        Window::new("cold", 0, now - 30 * day),
+        Window::new("warm", now - 30 * day, now - 7 * day),
+    ])
+    .unwrap();
+
+    let tier = |micros| {
+        let id = MicroShardUUID::from_micros(micros, 1).unwrap();
+        index.lookup(&id).map(|w| w.name.as_str())
+    };
+    assert_eq!(tier(now), Some("hot"));
+    assert_eq!(tier(now - 7 * day), Some("hot"));
+    assert_eq!(tier(now - 7 * day - 1), Some("warm"));
+    assert_eq!(tier(now - 100 * day), Some("cold"));
+    assert_eq!(tier(now + 2 * day), None);
+
+    let names = |ws: &[Window]| ws.iter().map(|w| w.name.clone()).collect::<Vec<_>>();
+    assert_eq!(
+        names(index.overlapping(now - 10 * day, now - 7 * day)),
+        vec!["warm"]
+    );
+    assert_eq!(
+        names(index.overlapping(now - 10 * day, now - 7 * day + 1)),
+        vec!["warm", "hot"]
+    );
+
+    // ID range of a window is tight and valid
+    let (lo, hi) = index.windows()[1].id_range().unwrap();
+    assert_eq!(lo.timestamp_micros(), now - 30 * day);
+    assert_eq!(hi.timestamp_micros(), now - 7 * day - 1);
+    assert_eq!(index.overlapping_ids(&lo, &hi).len(), 1);
+
+    // Empty or out-of-range windows have no ID range
+    assert_eq!(Window::new("x", 0, 0).id_range(), None);
+    assert_eq!(Window::new("x", 10, 5).id_range(), None);
+    assert_eq!(Window::new("x", 0, u64::MAX).id_range(), None);
+    let (_, last) = Window::new("x", 0, MAX_TIME_MICROS + 1).id_range().unwrap();
+    assert_eq!(last.timestamp_micros(), MAX_TIME_MICROS);
+
+    // Overlapping and empty windows are rejected
+    assert_eq!(
+        IntervalIndex::new(vec![Window::new("a", 0, 10), Window::new("b", 5, 20)]),
+        Err(MicroShardError::InvalidRange)
+    );
+    assert!(IntervalIndex::new(vec![Window::new("a", 10, 10)]).is_err());
+}
+
+#[test]
+fn test_shard_affinity() {
+    let pool = ["conn-0", "conn-1", "conn-2"];
+
+    let a = MicroShardUUID::generate(7).unwrap();
+    let b = MicroShardUUID::generate(7).unwrap();
+    assert_eq!(a.affinity_key(), 7);
+
+    // Same shard always lands on the same connection
+    assert_eq!(a.pool_index(pool.len()), Some(1));
+    assert_eq!(affinity::pick(&pool, &a), Some(&"conn-1"));
+    assert_eq!(affinity::pick(&pool, &b), affinity::pick(&pool, &a));
+    assert_eq!(affinity::pick(&pool, &7u32), Some(&"conn-1"));
+
+    let empty: [&str; 0] = [];
+    assert_eq!(affinity::pick(&empty, &a), None);
+}
+
+#[test]
+fn test_sql_values_writer() {
+    let a = MicroShardUUID::from_u128(0x1916_dd14_36ed_8000_8003_0391_2345_6789).unwrap();
+    let b = MicroShardUUID::from_u128(0x1916_dd14_36ed_8000_8003_0391_2345_678a).unwrap();
+    let c = MicroShardUUID::from_u128(0x1916_dd14_36ed_8000_8003_0391_2345_678b).unwrap();
+
+    assert_eq!(
+        Dialect::Postgres.literal(&a),
+        "'1916dd14-36ed-8000-8003-039123456789'::uuid"
+    );
+    assert_eq!(
+        Dialect::PostgresBytea.literal(&a),
+        "decode('1916dd1436ed80008003039123456789', 'hex')"
+    );
+    assert_eq!(
+        Dialect::MySql.literal(&a),
+        "UNHEX('1916dd1436ed80008003039123456789')"
+    );
+    assert_eq!(
+        Dialect::Sqlite.literal(&a),
+        "X'1916dd1436ed80008003039123456789'"
+    );
+    assert_eq!(
+        Dialect::ClickHouse.literal(&a),
+        "toUUID('1916dd14-36ed-8000-8003-039123456789')"
+    );
+
+    let mut out = String::new();
+    sql::write_values(&mut out, [a, b, c], Dialect::Sqlite, 2).unwrap();
+    assert_eq!(
+        out,
+        "VALUES (X'1916dd1436ed80008003039123456789'), (X'1916dd1436ed8000800303912345678a')\n\
+         VALUES (X'1916dd1436ed8000800303912345678b')\n"
+    );
+
+    let mut out = String::new();
+    sql::write_inserts(&mut out, "events", "id", [a, b], Dialect::MySql, 0).unwrap();
+    assert_eq!(
+        out,
+        "INSERT INTO events (id) VALUES (UNHEX('1916dd1436ed80008003039123456789')), \
+         (UNHEX('1916dd1436ed8000800303912345678a'));\n"
+    );
+
+    let mut out = String::new();
+    sql::write_values(&mut out, Vec::new(), Dialect::Postgres, 10).unwrap();
+    assert!(out.is_empty());
+}
+
+#[test]
+fn test_sql_clickhouse_range() {
+    let key = "bitOr(bitShiftLeft(reinterpretAsUInt128(id), 64), \
+               bitShiftRight(reinterpretAsUInt128(id), 64))";
+    assert_eq!(sql::clickhouse_sort_key_expr("id"), key);
+
+    let lo = MicroShardUUID::from_u128(0x1916_dd14_36ed_8000_8003_0391_2345_6789).unwrap();
+    let hi = lo.successor().unwrap();
+    assert_eq!(
+        sql::clickhouse_between("id", &lo, &hi),
+        format!(
+            "{} BETWEEN toUInt128('{}') AND toUInt128('{}')",
+            key,
+            lo.as_u128(),
+            hi.as_u128()
+        )
+    );
+
+    let (start, end) = MicroShardUUID::range_for_micros(1_000..2_000);
+    assert_eq!(
+        sql::clickhouse_time_range("e.id", 1_000..2_000),
+        sql::clickhouse_between("e.id", &start, &end)
+    );
+}
+
+#[test]
+fn test_sql_extract_exprs() {
+    assert_eq!(
+        sql::postgres_extract_shard_expr("id"),
+        "(((get_byte(uuid_send(id), 7)::bigint & 63) << 26) \
+         | ((get_byte(uuid_send(id), 8)::bigint & 63) << 20) \
+         | (get_byte(uuid_send(id), 9)::bigint << 12) \
+         | (get_byte(uuid_send(id), 10)::bigint << 4) \
+         | (get_byte(uuid_send(id), 11)::bigint >> 4))"
+    );
+    assert_eq!(
+        sql::mysql_extract_micros_expr("e.id"),
+        "((ORD(SUBSTRING(e.id, 1, 1)) << 46) \
+         | (ORD(SUBSTRING(e.id, 2, 1)) << 38) \
+         | (ORD(SUBSTRING(e.id, 3, 1)) << 30) \
+         | (ORD(SUBSTRING(e.id, 4, 1)) << 22) \
+         | (ORD(SUBSTRING(e.id, 5, 1)) << 14) \
+         | (ORD(SUBSTRING(e.id, 6, 1)) << 6) \
+         | ((ORD(SUBSTRING(e.id, 7, 1)) & 15) << 2) \
+         | (ORD(SUBSTRING(e.id, 8, 1)) >> 6))"
+    );
+
+    // The shard and micros expressions pick the same bytes in both dialects
+    let pg_shard = sql::postgres_extract_shard_expr("id");
+    let my_shard = sql::mysql_extract_shard_expr("id");
+    for i in 7..=11 {
+        assert!(pg_shard.contains(&format!("get_byte(uuid_send(id), {})", i)));
+        assert!(my_shard.contains(&format!("ORD(SUBSTRING(id, {}, 1))", i + 1)));
+    }
+    assert!(!my_shard.contains("get_byte"));
+
+    // Timestamps split the micros with integer division
+    let micros = sql::postgres_extract_micros_expr("id");
+    assert_eq!(
+        sql::postgres_extract_timestamp_expr("id"),
+        format!(
+            "(timestamptz 'epoch' + ({m} / 1000000) * interval '1 second' \
+             + ({m} % 1000000) * interval '1 microsecond')",
+            m = micros
+        )
+    );
+    let micros = sql::mysql_extract_micros_expr("id");
+    assert_eq!(
+        sql::mysql_extract_timestamp_expr("id"),
+        format!(
+            "DATE_ADD(DATE_ADD(FROM_UNIXTIME(0), INTERVAL ({m} DIV 1000000) SECOND), \
+             INTERVAL ({m} MOD 1000000) MICROSECOND)",
+            m = micros
+        )
+    );
+}
+
+#[test]
+fn test_pg_binary_param() {
+    let uuid = MicroShardUUID::from_u128(0x1916_dd14_36ed_8000_8003_0391_2345_6789).unwrap();
+
+    let param = uuid.to_pg_binary_param();
+    assert_eq!(param, uuid.as_bytes());
+    assert_eq!(MicroShardUUID::from_pg_binary(&param), Ok(uuid));
+
+    let mut bind = Vec::new();
+    uuid.write_pg_binary_param(&mut bind);
+    assert_eq!(&bind[..4], &[0, 0, 0, 16]);
+    assert_eq!(&bind[4..], &param);
+
+    assert_eq!(
+        MicroShardUUID::from_pg_binary(&param[..15]),
+        Err(MicroShardError::InvalidEncoding)
+    );
+    assert_eq!(
+        MicroShardUUID::from_pg_binary(&[0u8; 16]),
+        Err(MicroShardError::InvalidVersion(0))
+    );
+}
+
+#[test]
+fn test_mysql_ordered_bytes() {
+    let uuid = MicroShardUUID::from_u128(0x1916_dd14_36ed_8000_8003_0391_2345_6789).unwrap();
+
+    // Matches MySQL: HEX(UUID_TO_BIN('1916dd14-36ed-8000-8003-039123456789', 1))
+    let stored = uuid.to_mysql_ordered_bytes();
+    assert_eq!(
+        stored,
+        [
+            0x80, 0x00, 0x36, 0xed, 0x19, 0x16, 0xdd, 0x14, 0x80, 0x03, 0x03, 0x91, 0x23, 0x45,
+            0x67, 0x89
+        ]
+    );
+    assert_eq!(MicroShardUUID::from_mysql_ordered_bytes(stored), Ok(uuid));
+
+    // Canonical bytes are not a valid swapped encoding (version nibble moves)
+    assert!(MicroShardUUID::from_mysql_ordered_bytes(uuid.as_bytes()).is_err());
+}
+
+#[test]
+fn test_clickhouse_bytes() {
+    let uuid = MicroShardUUID::from_u128(0x1916_dd14_36ed_8000_8003_0391_2345_6789).unwrap();
+
+    // Two little-endian UInt64 halves, high half first (RowBinary layout)
+    let stored = uuid.to_clickhouse_bytes();
+    assert_eq!(
+        stored,
+        [
+            0x00, 0x80, 0xed, 0x36, 0x14, 0xdd, 0x16, 0x19, 0x89, 0x67, 0x45, 0x23, 0x91, 0x03,
+            0x03, 0x80
+        ]
+    );
+    assert_eq!(MicroShardUUID::from_clickhouse_bytes(stored), Ok(uuid));
+    assert!(MicroShardUUID::from_clickhouse_bytes(uuid.as_bytes()).is_err());
+
+    // reinterpretAsUInt128 puts the low half on top; swapping the halves
+    // (the sort key) restores creation order
+    let early = MicroShardUUID::from_parts(1_000, 9, (1 << 36) - 1).unwrap();
+    let late = MicroShardUUID::from_parts(2_000, 1, 0).unwrap();
+    let reinterpret = |id: &MicroShardUUID| u128::from_le_bytes(id.to_clickhouse_bytes());
+    assert!(reinterpret(&early) > reinterpret(&late));
+    for id in [early, late, uuid] {
+        assert_eq!(reinterpret(&id).rotate_left(64), id.as_u128());
+    }
+}
+
+#[test]
+fn test_string_parsing() {
+    let uuid = MicroShardUUID::from_micros(1_765_503_300_123_456, 12345).unwrap();
+    let text = uuid.to_string();
+    assert_eq!(MicroShardUUID::parse(&text), Ok(uuid));
+    assert_eq!(text.to_uppercase().parse::<MicroShardUUID>(), Ok(uuid));
+
+    let bad = [
+        "",
+        "1916dd1436ed80008003039123456789",
+        "1916dd14-36ed-8000-8003-03912345678",
+        "1916dd14-36ed-8000-8003-03912345678g",
+        "1916dd14_36ed-8000-8003-039123456789",
+    ];
+    for s in bad {
+        assert_eq!(
+            MicroShardUUID::parse(s),
+            Err(MicroShardError::InvalidUuidFormat),
+            "{}",
+            s
+        );
+    }
+
+    // Well-formed but not a MicroShard ID
+    assert_eq!(
+        MicroShardUUID::parse("550e8400-e29b-41d4-a716-446655440000"),
+        Err(MicroShardError::InvalidVersion(4))
+    );
+}
+
+#[test]
+fn test_any_uuid() {
+    let v4: AnyUuid = "550e8400-e29b-41d4-a716-446655440000".parse().unwrap();
+    let v7: AnyUuid = "018f3c2a-1b2c-7d3e-8f40-123456789abc".parse().unwrap();
+    let ms = MicroShardUUID::from_iso("2024-05-01T10:00:00Z", 9).unwrap();
+    let v8 = AnyUuid::from(ms);
+    let nil = AnyUuid::from_u128(0);
+
+    assert!(matches!(v4, AnyUuid::V4(_)));
+    assert!(matches!(v7, AnyUuid::V7(_)));
+    assert_eq!(AnyUuid::parse(&ms.to_string()), Ok(v8));
+    assert_eq!(nil, AnyUuid::Other(0));
+    assert_eq!(v7.version(), 7);
+
+    assert_eq!(v8.as_microshard(), Some(&ms));
+    assert_eq!(v4.as_microshard(), None);
+    assert_eq!(MicroShardUUID::try_from(v8), Ok(ms));
+    assert!(MicroShardUUID::try_from(v7).is_err());
+
+    assert_eq!(v8.timestamp_micros(), Some(ms.timestamp_micros()));
+    assert_eq!(v7.timestamp_micros(), Some(0x018f_3c2a_1b2c * 1000));
+    assert_eq!(v4.timestamp_micros(), None);
+
+    // Total order follows the raw 128-bit value, across versions
+    let mut all = vec![v8, v4, nil, v7];
+    all.sort();
+    assert_eq!(all, vec![nil, v7, ms.into(), v4]);
+    assert_eq!(v4.to_string(), "550e8400-e29b-41d4-a716-446655440000");
+    assert_eq!(AnyUuid::from_bytes(v4.as_bytes()), v4);
+}
+
+#[test]
+fn test_stress_run() {
+    let report = stress::run(
+        4,
+        Duration::from_millis(50),
+        GeneratorConfig::Shared { shard_id: 7 },
+    );
+    assert_eq!(report.threads, 4);
+    assert!(report.generated > 0);
+    assert!(report.is_clean(), "{}", report);
+    assert!(report.throughput() > 0.0);
+
+    let report = stress::run(
+        2,
+        Duration::from_millis(20),
+        GeneratorConfig::Stateless { shard_id: 7 },
+    );
+    assert!(report.generated > 0);
+    assert_eq!(report.duplicates, 0);
+    assert_eq!(report.errors, 0);
+}
+
+#[test]
+fn test_envelope_roundtrip() {
+    let uuid = MicroShardUUID::from_u128(0x1916_dd14_36ed_8000_8003_0391_2345_6789).unwrap();
+    let wire = uuid.to_envelope();
+    assert_eq!(wire.len(), 18);
+    assert_eq!(wire[0], envelope::MAGIC);
+    assert_eq!(wire[1], envelope::LAYOUT_V1);
+    assert_eq!(&wire[2..], &uuid.as_bytes());
+    assert_eq!(MicroShardUUID::from_envelope(&wire), Ok(uuid));
+
+    // Foreign magic, truncated input, and unknown layouts are rejected
+    let mut bad = wire;
+    bad[0] = 0;
+    assert_eq!(
+        envelope::decode(&bad),
+        Err(MicroShardError::InvalidEncoding)
+    );
+    assert_eq!(
+        envelope::decode(&wire[..17]),
+        Err(MicroShardError::InvalidEncoding)
+    );
+
+    let mut future = wire;
+    future[1] = 2;
+    assert_eq!(
+        envelope::decode(&future),
+        Err(MicroShardError::InvalidEncoding)
+    );
+    assert_eq!(envelope::decode_raw(&future), Ok((2, uuid.as_bytes())));
+
+    // A valid envelope around a non-MicroShard payload still fails validation
+    let mut v4 = wire;
+    v4[8] = 0x40;
+    assert_eq!(
+        envelope::decode(&v4),
+        Err(MicroShardError::InvalidVersion(4))
+    );
+}
+
+#[test]
+fn test_layout_revision_tag() {
+    let gen = UniqueGenerator::new(5).unwrap().with_layout_revision(2);
+    let base = 1_700_000_000_000_000;
+    let mut prev = None;
+    for i in 0..1000 {
+        let id = gen.next_at(base + i / 10).unwrap();
+        assert_eq!(id.layout_revision(), 2);
+        assert_eq!(id.shard_id(), 5);
+        assert!(prev < Some(id));
+        prev = Some(id);
+    }
+
+    // The untagged layout is unaffected: all four values occur at random
+    let seen: HashSet<u8> = (0..200)
+        .map(|_| MicroShardUUID::generate(1).unwrap().layout_revision())
+        .collect();
+    assert_eq!(seen.len(), 4);
+}
+
+#[test]
+#[should_panic(expected = "layout revision")]
+fn test_layout_revision_out_of_range() {
+    let _ = UniqueGenerator::new(1).unwrap().with_layout_revision(4);
+}
+
+#[test]
+fn test_validate_batch() {
+    let mut records: Vec<[u8; 16]> = (0..150)
+        .map(|_| MicroShardUUID::generate(3).unwrap().as_bytes())
+        .collect();
+    records[0][6] = 0x40; // v4
+    records[70][8] = 0xC0; // variant 3
+    records[149] = [0; 16]; // nil
+
+    let bitmap = validate_batch(&records);
+    assert_eq!(bitmap.len(), 150);
+    assert_eq!(bitmap.count_invalid(), 3);
+    assert!(!bitmap.all_valid());
+    assert_eq!(
+        bitmap.invalid_indices().collect::<Vec<_>>(),
+        vec![0, 70, 149]
+    );
+
+    // Agrees with per-record validation
+    for (i, r) in records.iter().enumerate() {
+        assert_eq!(bitmap.is_valid(i), MicroShardUUID::from_bytes(*r).is_ok());
+    }
+    assert_eq!(bitmap.as_words().len(), 3);
+    assert_eq!(bitmap.as_words()[2] >> 22, 0);
+
+    // Every lane of a full block packs into its own bit
+    let mut block = [MicroShardUUID::generate(3).unwrap().as_bytes(); 64];
+    for i in 0..64 {
+        block[i][6] ^= 0x80;
+        assert_eq!(validate_batch(&block).as_words(), [!(1u64 << i)]);
+        block[i][6] ^= 0x80;
+    }
+
+    assert!(validate_batch(&[]).is_empty());
+    assert!(validate_batch(&[]).all_valid());
+}
+
+#[test]
+fn test_msuuid_literal_macro() {
+    const SENTINEL: MicroShardUUID = msuuid!("1916dd14-36ed-8000-8003-039123456789");
+    assert_eq!(SENTINEL.shard_id(), 12345);
+    assert_eq!(SENTINEL.timestamp_micros(), 1_765_503_300_123_456);
+    assert_eq!(
+        MicroShardUUID::parse("1916DD14-36ED-8000-8003-039123456789"),
+        Ok(SENTINEL)
+    );
+
+    // Also usable in expression position
+    let upper = msuuid!("FFFFFFFF-FFFF-8FFF-BFFF-FFFFFFFFFFFF");
+    assert_eq!(upper.shard_id(), u32::MAX);
+}
+
+#[test]
+fn test_generate_with_time_source() {
+    let fixed = || Ok(1_765_503_300_123_456);
+    let id = MicroShardUUID::generate_with(&fixed, 77).unwrap();
+    assert_eq!(id.timestamp_micros(), 1_765_503_300_123_456);
+    assert_eq!(id.shard_id(), 77);
+    assert_eq!(id.iso_display().to_string(), id.to_iso_string());
+
+    // Source failures propagate unchanged
+    let broken = || Err(MicroShardError::TimeOverflow);
+    assert_eq!(
+        MicroShardUUID::generate_with(&broken, 1),
+        Err(MicroShardError::TimeOverflow)
+    );
+    let too_late = || Ok(1u64 << 54);
+    assert_eq!(
+        MicroShardUUID::generate_with(&too_late, 1),
+        Err(MicroShardError::TimeOverflow)
+    );
+
+    let before = SystemTimeSource.now_micros().unwrap();
+    let id = MicroShardUUID::generate_with(&SystemTimeSource, 5).unwrap();
+    assert!(id.timestamp_micros() >= before);
+
+    assert_eq!(
+        iso_to_micros("2025-12-12T01:35:00.123456Z"),
+        Ok(1_765_503_300_123_456)
+    );
+    assert!(iso_to_micros("2025-12-12").is_err());
+}
+
+#[test]
+fn test_rng_state_not_reseeded_per_call() {
+    // Every ID lands in the same microsecond, so only the cached RNG stream
+    // separates them. A per-call reseed from the clock would collide en masse;
+    // 36 random bits still allow a rare birthday collision (~0.07 expected).
+    let _guard = test_support::freeze_time(1_765_503_300_123_456);
+    let ids: HashSet<_> = (0..100_000)
+        .map(|_| MicroShardUUID::generate(9).unwrap())
+        .collect();
+    assert!(ids.len() >= 99_990, "{} collisions", 100_000 - ids.len());
+}
+
+#[test]
+fn test_microshard_generator() {
+    assert_eq!(
+        MicroShardGenerator::new(7).unwrap().shard_id(),
+        MicroShardUUID::generate(7).unwrap().shard_id()
+    );
+
+    let mut ids = MicroShardGenerator::new(u32::MAX).unwrap();
+    let micros = 1_765_503_300_123_456;
+    let id = ids.next_at(micros).unwrap();
+    assert_eq!(id.shard_id(), u32::MAX);
+    assert_eq!(id.timestamp_micros(), micros);
+    assert_eq!(MicroShardUUID::from_u128(id.as_u128()), Ok(id));
+
+    // Matches the layout of the one-shot constructor bit for bit
+    let one_shot = MicroShardUUID::from_micros(micros, u32::MAX).unwrap();
+    assert_eq!(id.high(), one_shot.high());
+    assert_eq!(id.low() >> 36, one_shot.low() >> 36);
+
+    let _guard = test_support::freeze_time(micros);
+    let batch: HashSet<_> = (0..10_000).map(|_| ids.next().unwrap()).collect();
+    assert_eq!(batch.len(), 10_000);
+    assert!(batch.iter().all(|id| id.timestamp_micros() == micros));
+
+    assert_eq!(ids.next_at(1 << 54), Err(MicroShardError::TimeOverflow));
+}
+
+#[test]
+fn test_microshard_generator_monotonic() {
+    let mut ids = MicroShardGenerator::monotonic(12).unwrap();
+    assert!(ids.is_monotonic());
+    assert!(!MicroShardGenerator::new(12).unwrap().is_monotonic());
+
+    let micros = 1_765_503_300_123_456;
+    let mut prev = ids.next_at(micros).unwrap();
+    assert_eq!(prev.random() >> 24, 0); // sequence restarts per microsecond
+
+    // 4096 IDs fit in one microsecond; the 4097th spills into the next.
+    for i in 1..=4096u64 {
+        let id = ids.next_at(micros).unwrap();
+        assert!(id > prev);
+        if i < 4096 {
+            assert_eq!(id.timestamp_micros(), micros);
+            assert_eq!(id.random() >> 24, i);
+        } else {
+            assert_eq!(id.timestamp_micros(), micros + 1);
+            assert_eq!(id.random() >> 24, 0);
+        }
+        prev = id;
+    }
+
+    // Clock regression holds the last timestamp
+    let held = ids.next_at(micros - 1_000).unwrap();
+    assert!(held > prev);
+    assert_eq!(held.timestamp_micros(), micros + 1);
+
+    // Exhausting the final microsecond overflows instead of wrapping
+    let max = (1u64 << 54) - 1;
+    let mut last = MicroShardGenerator::monotonic(1).unwrap();
+    for _ in 0..4096 {
+        last.next_at(max).unwrap();
+    }
+    assert_eq!(last.next_at(max), Err(MicroShardError::TimeOverflow));
+}
+
+#[test]
+fn test_batch_generation() {
+    let micros = 1_765_503_300_123_456;
+    let _guard = test_support::freeze_time(micros);
+
+    // Frozen clock: 10k IDs after a random start span three or four microseconds
+    let batch = MicroShardUUID::generate_batch(21, 10_000).unwrap();
+    assert_eq!(batch.len(), 10_000);
+    assert!(batch.windows(2).all(|w| w[0] < w[1]));
+    assert!(batch.iter().all(|id| id.shard_id() == 21));
+    assert_eq!(batch[0].timestamp_micros(), micros);
+    assert!((micros + 2..=micros + 3).contains(&batch[9_999].timestamp_micros()));
+
+    // Separate batches in the same microsecond start at independent counters
+    let starts: HashSet<_> = (0..16)
+        .map(|_| MicroShardUUID::generate_batch(21, 1).unwrap()[0].random() >> 24)
+        .collect();
+    assert!(starts.len() > 1);
+
+    let mut slots = [batch[0]; 64];
+    MicroShardUUID::fill_batch(22, &mut slots).unwrap();
+    assert!(slots.windows(2).all(|w| w[0] < w[1]));
+    assert!(slots.iter().all(|id| id.shard_id() == 22));
+
+    // A monotonic generator continues its sequence across batches
+    let mut ids = MicroShardGenerator::monotonic(23).unwrap();
+    let first = ids.generate_batch(100).unwrap();
+    let second = ids.generate_batch(100).unwrap();
+    assert!(first[99] < second[0]);
+
+    assert!(MicroShardUUID::generate_batch(1, 0).unwrap().is_empty());
+}
+
+#[test]
+fn test_range_boundary_ids() {
+    let t1 = 1_765_503_300_000_000;
+    let t2 = t1 + 1_000_000;
+    let lo = MicroShardUUID::min_for_micros(t1).unwrap();
+    let hi = MicroShardUUID::max_for_micros(t2).unwrap();
+
+    assert_eq!(lo.to_string(), "1916dd14-2f64-8000-8000-000000000000");
+    assert_eq!((lo.timestamp_micros(), lo.shard_id()), (t1, 0));
+    assert_eq!((hi.timestamp_micros(), hi.shard_id()), (t2, u32::MAX));
+    assert_eq!(hi.random(), (1 << 36) - 1);
+    assert_eq!(MicroShardUUID::from_u128(hi.as_u128()), Ok(hi));
+
+    // Inclusive on both ends, exclusive just outside
+    for (micros, shard, inside) in [
+        (t1, 0, true),
+        (t2, u32::MAX, true),
+        (t1 + 500, 77, true),
+        (t1 - 1, u32::MAX, false),
+        (t2 + 1, 0, false),
+    ] {
+        let id = MicroShardUUID::from_micros(micros, shard).unwrap();
+        assert_eq!(lo <= id && id <= hi, inside, "{micros} / {shard}");
+    }
+
+    let max = (1u64 << 54) - 1;
+    assert!(MicroShardUUID::max_for_micros(max).is_ok());
+    assert_eq!(
+        MicroShardUUID::min_for_micros(max + 1),
+        Err(MicroShardError::TimeOverflow)
+    );
+}
+
+#[test]
+fn test_decompose() {
+    let id = msuuid!("1916dd14-36ed-8000-8003-039123456789");
+    let fields = id.decompose();
+    assert_eq!(
+        fields,
+        DecodedMicroShard {
+            timestamp_micros: 1_765_503_300_123_456,
+            shard_id: 12345,
+            random: 0x1_2345_6789,
+            version: 8,
+            variant: 2,
+        }
+    );
+    assert_eq!(fields.timestamp_micros, id.timestamp_micros());
+    assert_eq!(fields.shard_id, id.shard_id());
+
+    let upper = msuuid!("FFFFFFFF-FFFF-8FFF-BFFF-FFFFFFFFFFFF").decompose();
+    assert_eq!(upper.random, (1 << 36) - 1);
+    assert_eq!(upper.shard_id, u32::MAX);
+}
+
+#[test]
+fn test_random_accessor() {
+    let id = msuuid!("1916dd14-36ed-8000-8003-039123456789");
+    assert_eq!(id.random(), 0x1_2345_6789);
+    assert_eq!(id.anonymize().random(), 0);
+    assert_eq!(id.random() >> 34, id.layout_revision() as u64);
+
+    let max = msuuid!("FFFFFFFF-FFFF-8FFF-BFFF-FFFFFFFFFFFF");
+    assert_eq!(max.random(), (1 << 36) - 1);
+
+    // Varies across IDs while the rest of the ID stays fixed
+    let _guard = test_support::freeze_time(1_765_503_300_123_456);
+    let a = MicroShardUUID::generate(4).unwrap();
+    let b = MicroShardUUID::generate(4).unwrap();
+    assert_eq!(a.high(), b.high());
+    assert_ne!(a.random(), b.random());
+}
+
+#[test]
+fn test_from_parts() {
+    let id = MicroShardUUID::from_parts(1_765_503_300_123_456, 12345, 0x1_2345_6789).unwrap();
+    assert_eq!(id, msuuid!("1916dd14-36ed-8000-8003-039123456789"));
+
+    let max = (1u64 << 54) - 1;
+    let top = MicroShardUUID::from_parts(max, u32::MAX, (1 << 36) - 1).unwrap();
+    assert_eq!(top.to_string(), "ffffffff-ffff-8fff-bfff-ffffffffffff");
+    assert_eq!(
+        MicroShardUUID::from_parts(0, 0, 0).unwrap().to_string(),
+        "00000000-0000-8000-8000-000000000000"
+    );
+
+    let fields = top.decompose();
+    assert_eq!(
+        (fields.timestamp_micros, fields.shard_id, fields.random),
+        (max, u32::MAX, (1 << 36) - 1)
+    );
+
+    assert_eq!(
+        MicroShardUUID::from_parts(max + 1, 0, 0),
+        Err(MicroShardError::TimeOverflow)
+    );
+    assert_eq!(
+        MicroShardUUID::from_parts(0, 0, 1 << 36),
+        Err(MicroShardError::InvalidRandom(1 << 36))
+    );
+    assert_eq!(
+        MicroShardError::InvalidRandom(1 << 36).to_string(),
+        "Random field 68719476736 does not fit in 36 bits"
+    );
+}
+
+#[test]
+fn test_unchecked_constructors() {
+    let id = MicroShardUUID::generate(31).unwrap();
+    assert_eq!(MicroShardUUID::from_u128_unchecked(id.as_u128()), id);
+    assert_eq!(MicroShardUUID::from_bytes_unchecked(id.as_bytes()), id);
+
+    const MAX: MicroShardUUID =
+        MicroShardUUID::from_u128_unchecked(0xffff_ffff_ffff_8fff_bfff_ffff_ffff_ffff);
+    assert_eq!(MAX.shard_id(), u32::MAX);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "not a v8/variant-2 UUID")]
+fn test_unchecked_constructor_asserts_in_debug() {
+    let _ = MicroShardUUID::from_bytes_unchecked([0; 16]);
+}
+
+#[test]
+fn test_base32_roundtrip_and_order() {
+    let id = msuuid!("1916dd14-36ed-8000-8003-039123456789");
+    let encoded = id.to_base32();
+    assert_eq!(encoded, "0S2VEH8DQDG00800R3J4HMASW9");
+    assert_eq!(MicroShardUUID::from_base32(&encoded), Ok(id));
+    assert_eq!(MicroShardUUID::from_base32(&encoded.to_lowercase()), Ok(id));
+    // Crockford aliases: O -> 0, I/L -> 1
+    assert_eq!(
+        MicroShardUUID::from_base32("OS2VEH8DQDGOO8OOR3J4HMASW9"),
+        Ok(id)
+    );
+
+    let max = msuuid!("FFFFFFFF-FFFF-8FFF-BFFF-FFFFFFFFFFFF");
+    assert_eq!(max.to_base32(), "7ZZZZZZZZZHZZVZZZZZZZZZZZZ");
+
+    // Lexicographic order of the strings matches ID order
+    let mut ids: Vec<_> = (0..200)
+        .map(|i| MicroShardUUID::from_micros(i * 7_919, (i * 31) as u32).unwrap())
+        .collect();
+    ids.push(max);
+    let mut by_string = ids.clone();
+    by_string.sort_by_key(|id| id.to_base32());
+    ids.sort();
+    assert_eq!(ids, by_string);
+
+    for bad in [
+        "",
+        "0S2VEH8DQDG00800R3J4HMASW",   // too short
+        "0S2VEH8DQDG00800R3J4HMASW9Z", // too long
+        "0S2VEH8DQDG00800R3J4HMASWU",  // U is not in the alphabet
+        "8ZZZZZZZZZHZZVZZZZZZZZZZZZ",  // > 128 bits
+    ] {
+        assert_eq!(
+            MicroShardUUID::from_base32(bad),
+            Err(MicroShardError::InvalidEncoding),
+            "{bad}"
+        );
+    }
+    assert_eq!(
+        MicroShardUUID::from_base32("00000000000000000000000000"),
+        Err(MicroShardError::InvalidVersion(0))
+    );
+}
+
+#[test]
+fn test_base64url_roundtrip() {
+    let id = msuuid!("1916dd14-36ed-8000-8003-039123456789");
+    assert_eq!(id.to_base64url(), "GRbdFDbtgACAAwORI0VniQ");
+    assert_eq!(
+        MicroShardUUID::from_base64url("GRbdFDbtgACAAwORI0VniQ"),
+        Ok(id)
+    );
+
+    let max = msuuid!("FFFFFFFF-FFFF-8FFF-BFFF-FFFFFFFFFFFF");
+    assert_eq!(max.to_base64url(), "________j_-__________w");
+    assert_eq!(MicroShardUUID::from_base64url(&max.to_base64url()), Ok(max));
+
+    for _ in 0..1_000 {
+        let id = MicroShardUUID::generate(u32::MAX).unwrap();
+        let encoded = id.to_base64url();
+        assert_eq!(encoded.len(), 22);
+        assert_eq!(MicroShardUUID::from_base64url(&encoded), Ok(id));
+    }
+
+    for bad in [
+        "",
+        "GRbdFDbtgACAAwORI0Vni",    // too short
+        "GRbdFDbtgACAAwORI0VniQ==", // padded
+        "GRbdFDbtgACAAwORI0Vni+",   // standard alphabet
+        "GRbdFDbtgACAAwORI0VniR",   // non-zero trailing bits
+    ] {
+        assert_eq!(
+            MicroShardUUID::from_base64url(bad),
+            Err(MicroShardError::InvalidEncoding),
+            "{bad}"
+        );
+    }
+    assert_eq!(
+        MicroShardUUID::from_base64url("AAAAAAAAAAAAAAAAAAAAAA"),
+        Err(MicroShardError::InvalidVersion(0))
+    );
+}
+
+#[test]
+fn test_urn_form() {
+    let id = msuuid!("1916dd14-36ed-8000-8003-039123456789");
+    let urn = id.to_urn();
+    assert_eq!(urn, "urn:uuid:1916dd14-36ed-8000-8003-039123456789");
+    assert_eq!(MicroShardUUID::parse(&urn), Ok(id));
+    assert_eq!(urn.parse::<MicroShardUUID>(), Ok(id));
+    assert_eq!(
+        MicroShardUUID::parse("URN:UUID:1916DD14-36ED-8000-8003-039123456789"),
+        Ok(id)
+    );
+    assert_eq!(msuuid!("urn:uuid:1916dd14-36ed-8000-8003-039123456789"), id);
+    assert_eq!(AnyUuid::parse(&urn), Ok(AnyUuid::V8MicroShard(id)));
+
+    for bad in [
+        "urn:uuid:",
+        "urn:uuid:1916dd14-36ed-8000-8003-03912345678",
+        "urn:uid:1916dd14-36ed-8000-8003-0391234567899",
+        "uuid:1916dd14-36ed-8000-8003-039123456789",
+        "urn:uuid:urn:uuid:1916dd14-36ed-8000-8003-039123456789",
+    ] {
+        assert_eq!(
+            MicroShardUUID::parse(bad),
+            Err(MicroShardError::InvalidUuidFormat),
+            "{bad}"
+        );
+    }
+}
+
+#[test]
+fn test_parse_lenient() {
+    let id = msuuid!("1916dd14-36ed-8000-8003-039123456789");
+    for ok in [
+        "1916dd14-36ed-8000-8003-039123456789",
+        "urn:uuid:1916dd14-36ed-8000-8003-039123456789",
+        "{1916dd14-36ed-8000-8003-039123456789}",
+        "{1916DD14-36ED-8000-8003-039123456789}",
+        "(1916dd14-36ed-8000-8003-039123456789)",
+        "1916dd1436ed80008003039123456789",
+        "{1916dd1436ed80008003039123456789}",
+    ] {
+        assert_eq!(MicroShardUUID::parse_lenient(ok), Ok(id), "{ok}");
+    }
+
+    // `parse` stays strict
+    assert_eq!(
+        MicroShardUUID::parse("{1916dd14-36ed-8000-8003-039123456789}"),
+        Err(MicroShardError::InvalidUuidFormat)
+    );
+    assert_eq!(
+        MicroShardUUID::parse("1916dd1436ed80008003039123456789"),
+        Err(MicroShardError::InvalidUuidFormat)
+    );
+
+    for bad in [
+        "",
+        "{}",
+        "{1916dd14-36ed-8000-8003-039123456789)",
+        "{urn:uuid:1916dd14-36ed-8000-8003-039123456789}",
+        "1916dd1436ed8000800303912345678g",
+        " 1916dd14-36ed-8000-8003-039123456789",
+    ] {
+        assert_eq!(
+            MicroShardUUID::parse_lenient(bad),
+            Err(MicroShardError::InvalidUuidFormat),
+            "{bad}"
+        );
+    }
+    assert_eq!(
+        MicroShardUUID::parse_lenient("{550e8400-e29b-41d4-a716-446655440000}"),
+        Err(MicroShardError::InvalidVersion(4))
+    );
+}
+
+#[test]
+fn test_hex_formatting_traits() {
+    let id = msuuid!("1916DD14-36ED-8000-8003-0391ABCDEF89");
+    assert_eq!(id.to_string(), "1916dd14-36ed-8000-8003-0391abcdef89");
+    assert_eq!(format!("{:x}", id), id.to_string());
+    assert_eq!(format!("{:X}", id), "1916DD14-36ED-8000-8003-0391ABCDEF89");
+    assert_eq!(id.to_string_upper(), format!("{:X}", id));
+    assert_eq!(MicroShardUUID::parse(&id.to_string_upper()), Ok(id));
+
+    for _ in 0..100 {
+        let id = MicroShardUUID::generate(u32::MAX).unwrap();
+        assert_eq!(id.to_string_upper(), id.to_string().to_uppercase());
+    }
+}
+
+#[test]
+fn test_simple_form() {
+    let id = msuuid!("1916dd14-36ed-8000-8003-039123456789");
+    let simple = id.to_simple_string();
+    assert_eq!(simple, "1916dd1436ed80008003039123456789");
+    assert_eq!(MicroShardUUID::parse_simple(&simple), Ok(id));
+    assert_eq!(
+        MicroShardUUID::parse_simple("1916DD1436ED80008003039123456789"),
+        Ok(id)
+    );
+    assert_eq!(MicroShardUUID::parse_lenient(&simple), Ok(id));
+
+    for _ in 0..100 {
+        let id = MicroShardUUID::generate(7).unwrap();
+        assert_eq!(id.to_simple_string(), id.to_string().replace('-', ""));
+        assert_eq!(MicroShardUUID::parse_simple(&id.to_simple_string()), Ok(id));
+    }
+
+    for bad in [
+        "",
+        "1916dd14-36ed-8000-8003-039123456789",
+        "1916dd1436ed8000800303912345678",
+        "1916dd1436ed800080030391234567890",
+        "1916dd1436ed8000800303912345678z",
+    ] {
+        assert_eq!(
+            MicroShardUUID::parse_simple(bad),
+            Err(MicroShardError::InvalidUuidFormat),
+            "{bad}"
+        );
+    }
+    assert_eq!(
+        MicroShardUUID::parse_simple("550e8400e29b41d4a716446655440000"),
+        Err(MicroShardError::InvalidVersion(4))
+    );
+}
+
+#[test]
+fn test_encode_into_buffers() {
+    let mut hyphenated = [0u8; 36];
+    let mut simple = [0u8; 32];
+    for _ in 0..100 {
+        let id = MicroShardUUID::generate(u32::MAX).unwrap();
+        assert_eq!(id.encode_hyphenated(&mut hyphenated), id.to_string());
+        assert_eq!(id.encode_simple(&mut simple), id.to_simple_string());
+    }
+
+    // The returned &str borrows the caller's buffer
+    let id = msuuid!("1916dd14-36ed-8000-8003-039123456789");
+    let s = id.encode_simple(&mut simple);
+    assert_eq!(s, "1916dd1436ed80008003039123456789");
+    assert_eq!(&simple[..4], b"1916");
+}
+
+#[test]
+fn test_array_string() {
+    let id = msuuid!("1916dd14-36ed-8000-8003-039123456789");
+    let s: FixedStr<36> = id.to_array_string();
+    assert_eq!(s, "1916dd14-36ed-8000-8003-039123456789");
+    assert_eq!(s.as_str(), id.to_string());
+    assert_eq!(s.as_bytes(), id.to_string().as_bytes());
+    assert_eq!(s.to_uppercase(), id.to_string_upper()); // via Deref<Target = str>
+    assert_eq!(format!("{s}"), id.to_string());
+    assert_eq!(format!("{s:?}"), format!("{:?}", id.to_string()));
+    assert_eq!(format!("[{s:>38}]"), format!("[  {id}]"));
+
+    // Same ordering as the IDs themselves
+    let a = MicroShardUUID::from_micros(1, 0).unwrap();
+    let b = MicroShardUUID::from_micros(2, 0).unwrap();
+    assert!(a.to_array_string() < b.to_array_string());
+}
+
+#[test]
+fn test_hex_decoder_matches_reference_for_every_byte() {
+    // Corrupt each position of a valid string with every byte value; the
+    // branch-free decoder must agree with a straightforward reference.
+    let valid = *b"1916dd14-36ed-8000-8003-039123456789";
+    for pos in 0..36 {
+        for c in 0..=255u8 {
+            let mut b = valid;
+            b[pos] = c;
+            let Ok(text) = std::str::from_utf8(&b) else {
+                continue;
+            };
+            let hyphens_ok = [8, 13, 18, 23].iter().all(|&h| b[h] == b'-');
+            let digits: String = text.chars().filter(|&c| c != '-').collect();
+            let reference =
+                (hyphens_ok && digits.len() == 32 && digits.bytes().all(|c| c.is_ascii_hexdigit()))
+                    .then(|| u128::from_str_radix(&digits, 16).unwrap());
+
+            let parsed = MicroShardUUID::parse(text)
+                .map(|id| id.as_u128())
+                .map_err(|e| e == MicroShardError::InvalidUuidFormat);
+            match reference {
+                Some(v) if MicroShardUUID::from_u128(v).is_ok() => assert_eq!(parsed, Ok(v)),
+                Some(_) => assert_eq!(parsed, Err(false), "{text}"),
+                None => assert_eq!(parsed, Err(true), "{text}"),
+            }
+            // FromStr (SIMD kernels) must agree with the const (scalar) path
+            assert_eq!(text.parse(), MicroShardUUID::parse(text), "{text}");
+        }
+    }
+
+    // Same for the simple form, through both of its decoders
+    let valid = *b"1916dd1436ed80008003039123456789";
+    for pos in 0..32 {
+        for c in 0..=255u8 {
+            let mut b = valid;
+            b[pos] = c;
+            let Ok(text) = std::str::from_utf8(&b) else {
+                continue;
+            };
+            let expected = if text.bytes().all(|c| c.is_ascii_hexdigit()) {
+                MicroShardUUID::from_u128(u128::from_str_radix(text, 16).unwrap())
+            } else {
+                Err(MicroShardError::InvalidUuidFormat)
+            };
+            assert_eq!(MicroShardUUID::parse_simple(text), expected, "{text}");
+            assert_eq!(MicroShardUUID::parse_lenient(text), expected, "{text}");
+        }
+    }
+}
+
+#[test]
+fn test_hex_encoder_matches_reference() {
+    // Spread values over every nibble of every position, in both cases
+    let mut x = 0x9E37_79B9_7F4A_7C15_u64;
+    for _ in 0..10_000 {
+        x = x.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+        let id = MicroShardUUID::from_parts(x >> 10, (x >> 7) as u32, x & ((1 << 36) - 1)).unwrap();
+        let v = id.as_u128();
+        assert_eq!(id.to_simple_string(), format!("{v:032x}"));
+        let upper = format!("{v:032X}");
+        let hyphenated = format!(
+            "{}-{}-{}-{}-{}",
+            &upper[..8],
+            &upper[8..12],
+            &upper[12..16],
+            &upper[16..20],
+            &upper[20..]
+        );
+        assert_eq!(id.to_string_upper(), hyphenated);
+        assert_eq!(id.to_string(), hyphenated.to_lowercase());
+        assert_eq!(hyphenated.parse(), Ok(id));
+    }
+}
+
+#[test]
+fn test_custom_epoch() {
+    const EPOCH_2020: u64 = 1_577_836_800_000_000;
+    let mut ids = MicroShardGenerator::new(5).unwrap().with_epoch(EPOCH_2020);
+    assert_eq!(ids.epoch_micros(), EPOCH_2020);
+    assert_eq!(MicroShardGenerator::new(5).unwrap().epoch_micros(), 0);
+
+    let now = 1_765_503_300_123_456;
+    let id = ids.next_at(now).unwrap();
+    assert_eq!(id.timestamp_micros(), now - EPOCH_2020);
+    assert_eq!(id.timestamp_micros_with_epoch(EPOCH_2020), Ok(now));
+    assert_eq!(id.timestamp_micros_with_epoch(0), Ok(id.timestamp_micros()));
+    assert_eq!(id.shard_id(), 5);
+
+    // Range moves with the epoch: past 2541 works, before the epoch does not
+    let max = (1u64 << 54) - 1;
+    let late = ids.next_at(max + 1_000).unwrap();
+    assert_eq!(
+        late.timestamp_micros_with_epoch(EPOCH_2020),
+        Ok(max + 1_000)
+    );
+    assert_eq!(
+        ids.next_at(EPOCH_2020 + max + 1),
+        Err(MicroShardError::TimeOverflow)
+    );
+    assert_eq!(
+        ids.next_at(EPOCH_2020 - 1),
+        Err(MicroShardError::TimeUnderflow)
+    );
+    assert_eq!(
+        late.timestamp_micros_with_epoch(u64::MAX),
+        Err(MicroShardError::TimeOverflow)
+    );
+
+    // Batches and monotonic mode apply the same offset
+    let _guard = test_support::freeze_time(now);
+    let mut ids = MicroShardGenerator::monotonic(6)
+        .unwrap()
+        .with_epoch(EPOCH_2020);
+    let batch = ids.generate_batch(3).unwrap();
+    assert!(batch.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(batch[0].timestamp_micros_with_epoch(EPOCH_2020), Ok(now));
+    assert_eq!(
+        ids.next().unwrap().timestamp_micros_with_epoch(EPOCH_2020),
+        Ok(now)
+    );
+}
+
+#[test]
+fn test_v7_layout() {
+    let micros = 1_765_503_300_123_456;
+    let id = MicroShardV7::from_parts(micros, u32::MAX, (1 << 30) - 1).unwrap();
+    assert_eq!(id.timestamp_millis(), micros / 1000);
+    assert_eq!(id.timestamp_micros(), micros);
+    assert_eq!(id.shard_id(), u32::MAX);
+    assert_eq!(id.random(), (1 << 30) - 1);
+
+    // RFC 9562 v7 fields: unix_ts_ms in the top 48 bits, version 7, variant 2
+    let v = id.as_u128();
+    assert_eq!((v >> 80) as u64, micros / 1000);
+    assert_eq!((v >> 76) & 0xF, 7);
+    assert_eq!((v >> 62) & 0x3, 2);
+    assert_eq!(AnyUuid::from_u128(v), AnyUuid::V7(v));
+
+    // Every sub-millisecond offset survives the 12-bit fraction, in order
+    let base = 1_700_000_000_000_000;
+    let mut prev = None;
+    for rem in 0..1000 {
+        let id = MicroShardV7::from_parts(base + rem, 9, 0).unwrap();
+        assert_eq!(id.timestamp_micros(), base + rem);
+        assert!(prev < Some(id));
+        prev = Some(id);
+    }
+
+    // Round trips and validation
+    let s = id.to_string();
+    assert_eq!(MicroShardV7::parse(&s), Ok(id));
+    assert_eq!(s.parse::<MicroShardV7>(), Ok(id));
+    assert_eq!(MicroShardV7::from_bytes(id.as_bytes()), Ok(id));
+    let v8 = MicroShardUUID::from_parts(micros, 1, 0).unwrap();
+    assert_eq!(
+        MicroShardV7::from_u128(v8.as_u128()),
+        Err(MicroShardError::InvalidVersion(8))
+    );
+    assert_eq!(
+        MicroShardV7::from_u128(v & !(0b11 << 62)),
+        Err(MicroShardError::InvalidVariant(0))
+    );
+    assert_eq!(
+        MicroShardV7::from_parts(micros, 1, 1 << 30),
+        Err(MicroShardError::InvalidRandom(1 << 30))
+    );
+    assert_eq!(
+        MicroShardV7::from_parts(((1 << 48) - 1) * 1000 + 1000, 1, 0),
+        Err(MicroShardError::TimeOverflow)
+    );
+
+    // A foreign v7 whose fraction exceeds 999µs still decodes within its ms
+    let foreign = MicroShardV7::from_u128(v | (0xFFF << 64)).unwrap();
+    assert_eq!(foreign.timestamp_micros(), micros / 1000 * 1000 + 999);
+
+    let _guard = test_support::freeze_time(micros);
+    let live = MicroShardV7::generate(77).unwrap();
+    assert_eq!(live.timestamp_micros(), micros);
+    assert_eq!(live.shard_id(), 77);
+}
+
+#[test]
+fn test_uuid_v7_conversion() {
+    let micros = 1_765_503_300_123_456;
+    let id = MicroShardUUID::from_parts(micros, 4_000_000_000, 0xA_BCDE_F123).unwrap();
+
+    let v7 = id.to_uuid_v7();
+    assert_eq!(v7.timestamp_micros(), micros);
+    assert_eq!(v7.shard_id(), 4_000_000_000);
+    assert_eq!(v7.random(), 0xA_BCDE_F123 >> 6);
+    assert_eq!(MicroShardV7::from(id), v7);
+
+    // Back again: time and shard exact, low 6 random bits lost
+    let back = MicroShardUUID::try_from(v7).unwrap();
+    assert_eq!(back.timestamp_micros(), micros);
+    assert_eq!(back.shard_id(), 4_000_000_000);
+    assert_eq!(back.random(), 0xA_BCDE_F123 & !0x3F);
+
+    // Monotonic sequences keep their order through the narrower field
+    let _guard = test_support::freeze_time(micros);
+    let batch = MicroShardUUID::generate_batch(3, 50).unwrap();
+    let v7s: Vec<_> = batch.iter().map(MicroShardUUID::to_uuid_v7).collect();
+    assert!(v7s.windows(2).all(|w| w[0] < w[1]));
+
+    // Foreign v7 import: ms kept, caller-chosen shard, deterministic
+    let foreign: u128 = 0x018b_cfe5_687b_7abc_9def_0123_4567_89ab;
+    let imported = MicroShardUUID::from_uuid_v7(foreign, 0).unwrap();
+    assert_eq!(imported.timestamp_micros() / 1000, 0x018b_cfe5_687b);
+    assert_eq!(imported.shard_id(), 0);
+    assert_eq!(MicroShardUUID::from_uuid_v7(foreign, 0), Ok(imported));
+    assert_eq!(
+        MicroShardUUID::from_uuid_v7(foreign, 12)
+            .unwrap()
+            .shard_id(),
+        12
+    );
+    let later = MicroShardUUID::from_uuid_v7(foreign + 1, 0).unwrap();
+    assert!(later >= imported);
+    assert_eq!(
+        MicroShardUUID::from_uuid_v7(id.as_u128(), 0),
+        Err(MicroShardError::InvalidVersion(8))
+    );
+
+    // v7 times beyond the 54-bit microsecond range cannot come back
+    let far = MicroShardV7::from_parts(1 << 54, 1, 0).unwrap();
+    assert_eq!(
+        MicroShardUUID::try_from(far),
+        Err(MicroShardError::TimeOverflow)
+    );
+    assert_eq!(
+        MicroShardUUID::from_uuid_v7(far.as_u128(), 1),
+        Err(MicroShardError::TimeOverflow)
+    );
+}
+
+#[test]
+fn test_timeuuid_conversion() {
+    // The Unix Epoch is 0x01B21DD213814000 ticks in the v1 time fields
+    let epoch = MicroShardUUID::from_parts(0, 0, 0).unwrap().to_timeuuid();
+    assert_eq!(epoch, 0x1381_4000_1dd2_11b2_8080_8080_8080_8080);
+
+    let micros = 1_765_503_300_123_456;
+    let id = MicroShardUUID::from_parts(micros, 4_000_000_000, 0xA_BCDE_F123).unwrap();
+    let v1 = id.to_timeuuid();
+    assert_eq!((v1 >> 76) & 0xF, 1);
+    assert_eq!((v1 >> 62) & 0x3, 2);
+
+    // Back again: time and shard exact, low 6 random bits lost
+    let back = MicroShardUUID::from_timeuuid(v1).unwrap();
+    assert_eq!(back.timestamp_micros(), micros);
+    assert_eq!(back.shard_id(), 4_000_000_000);
+    assert_eq!(back.random(), 0xA_BCDE_F123 & !0x3F);
+    assert_eq!(back.to_timeuuid(), v1);
+
+    // A timeuuid from CQL now() (2024-10-15) decodes deterministically
+    let foreign: u128 = 0xe3a1_b5f0_8b2d_11ef_9c4e_0242_ac12_0002;
+    let imported = MicroShardUUID::from_timeuuid(foreign).unwrap();
+    assert_eq!(imported.timestamp_micros(), 1_729_021_468_058_980);
+    assert_eq!(imported.shard_id(), 1_933_183_752);
+    assert_eq!(imported.random(), 47_859_114_112);
+
+    assert_eq!(
+        MicroShardUUID::from_timeuuid(id.as_u128()),
+        Err(MicroShardError::InvalidVersion(8))
+    );
+    assert_eq!(
+        MicroShardUUID::from_timeuuid(v1 & !(0b11 << 62)),
+        Err(MicroShardError::InvalidVariant(0))
+    );
+    // 1582-10-15 predates the Unix Epoch; year 5236 is past the 54-bit range
+    assert_eq!(
+        MicroShardUUID::from_timeuuid(0x0000_0000_0000_1000_8000_0000_0000_0000),
+        Err(MicroShardError::TimeUnderflow)
+    );
+    assert_eq!(
+        MicroShardUUID::from_timeuuid(0xFFFF_FFFF_FFFF_1FFF_8000_0000_0000_0000),
+        Err(MicroShardError::TimeOverflow)
+    );
+}
+
+#[test]
+fn test_ulid_conversion() {
+    // Reference vector from the ULID spec
+    let spec: Ulid = "01ARZ3NDEKTSV4RRFFQ69G5FAV".parse().unwrap();
+    assert_eq!(spec.timestamp_millis(), 1_469_922_850_259);
+    assert_eq!(spec.to_string(), "01ARZ3NDEKTSV4RRFFQ69G5FAV");
+    assert_eq!(Ulid::parse("01arz3ndektsv4rrffq69g5fav"), Ok(spec));
+    assert_eq!(Ulid::from_bytes(spec.as_bytes()), spec);
+    assert_eq!(Ulid::from(spec.as_u128()), spec);
+    assert_eq!(
+        Ulid::parse("81ARZ3NDEKTSV4RRFFQ69G5FAV"),
+        Err(MicroShardError::InvalidEncoding)
+    );
+
+    // MicroShard -> ULID -> MicroShard is lossless
+    let id = MicroShardUUID::from_parts(1_765_503_300_123_456, u32::MAX, 0xF_FFFF_FFFF).unwrap();
+    let ulid = Ulid::from(id);
+    assert_eq!(ulid.timestamp_millis(), 1_765_503_300_123);
+    assert_eq!(MicroShardUUID::try_from(ulid), Ok(id));
+    assert_eq!(ulid.as_u128() & 0b11, 0);
+
+    // Order is preserved, including within a millisecond
+    let _guard = test_support::freeze_time(1_765_503_300_123_456);
+    let mut ids = MicroShardUUID::generate_batch(1, 20).unwrap();
+    ids.push(MicroShardUUID::from_parts(1_765_503_300_123_457, 0, 0).unwrap());
+    let ulids: Vec<Ulid> = ids.iter().copied().map(Ulid::from).collect();
+    assert!(ulids.windows(2).all(|w| w[0] < w[1]));
+    let text: Vec<String> = ulids.iter().map(Ulid::to_string).collect();
+    assert!(text.windows(2).all(|w| w[0] < w[1]));
+
+    // Foreign ULIDs keep their millisecond
+    let imported = MicroShardUUID::try_from(spec).unwrap();
+    assert_eq!(imported.timestamp_micros() / 1000, 1_469_922_850_259);
+    assert_eq!(
+        MicroShardUUID::try_from(Ulid::from_u128(u128::MAX)),
+        Err(MicroShardError::TimeOverflow)
+    );
+}
+
+#[test]
+fn test_from_snowflake() {
+    use microshard_uuid::snowflake::{DISCORD_EPOCH_MILLIS, TWITTER_EPOCH_MILLIS};
+
+    let snowflake = |millis: u64, worker: u64, seq: u64| (millis << 22) | (worker << 12) | seq;
+
+    let id =
+        MicroShardUUID::from_snowflake(snowflake(1_000, 1023, 4095), TWITTER_EPOCH_MILLIS).unwrap();
+    assert_eq!(id.timestamp_micros(), (TWITTER_EPOCH_MILLIS + 1_000) * 1000);
+    assert_eq!(id.shard_id(), 1023);
+    assert_eq!(id.random(), 4095 << 24);
+
+    // Sort order (time, worker, sequence) survives, and imports are repeatable
+    let keys = [
+        snowflake(5, 0, 0),
+        snowflake(5, 0, 1),
+        snowflake(5, 1, 0),
+        snowflake(6, 0, 0),
+        snowflake((1 << 41) - 1, 1023, 4095),
+    ];
+    let ids: Vec<_> = keys
+        .iter()
+        .map(|&k| MicroShardUUID::from_snowflake(k, DISCORD_EPOCH_MILLIS).unwrap())
+        .collect();
+    assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(
+        MicroShardUUID::from_snowflake(keys[1], DISCORD_EPOCH_MILLIS),
+        Ok(ids[1])
+    );
+
+    assert_eq!(
+        MicroShardUUID::from_snowflake(1 << 63, TWITTER_EPOCH_MILLIS),
+        Err(MicroShardError::InvalidEncoding)
+    );
+    assert_eq!(
+        MicroShardUUID::from_snowflake(0, u64::MAX),
+        Err(MicroShardError::TimeOverflow)
+    );
+}
+
+#[test]
+fn test_ksuid_conversion() {
+    // Reference vector from the KSUID README
+    let spec: Ksuid = "0ujtsYcgvSTl8PAuAdqWYSMnLOv".parse().unwrap();
+    assert_eq!(spec.timestamp_secs(), 1_507_608_047);
+    assert_eq!(
+        u128::from_be_bytes(spec.payload()),
+        0xB5A1CD34B5F99D1154FB6853345C9735
+    );
+    assert_eq!(spec.to_string(), "0ujtsYcgvSTl8PAuAdqWYSMnLOv");
+    assert_eq!(Ksuid::from_bytes(*spec.as_bytes()), spec);
+    let max = Ksuid::from_bytes([0xFF; 20]);
+    assert_eq!(max.to_string(), "aWgEPTl1tmebfsQzFP4bxwgy80V");
+    assert_eq!(Ksuid::parse("aWgEPTl1tmebfsQzFP4bxwgy80V"), Ok(max));
+    assert_eq!(
+        Ksuid::parse("aWgEPTl1tmebfsQzFP4bxwgy80W"),
+        Err(MicroShardError::InvalidEncoding)
+    );
+    assert_eq!(
+        Ksuid::parse("0ujtsYcgvSTl8PAuAdqWYSMnLO-"),
+        Err(MicroShardError::InvalidEncoding)
+    );
+    assert_eq!(
+        Ksuid::from_bytes([0; 20]).timestamp_secs(),
+        KSUID_EPOCH_SECS
+    );
+
+    // MicroShard -> KSUID -> MicroShard is lossless
+    let id = MicroShardUUID::from_parts(1_765_503_300_123_456, 7, 99).unwrap();
+    let ksuid = Ksuid::try_from(id).unwrap();
+    assert_eq!(ksuid.timestamp_secs(), 1_765_503_300);
+    assert_eq!(MicroShardUUID::try_from(ksuid), Ok(id));
+    let early = MicroShardUUID::from_parts(1_000_000, 7, 0).unwrap();
+    assert_eq!(Ksuid::try_from(early), Err(MicroShardError::TimeUnderflow));
+    let late = MicroShardUUID::from_parts((1u64 << 54) - 1, 7, 0).unwrap();
+    assert_eq!(Ksuid::try_from(late), Err(MicroShardError::TimeOverflow));
+    assert_eq!(
+        MicroShardUUID::try_from(spec),
+        Err(MicroShardError::InvalidEncoding)
+    );
+
+    // Foreign import: second kept, order kept, deterministic
+    let imported = MicroShardUUID::from_ksuid(&spec, 3);
+    assert_eq!(imported.timestamp_micros() / 1_000_000, 1_507_608_047);
+    assert_eq!(imported.shard_id(), 3);
+    assert_eq!(MicroShardUUID::from_ksuid(&spec, 3), imported);
+    let mut payload = spec.payload();
+    payload[0] += 1;
+    let next = Ksuid::from_parts(spec.timestamp_secs(), payload).unwrap();
+    assert!(spec < next);
+    assert!(imported < MicroShardUUID::from_ksuid(&next, 3));
+}
+
+#[test]
+fn test_from_object_id() {
+    let oid = |secs: u32, process: u64, counter: u32| {
+        let mut b = [0u8; 12];
+        b[..4].copy_from_slice(&secs.to_be_bytes());
+        b[4..9].copy_from_slice(&process.to_be_bytes()[3..]);
+        b[9..].copy_from_slice(&counter.to_be_bytes()[1..]);
+        b
+    };
+
+    let id = MicroShardUUID::from_object_id(oid(u32::MAX, (1 << 40) - 1, 0xFF_FFFF));
+    assert_eq!(id.timestamp_micros(), u32::MAX as u64 * 1_000_000);
+    assert_eq!(id.shard_id(), u32::MAX);
+    assert_eq!(id.random(), 0xFFFF_FFFF);
+
+    // ObjectId order (seconds, process, counter) is preserved
+    let keys = [
+        oid(1_700_000_000, 5, 0),
+        oid(1_700_000_000, 5, 1),
+        oid(1_700_000_000, 6, 0),
+        oid(1_700_000_000, 1 << 8, 0),
+        oid(1_700_000_001, 0, 0),
+    ];
+    let ids: Vec<_> = keys
+        .iter()
+        .map(|&k| MicroShardUUID::from_object_id(k))
+        .collect();
+    assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    assert!(keys.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[test]
+fn test_tsid_conversion() {
+    use microshard_uuid::tsid::{DEFAULT_NODE_BITS, TSID_EPOCH_MILLIS};
+
+    // TSID -> MicroShard is lossless and keeps order, for any node width
+    for node_bits in [0, DEFAULT_NODE_BITS, 22] {
+        let keys = [0u64, 1, 1 << 12, 1 << 22, (5 << 22) | 3, u64::MAX];
+        let ids: Vec<_> = keys
+            .iter()
+            .map(|&k| MicroShardUUID::from_tsid(k, node_bits).unwrap())
+            .collect();
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
+        for (&key, id) in keys.iter().zip(&ids) {
+            assert_eq!(id.to_tsid(node_bits), Ok(key));
+        }
+    }
+    let id = MicroShardUUID::from_tsid(u64::MAX, DEFAULT_NODE_BITS).unwrap();
+    assert_eq!(id.shard_id(), 1023);
+    assert_eq!(
+        id.timestamp_micros(),
+        (TSID_EPOCH_MILLIS + (1 << 42) - 1) * 1_000
+    );
+
+    // MicroShard -> TSID drops sub-millisecond time and low random bits
+    let a = MicroShardUUID::from_parts(1_765_503_300_123_456, 9, 0xFFF << 24).unwrap();
+    let b = MicroShardUUID::from_parts(1_765_503_300_123_999, 9, (0xFFF << 24) | 1).unwrap();
+    assert_eq!(a.to_tsid(DEFAULT_NODE_BITS), b.to_tsid(DEFAULT_NODE_BITS));
+    let tsid = a.to_tsid(DEFAULT_NODE_BITS).unwrap();
+    assert_eq!(tsid >> 22, 1_765_503_300_123 - TSID_EPOCH_MILLIS);
+    assert_eq!((tsid >> 12) & 0x3FF, 9);
+    assert_eq!(tsid & 0xFFF, 0xFFF);
+
+    // Shard must fit the node field; time must fit the TSID range
+    let wide = MicroShardUUID::from_parts(1_765_503_300_123_456, 1024, 0).unwrap();
+    assert_eq!(
+        wide.to_tsid(DEFAULT_NODE_BITS),
+        Err(MicroShardError::InvalidShardId(1023))
+    );
+    let old = MicroShardUUID::from_parts(1_000, 0, 0).unwrap();
+    assert_eq!(old.to_tsid(0), Err(MicroShardError::TimeUnderflow));
+    let far = MicroShardUUID::from_parts((1 << 54) - 1, 0, 0).unwrap();
+    assert_eq!(far.to_tsid(0), Err(MicroShardError::TimeOverflow));
+    assert_eq!(
+        MicroShardUUID::from_tsid(0, 23),
+        Err(MicroShardError::InvalidRange)
+    );
+    assert_eq!(a.to_tsid(23), Err(MicroShardError::InvalidRange));
+}
+
+#[test]
+fn test_from_sonyflake() {
+    use microshard_uuid::snowflake::SONYFLAKE_EPOCH_MILLIS;
+
+    let sonyflake = |ticks: u64, seq: u64, machine: u64| (ticks << 24) | (seq << 16) | machine;
+
+    let id = MicroShardUUID::from_sonyflake(sonyflake((1 << 39) - 1, 255, 0xFFFF)).unwrap();
+    assert_eq!(
+        id.timestamp_micros(),
+        SONYFLAKE_EPOCH_MILLIS * 1_000 + ((1 << 39) - 1) * 10_000
+    );
+    assert_eq!(id.shard_id(), 0xFFFF);
+    assert_eq!(id.random(), 255 << 28);
+
+    // Ticks keep their order; the same machine keeps its sequence order
+    let keys = [
+        sonyflake(7, 0, 2),
+        sonyflake(7, 1, 2),
+        sonyflake(7, 0, 3),
+        sonyflake(8, 0, 0),
+    ];
+    let ids: Vec<_> = keys
+        .iter()
+        .map(|&k| MicroShardUUID::from_sonyflake(k).unwrap())
+        .collect();
+    assert!(ids.windows(2).all(|w| w[0] < w[1]));
+
+    let custom = MicroShardUUID::from_sonyflake_with_epoch(sonyflake(1, 0, 1), 0).unwrap();
+    assert_eq!(custom.timestamp_micros(), 10_000);
+    assert_eq!(
+        MicroShardUUID::from_sonyflake(1 << 63),
+        Err(MicroShardError::InvalidEncoding)
+    );
+    assert_eq!(
+        MicroShardUUID::from_sonyflake_with_epoch(0, u64::MAX),
+        Err(MicroShardError::TimeOverflow)
+    );
+}
+
+#[test]
+fn test_pluggable_clock() {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    // A simulated clock shared between the test and the generators
+    let sim = Arc::new(AtomicU64::new(1_765_503_300_000_000));
+    let clock = {
+        let sim = Arc::clone(&sim);
+        move || Ok(sim.load(Ordering::SeqCst))
+    };
+
+    let mut ids = MicroShardGenerator::new(3)
+        .unwrap()
+        .with_clock(clock.clone());
+    assert_eq!(
+        ids.next().unwrap().timestamp_micros(),
+        1_765_503_300_000_000
+    );
+    sim.store(1_765_503_300_000_042, Ordering::SeqCst);
+    assert_eq!(
+        ids.next().unwrap().timestamp_micros(),
+        1_765_503_300_000_042
+    );
+    let batch = ids.generate_batch(3).unwrap();
+    assert!(batch
+        .iter()
+        .all(|id| id.timestamp_micros() == 1_765_503_300_000_042));
+
+    // The caller's clock wins over freeze_time
+    let _guard = test_support::freeze_time(1);
+    let unique = UniqueGenerator::new(3).unwrap().with_clock(clock);
+    let a = unique.next().unwrap();
+    let b = unique.next().unwrap();
+    assert_eq!(a.timestamp_micros(), 1_765_503_300_000_042);
+    assert!(a < b);
+
+    // Clock is the same trait as TimeSource
+    fn read(clock: &impl microshard_uuid::time_source::Clock) -> u64 {
+        clock.now_micros().unwrap()
+    }
+    assert!(read(&SystemTimeSource) > 1_700_000_000_000_000);
+
+    // Clock errors propagate
+    let broken = || Err(MicroShardError::SystemTimeError);
+    let mut ids = MicroShardGenerator::new(3).unwrap().with_clock(broken);
+    assert_eq!(ids.next(), Err(MicroShardError::SystemTimeError));
+}
+
+#[test]
+fn test_mock_clock_and_deterministic_generator() {
+    use microshard_uuid::test_support::{DeterministicGenerator, MockClock};
+
+    let clock = MockClock::new(1_000);
+    let handle = clock.clone();
+    handle.advance(Duration::from_micros(500));
+    assert_eq!(clock.get(), 1_500);
+    clock.set(10);
+    assert_eq!(handle.now_micros(), Ok(10));
+    clock.set(u64::MAX);
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(clock.get(), u64::MAX);
+
+    // Same seed and clock movements: identical IDs, across threads
+    let run = || {
+        let mut ids = DeterministicGenerator::new(5, 1234, 1_765_503_300_000_000).unwrap();
+        let mut out = vec![ids.next().unwrap(), ids.next().unwrap()];
+        ids.clock().advance(Duration::from_micros(3));
+        out.extend(ids.generate_batch(3).unwrap());
+        out
+    };
+    let first = run();
+    assert_eq!(first, std::thread::spawn(run).join().unwrap());
+    assert_eq!(first[0].timestamp_micros(), 1_765_503_300_000_000);
+    assert_eq!(first[2].timestamp_micros(), 1_765_503_300_000_003);
+    assert!(first.iter().all(|id| id.shard_id() == 5));
+    assert_ne!(first[0], first[1]);
+
+    // A different seed gives different random bits
+    let mut other = DeterministicGenerator::new(5, 4321, 1_765_503_300_000_000).unwrap();
+    assert_ne!(other.next().unwrap(), first[0]);
+
+    // Monotonic mode holds time when the clock moves backwards
+    let mut ids = DeterministicGenerator::new(5, 1, 2_000)
+        .unwrap()
+        .monotonic();
+    let a = ids.next().unwrap();
+    ids.clock().set(1_000);
+    let b = ids.next().unwrap();
+    assert!(a < b);
+    assert_eq!(b.timestamp_micros(), 2_000);
+}
+
+#[test]
+fn test_clock_regression_policy() {
+    use microshard_uuid::test_support::MockClock;
+
+    let t = 1_765_503_300_000_000;
+
+    // Default: no guard, IDs follow the clock backwards
+    let mut ids = MicroShardGenerator::new(1).unwrap();
+    ids.next_at(t).unwrap();
+    assert_eq!(ids.next_at(t - 10).unwrap().timestamp_micros(), t - 10);
+
+    // Error
+    let mut ids = MicroShardGenerator::new(1)
+        .unwrap()
+        .with_regression_policy(ClockRegressionPolicy::Error);
+    ids.next_at(t).unwrap();
+    assert_eq!(ids.next_at(t).unwrap().timestamp_micros(), t);
+    assert_eq!(
+        ids.next_at(t - 10),
+        Err(MicroShardError::ClockMovedBackwards(10))
+    );
+    assert!(ids.next_at(t + 1).is_ok());
+
+    // NextMicro: each regressed ID moves 1µs past the last one
+    let mut ids = MicroShardGenerator::new(1)
+        .unwrap()
+        .with_regression_policy(ClockRegressionPolicy::NextMicro);
+    let a = ids.next_at(t).unwrap();
+    let b = ids.next_at(t - 10).unwrap();
+    let c = ids.next_at(t - 5).unwrap();
+    assert_eq!(b.timestamp_micros(), t + 1);
+    assert_eq!(c.timestamp_micros(), t + 2);
+    assert!(a < b && b < c);
+    assert_eq!(ids.next_at(t + 50).unwrap().timestamp_micros(), t + 50);
+
+    // Batches respect the last issued timestamp too
+    let clock = MockClock::new(t);
+    let mut ids = MicroShardGenerator::new(1)
+        .unwrap()
+        .with_clock(clock.clone())
+        .with_regression_policy(ClockRegressionPolicy::NextMicro);
+    ids.next().unwrap();
+    clock.set(t - 100);
+    let batch = ids.generate_batch(3).unwrap();
+    assert!(batch.iter().all(|id| id.timestamp_micros() == t + 1));
+
+    // Wait: sleeps until the clock catches up, or gives up
+    let clock = MockClock::new(t);
+    let mut ids = MicroShardGenerator::new(1)
+        .unwrap()
+        .with_clock(clock.clone())
+        .with_regression_policy(ClockRegressionPolicy::Wait(Duration::from_millis(20)));
+    ids.next().unwrap();
+    clock.set(t - 1_000);
+    assert_eq!(ids.next(), Err(MicroShardError::ClockMovedBackwards(1_000)));
+    assert_eq!(
+        ids.next_at(t - 1),
+        Err(MicroShardError::ClockMovedBackwards(1))
+    );
+    let ticking = {
+        let clock = clock.clone();
+        move || {
+            clock.advance(Duration::from_micros(400));
+            Ok(clock.get())
+        }
+    };
+    let mut ids = MicroShardGenerator::new(1)
+        .unwrap()
+        .with_clock(ticking)
+        .with_regression_policy(ClockRegressionPolicy::Wait(Duration::from_secs(5)));
+    let first = ids.next().unwrap();
+    clock.set(first.timestamp_micros() - 1_000);
+    let caught_up = ids.next().unwrap();
+    assert!(caught_up.timestamp_micros() >= first.timestamp_micros());
+}
+
+#[test]
+fn test_clock_sequence_policy() {
+    use microshard_uuid::test_support::MockClock;
+
+    let t = 1_765_503_300_000_000;
+    let mut ids = MicroShardGenerator::new(2)
+        .unwrap()
+        .with_regression_policy(ClockRegressionPolicy::ClockSequence);
+    assert_eq!(
+        ids.regression_policy(),
+        Some(ClockRegressionPolicy::ClockSequence)
+    );
+
+    let before = ids.next_at(t).unwrap();
+    assert_eq!(before.clock_sequence(), 0);
+    assert_eq!(ids.next_at(t).unwrap().clock_sequence(), 0);
+
+    // The clock steps back: real time is stamped, the clock sequence bumps
+    let after = ids.next_at(t - 1_000).unwrap();
+    assert_eq!(after.timestamp_micros(), t - 1_000);
+    assert_eq!(after.clock_sequence(), 1);
+    assert_eq!(ids.clock_sequence(), 1);
+    assert!(after < before);
+    let key = |id: &MicroShardUUID| (id.clock_sequence(), *id);
+    assert!(key(&before) < key(&after));
+    assert_eq!(ids.next_at(t + 5).unwrap().clock_sequence(), 1);
+
+    // Wraps after 16 steps
+    for _ in 0..15 {
+        ids.next_at(0).unwrap();
+        ids.next_at(t).unwrap();
+    }
+    assert_eq!(ids.clock_sequence(), 0);
+
+    // Monotonic mode restarts its sequence at the earlier time
+    let clock = MockClock::new(t);
+    let mut ids = MicroShardGenerator::monotonic(2)
+        .unwrap()
+        .with_clock(clock.clone())
+        .with_regression_policy(ClockRegressionPolicy::ClockSequence);
+    let a = ids.next().unwrap();
+    clock.set(t - 10);
+    let batch = ids.generate_batch(3).unwrap();
+    assert!(batch.windows(2).all(|w| w[0] < w[1]));
+    assert!(batch
+        .iter()
+        .all(|id| id.timestamp_micros() == t - 10 && id.clock_sequence() == 1));
+    assert_eq!(a.clock_sequence(), 0);
+    assert!(!batch.contains(&a));
+
+    // Without the policy the low bits are plain random bits
+    assert_eq!(
+        MicroShardGenerator::new(2).unwrap().regression_policy(),
+        None
+    );
+}
+
+#[test]
+fn test_max_future_skew() {
+    let t = 1_765_503_300_000_000;
+    let _guard = test_support::freeze_time(t);
+    let skew = Duration::from_secs(60);
+
+    // Ingestion: reject IDs stamped too far ahead of the clock
+    let ok = MicroShardUUID::from_micros(t + 60_000_000, 1).unwrap();
+    assert_eq!(ok.check_future_skew(skew), Ok(ok));
+    let past = MicroShardUUID::from_micros(0, 1).unwrap();
+    assert_eq!(past.check_future_skew(Duration::ZERO), Ok(past));
+    let ahead = MicroShardUUID::from_micros(t + 60_000_001, 1).unwrap();
+    assert_eq!(
+        ahead.check_future_skew(skew),
+        Err(MicroShardError::TimestampInFuture(60_000_001))
+    );
+    let far = MicroShardUUID::from_iso("2400-01-01T00:00:00.000000Z", 1).unwrap();
+    assert!(matches!(
+        far.check_future_skew(skew),
+        Err(MicroShardError::TimestampInFuture(_))
+    ));
+
+    // Generation: next_at is checked, next is not
+    let mut ids = MicroShardGenerator::new(1)
+        .unwrap()
+        .with_max_future_skew(skew);
+    assert_eq!(ids.max_future_skew(), Some(skew));
+    assert!(ids.next_at(t + 60_000_000).is_ok());
+    assert_eq!(
+        ids.next_at(t + 120_000_000),
+        Err(MicroShardError::TimestampInFuture(120_000_000))
+    );
+    assert!(ids.next_at(t - 1).is_ok());
+    assert!(ids.next().is_ok());
+    assert_eq!(MicroShardGenerator::new(1).unwrap().max_future_skew(), None);
+
+    assert_eq!(
+        MicroShardError::TimestampInFuture(5).to_string(),
+        "Timestamp is 5 microseconds ahead of the clock"
+    );
+}
+
+#[test]
+fn test_timestamp_as_system_time() {
+    let id = MicroShardUUID::from_parts(1_700_000_000_123_456, 1, 0).unwrap();
+    assert_eq!(
+        id.since_epoch(),
+        Duration::from_micros(1_700_000_000_123_456)
+    );
+    assert_eq!(id.since_epoch().subsec_micros(), 123_456);
+    assert_eq!(
+        id.timestamp().duration_since(UNIX_EPOCH).unwrap(),
+        id.since_epoch()
+    );
+
+    // Round trip through the live clock, at microsecond precision
+    let before = SystemTime::now();
+    let live = MicroShardUUID::generate(1).unwrap();
+    let truncated = UNIX_EPOCH
+        + Duration::from_micros(before.duration_since(UNIX_EPOCH).unwrap().as_micros() as u64);
+    assert!(live.timestamp() >= truncated);
+
+    let max = MicroShardUUID::from_parts((1 << 54) - 1, 0, 0).unwrap();
+    assert_eq!(max.since_epoch().as_micros(), (1 << 54) - 1);
+    let zero = MicroShardUUID::from_parts(0, 0, 0).unwrap();
+    assert_eq!(zero.timestamp(), UNIX_EPOCH);
+}
+
+#[test]
+fn test_age() {
+    let t = 1_765_503_300_000_000;
+    let _guard = test_support::freeze_time(t);
+    let id = MicroShardUUID::from_micros(t - 1_500_000, 1).unwrap();
+    assert_eq!(id.age(), Ok(Duration::from_micros(1_500_000)));
+    let now = MicroShardUUID::generate(1).unwrap();
+    assert_eq!(now.age(), Ok(Duration::ZERO));
+
+    test_support::advance(Duration::from_secs(10));
+    assert_eq!(id.age(), Ok(Duration::from_micros(11_500_000)));
+
+    let future = MicroShardUUID::from_micros(t + 10_000_007, 1).unwrap();
+    assert_eq!(future.age(), Err(MicroShardError::TimestampInFuture(7)));
+}
+
+#[test]
+fn test_iso_utc_offsets() {
+    let utc = iso_to_micros("2024-05-01T04:30:00.123456Z").unwrap();
+    assert_eq!(iso_to_micros("2024-05-01T10:00:00.123456+05:30"), Ok(utc));
+    assert_eq!(iso_to_micros("2024-04-30T20:30:00.123456-08:00"), Ok(utc));
+    assert_eq!(
+        iso_to_micros("2024-05-01T10:00:00+00:00"),
+        iso_to_micros("2024-05-01T10:00:00Z")
+    );
+    assert_eq!(
+        iso_to_micros("2024-05-01T10:00:00-00:00"),
+        iso_to_micros("2024-05-01T10:00:00Z")
+    );
+
+    // Normalized to UTC before encoding
+    let id = MicroShardUUID::from_iso("2024-05-01T10:00:00.123456+05:30", 1).unwrap();
+    assert_eq!(id.to_iso_string(), "2024-05-01T04:30:00.123456Z");
+
+    // Offsets can cross the date line and the year
+    assert_eq!(
+        iso_to_micros("2024-01-01T00:30:00+01:00"),
+        iso_to_micros("2023-12-31T23:30:00Z")
+    );
+    assert_eq!(
+        iso_to_micros("1970-01-01T00:30:00+01:00"),
+        Err(MicroShardError::InvalidIsoFormat)
+    );
+
+    // Malformed offsets
+    for bad in [
+        "2024-05-01T10:00:00+5:30",
+        "2024-05-01T10:00:00+05",
+        "2024-05-01T10:00:00+0530",
+        "2024-05-01T10:00:00+24:00",
+        "2024-05-01T10:00:00+05:60",
+        "2024-05-01T10:00:00.5-05:3x",
+        "2024-05-01T10:00:00+05:30Z",
+    ] {
+        assert_eq!(
+            iso_to_micros(bad),
+            Err(MicroShardError::InvalidUtcOffset),
+            "{}",
+            bad
+        );
+    }
+    assert_eq!(
+        iso_to_micros("2024-05-01T10:00:00X"),
+        Err(MicroShardError::InvalidIsoFormat)
+    );
+    assert_eq!(
+        MicroShardError::InvalidUtcOffset.to_string(),
+        "Invalid UTC offset, expected ±HH:MM"
+    );
+}
+
+#[test]
+fn test_iso_lenient() {
+    let expected = iso_to_micros("2024-05-01T10:00:00.123456Z").unwrap();
+    for ok in [
+        "2024-05-01T10:00:00.123456Z",
+        "2024-05-01 10:00:00.123456Z",
+        "2024-05-01t10:00:00.123456z",
+        "2024-05-01 10:00:00.123456",
+        "2024-05-01 10:00:00,123456Z",
+        "2024-05-01 10:00:00123456",
+        "2024-05-01 10:00:00.123456+00",
+        "2024-05-01 10:00:00.123456 +0000",
+        "2024-05-01T15:30:00.123456+0530",
+        "2024-05-01T05:00:00.123456-05",
+    ] {
+        assert_eq!(iso_to_micros_lenient(ok), Ok(expected), "{}", ok);
+    }
+    assert_eq!(
+        iso_to_micros_lenient("2024-05-01 10:00:00"),
+        iso_to_micros("2024-05-01T10:00:00Z")
+    );
+    let id = MicroShardUUID::from_iso_lenient("2024-05-01 10:00:00.123456+00", 3).unwrap();
+    assert_eq!(id.to_iso_string(), "2024-05-01T10:00:00.123456Z");
+
+    // The strict parser is unchanged
+    assert!(iso_to_micros("2024-05-01 10:00:00Z").is_err());
+    assert!(iso_to_micros("2024-05-01T10:00:00.123456+0530").is_err());
+    assert!(MicroShardUUID::from_iso("2024-05-01t10:00:00z", 3).is_err());
+
+    // Field ranges are still validated
+    assert_eq!(
+        iso_to_micros_lenient("2024-02-30 10:00:00"),
+        Err(MicroShardError::InvalidIsoFormat)
+    );
+    assert_eq!(
+        iso_to_micros_lenient("2024-05-01 10:00:00+2400"),
+        Err(MicroShardError::InvalidUtcOffset)
+    );
+    assert_eq!(
+        iso_to_micros_lenient("2024-05-01 10:00:00+5"),
+        Err(MicroShardError::InvalidUtcOffset)
+    );
+    assert_eq!(
+        iso_to_micros_lenient("2024-05-01_10:00:00Z"),
+        Err(MicroShardError::InvalidIsoFormat)
+    );
+    assert_eq!(
+        iso_to_micros_lenient("2024-05-01 10:00:0\u{e9}Z"),
+        Err(MicroShardError::InvalidIsoFormat)
+    );
+}
+
+#[test]
+fn test_iso_precision() {
+    let id = MicroShardUUID::from_iso("2024-05-01T10:00:00.987654Z", 1).unwrap();
+    let expected = [
+        "2024-05-01T10:00:00Z",
+        "2024-05-01T10:00:00.9Z",
+        "2024-05-01T10:00:00.98Z",
+        "2024-05-01T10:00:00.987Z",
+        "2024-05-01T10:00:00.9876Z",
+        "2024-05-01T10:00:00.98765Z",
+        "2024-05-01T10:00:00.987654Z",
+    ];
+    for (digits, want) in expected.iter().enumerate() {
+        assert_eq!(id.to_iso_string_with_precision(digits as u8), *want);
+        assert_eq!(id.iso_display().precision(digits as u8).to_string(), *want);
+        // Every precision parses back (truncated) with the strict parser
+        assert!(iso_to_micros(want).unwrap() <= id.timestamp_micros());
+    }
+    assert_eq!(id.to_iso_string_with_precision(9), id.to_iso_string());
+
+    // Leading zeros in the fraction are kept
+    let early = MicroShardUUID::from_iso("2024-05-01T10:00:00.001002Z", 1).unwrap();
+    assert_eq!(
+        early.to_iso_string_with_precision(3),
+        "2024-05-01T10:00:00.001Z"
+    );
+}
+
+#[test]
+fn test_iso_format_profiles() {
+    let id = MicroShardUUID::from_iso("2024-05-01T10:00:00.123456Z", 1).unwrap();
+    let iso = id.iso_display();
+
+    // Elasticsearch-style numeric offset
+    let offset = iso.numeric_offset().to_string();
+    assert_eq!(offset, "2024-05-01T10:00:00.123456+00:00");
+    assert_eq!(iso_to_micros(&offset), Ok(id.timestamp_micros()));
+
+    // Space separator (Splunk, SQL literals)
+    let spaced = iso.separator(' ').to_string();
+    assert_eq!(spaced, "2024-05-01 10:00:00.123456Z");
+    assert_eq!(iso_to_micros_lenient(&spaced), Ok(id.timestamp_micros()));
+
+    // ISO 8601 basic form (legacy SOAP)
+    assert_eq!(iso.basic().to_string(), "20240501T100000.123456Z");
+    assert_eq!(
+        iso.basic().precision(0).numeric_offset().to_string(),
+        "20240501T100000+0000"
+    );
+
+    // Options combine and the default is unchanged
+    assert_eq!(
+        iso.separator(' ').numeric_offset().precision(3).to_string(),
+        "2024-05-01 10:00:00.123+00:00"
+    );
+    assert_eq!(iso.to_string(), id.to_iso_string());
+}
+
+#[test]
+fn test_nanos_layout() {
+    let t = 1_765_503_300_123_456_789;
+    let id = MicroShardUUIDNanos::from_parts(t, u32::MAX, (1 << 26) - 1).unwrap();
+    assert_eq!(id.timestamp_nanos(), t);
+    assert_eq!(id.timestamp_micros(), t / 1_000);
+    assert_eq!(id.shard_id(), u32::MAX);
+    assert_eq!(id.random(), (1 << 26) - 1);
+    assert_eq!(id.since_epoch(), Duration::from_nanos(t));
+    assert_eq!(id.timestamp(), UNIX_EPOCH + Duration::from_nanos(t));
+
+    // A valid v8 UUID that round-trips through every form
+    assert_eq!(id.as_u128() >> 76 & 0xF, 8);
+    assert_eq!(id.as_u128() >> 62 & 0x3, 2);
+    assert_eq!(MicroShardUUIDNanos::from_bytes(id.as_bytes()), Ok(id));
+    assert_eq!(id.to_string().parse::<MicroShardUUIDNanos>(), Ok(id));
+
+    // Full u64 range; 1ns apart sorts in order
+    for nanos in [0, 1, 15, 16, u64::MAX] {
+        let a = MicroShardUUIDNanos::from_parts(nanos, 7, 0).unwrap();
+        assert_eq!(a.timestamp_nanos(), nanos);
+    }
+    let ids: Vec<_> = (0..64)
+        .map(|i| MicroShardUUIDNanos::from_parts(t + i, 0, (1 << 26) - 1).unwrap())
+        .collect();
+    assert!(ids.windows(2).all(|w| w[0] < w[1]));
+
+    assert_eq!(
+        MicroShardUUIDNanos::from_parts(t, 1, 1 << 26),
+        Err(MicroShardError::InvalidRandom(1 << 26))
+    );
+}
+
+#[test]
+fn test_nanos_generation_and_conversion() {
+    let _guard = test_support::freeze_time(1_765_503_300_123_456);
+    let id = MicroShardUUIDNanos::generate(9).unwrap();
+    assert_eq!(id.timestamp_nanos(), 1_765_503_300_123_456_000);
+    assert_eq!(id.shard_id(), 9);
+    assert!(MicroShardUUIDNanos::from_nanos(0, 9).is_ok());
+
+    let micro = MicroShardUUID::from_parts(1_765_503_300_123_456, 9, 0xA_BCDE_F123).unwrap();
+    let nano = MicroShardUUIDNanos::from(micro);
+    assert_eq!(nano.timestamp_nanos(), 1_765_503_300_123_456_000);
+    assert_eq!(nano.shard_id(), 9);
+    assert_eq!(nano.random(), 0xA_BCDE_F123 >> 10);
+    let back = MicroShardUUID::try_from(nano).unwrap();
+    assert_eq!(back.timestamp_micros(), micro.timestamp_micros());
+    assert_eq!(back.shard_id(), 9);
+    assert_eq!(back.random(), 0xA_BCDE_F123 & !0x3FF);
+
+    let past_2541 = MicroShardUUIDNanos::from_parts(u64::MAX, 0, 0).unwrap();
+    assert_eq!(
+        MicroShardUUID::try_from(past_2541),
+        Err(MicroShardError::TimeOverflow)
+    );
+
+    // A frozen time past u64 nanoseconds errors instead of wrapping
+    let _far = test_support::freeze_time(u64::MAX / 1_000 + 1);
+    assert_eq!(
+        MicroShardUUIDNanos::generate(9),
+        Err(MicroShardError::TimeOverflow)
+    );
+}
+
+#[test]
+fn test_sharded_uuid_default_split_matches_microshard() {
+    type Default = ShardedUuid<54, 32>;
+    assert_eq!(Default::RANDOM_BITS, 36);
+    for (micros, shard, random) in [
+        (0, 0, 0),
+        (1_765_503_300_123_456, 0xDEAD_BEEF, 0xA_BCDE_F123),
+        ((1 << 54) - 1, u32::MAX, (1 << 36) - 1),
+    ] {
+        let ms = MicroShardUUID::from_parts(micros, shard, random).unwrap();
+        let generic = Default::from_parts(micros, shard as u64, random).unwrap();
+        assert_eq!(generic.as_u128(), ms.as_u128());
+        assert_eq!(generic.timestamp_micros(), micros);
+        assert_eq!(generic.shard_id(), shard as u64);
+        assert_eq!(generic.random(), random);
+        assert_eq!(MicroShardUUID::from(generic), ms);
+        assert_eq!(Default::from(ms), generic);
+    }
+}
+
+#[test]
+fn test_sharded_uuid_custom_splits() {
+    type Small = ShardedUuid<54, 8>;
+    type Wide = ShardedUuid<54, 40>;
+    type Nanos = ShardedUuid<64, 0>;
+    assert_eq!(
+        (Small::RANDOM_BITS, Wide::RANDOM_BITS, Nanos::RANDOM_BITS),
+        (60, 28, 58)
+    );
+
+    let t = 1_765_503_300_123_456;
+    let small = Small::from_parts(t, 255, Small::MAX_RANDOM).unwrap();
+    assert_eq!(
+        (small.timestamp_micros(), small.shard_id(), small.random()),
+        (t, 255, (1 << 60) - 1)
+    );
+    let wide = Wide::from_parts(t, Wide::MAX_SHARD, 0).unwrap();
+    assert_eq!(wide.shard_id(), (1 << 40) - 1);
+    let full = Nanos::from_parts(u64::MAX, 0, 0).unwrap();
+    assert_eq!(full.timestamp_micros(), u64::MAX);
+
+    // Valid v8, order follows time then shard
+    for v in [small.as_u128(), wide.as_u128(), full.as_u128()] {
+        assert_eq!((v >> 76) & 0xF, 8);
+        assert_eq!((v >> 62) & 0x3, 2);
+    }
+    assert!(Small::from_parts(t, 1, 0).unwrap() < Small::from_parts(t, 2, 0).unwrap());
+    assert!(Small::from_parts(t, 255, 0).unwrap() < Small::from_parts(t + 1, 0, 0).unwrap());
+
+    // Per-field validation
+    assert_eq!(
+        Small::from_parts(t, 256, 0),
+        Err(MicroShardError::InvalidShardId(255))
+    );
+    assert_eq!(
+        Wide::from_parts(t, 1 << 40, 0),
+        Err(MicroShardError::InvalidShardId(u32::MAX))
+    );
+    assert_eq!(
+        Wide::from_parts(t, 0, 1 << 28),
+        Err(MicroShardError::InvalidRandom(1 << 28))
+    );
+    assert_eq!(
+        Small::from_parts(1 << 54, 0, 0),
+        Err(MicroShardError::TimeOverflow)
+    );
+
+    // Generation and text round trips
+    let live = Small::generate(3).unwrap();
+    assert_eq!(live.shard_id(), 3);
+    assert_eq!(live.to_string().parse::<Small>(), Ok(live));
+    assert_eq!(Wide::from_bytes(wide.as_bytes()), Ok(wide));
+    let randoms: HashSet<u64> = (0..100)
+        .map(|_| Small::from_micros(t, 1).unwrap().random())
+        .collect();
+    assert_eq!(randoms.len(), 100);
+    assert!(randoms.iter().any(|&r| r >> 36 != 0));
+}
+
+#[test]
+fn test_runtime_layout() {
+    assert_eq!(Layout::new(54, 32, 36), Ok(Layout::MICROSHARD));
+    assert_eq!(Layout::new(54, 32, 35), Err(MicroShardError::InvalidRange));
+    assert_eq!(Layout::new(0, 58, 64), Err(MicroShardError::InvalidRange));
+    assert_eq!(Layout::new(54, 0, 68), Err(MicroShardError::InvalidRange));
+    assert_eq!(Layout::new(65, 57, 0), Err(MicroShardError::InvalidRange));
+
+    // Matches the fixed layout and the compile-time one bit for bit
+    let id = MicroShardUUID::from_parts(1_765_503_300_123_456, 0xDEAD_BEEF, 0xA_BCDE_F123).unwrap();
+    let v = layout::encode(
+        Layout::MICROSHARD,
+        1_765_503_300_123_456,
+        0xDEAD_BEEF,
+        0xA_BCDE_F123,
+    );
+    assert_eq!(v, Ok(id.as_u128()));
+    let fields = layout::decode(Layout::MICROSHARD, id.as_u128()).unwrap();
+    assert_eq!(
+        (fields.micros, fields.shard, fields.random),
+        (id.timestamp_micros(), id.shard_id() as u64, id.random())
+    );
+
+    let wide = Layout::new(54, 40, 28).unwrap();
+    assert_eq!(ShardedUuid::<54, 40>::LAYOUT, wide);
+    assert_eq!(
+        (wide.max_time(), wide.max_shard(), wide.max_random()),
+        ((1 << 54) - 1, (1 << 40) - 1, (1 << 28) - 1)
+    );
+    let typed = ShardedUuid::<54, 40>::from_parts(42, 1 << 39, 5).unwrap();
+    assert_eq!(layout::encode(wide, 42, 1 << 39, 5), Ok(typed.as_u128()));
+    let fields = layout::decode(wide, typed.as_u128()).unwrap();
+    assert_eq!(
+        (fields.micros, fields.shard, fields.random),
+        (42, 1 << 39, 5)
+    );
+
+    // Per-field validation and version/variant checks
+    assert_eq!(
+        layout::encode(wide, 1 << 54, 0, 0),
+        Err(MicroShardError::TimeOverflow)
+    );
+    assert_eq!(
+        layout::encode(Layout::new(54, 8, 60).unwrap(), 0, 256, 0),
+        Err(MicroShardError::InvalidShardId(255))
+    );
+    assert_eq!(
+        layout::encode(wide, 0, 0, 1 << 28),
+        Err(MicroShardError::InvalidRandom(1 << 28))
+    );
+    assert_eq!(
+        layout::decode(wide, 0),
+        Err(MicroShardError::InvalidVersion(0))
+    );
+}
+
+#[test]
+fn test_builder() {
+    let t = 1_765_503_300_123_456;
+    let full = MicroShardBuilder::new()
+        .shard(42)
+        .timestamp_micros(t)
+        .random(0xA_BCDE_F123)
+        .build()
+        .unwrap();
+    assert_eq!(
+        full,
+        MicroShardUUID::from_parts(t, 42, 0xA_BCDE_F123).unwrap()
+    );
+
+    // Defaults: shard 0, now, fresh random
+    let _guard = test_support::freeze_time(t);
+    let defaults = MicroShardBuilder::new().build().unwrap();
+    assert_eq!((defaults.shard_id(), defaults.timestamp_micros()), (0, t));
+    let builder = MicroShardBuilder::new().shard(7);
+    let randoms: HashSet<u64> = (0..100)
+        .map(|_| builder.build().unwrap().random())
+        .collect();
+    assert_eq!(randoms.len(), 100);
+
+    // Fixing the random field alone is deterministic at a frozen time
+    let fixed = MicroShardBuilder::new().shard(7).random(5);
+    assert_eq!(fixed.build(), fixed.build());
+
+    // Later setters win
+    let id = MicroShardBuilder::new().shard(1).shard(2).build().unwrap();
+    assert_eq!(id.shard_id(), 2);
+
+    assert_eq!(
+        MicroShardBuilder::new().timestamp_micros(1 << 54).build(),
+        Err(MicroShardError::TimeOverflow)
+    );
+    assert_eq!(
+        MicroShardBuilder::new().random(1 << 36).build(),
+        Err(MicroShardError::InvalidRandom(1 << 36))
+    );
+}
+
+#[test]
+fn test_shard_from_ip() {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    assert_eq!(
+        shard::from_ip(Ipv4Addr::new(10, 0, 3, 7).into()),
+        0x0A00_0307
+    );
+    assert_eq!(shard::from_ip(Ipv4Addr::BROADCAST.into()), u32::MAX);
+    let v6: IpAddr = "fd00::1:c0a8:0107".parse::<Ipv6Addr>().unwrap().into();
+    assert_eq!(shard::from_ip(v6), 0xC0A8_0107);
+
+    // The sandbox may have no route at all; when it has one, it is usable
+    match shard::local_ipv4() {
+        Ok(ip) => {
+            assert!(!ip.is_loopback() && !ip.is_unspecified());
+            let id = shard::from_local_ip().unwrap();
+            assert_eq!(id, u32::from(ip));
+            assert_eq!(MicroShardUUID::generate(id).unwrap().shard_id(), id);
+        }
+        Err(e) => assert!(shard::from_local_ip().is_err(), "{}", e),
+    }
+}
+
+#[test]
+fn test_shard_id_from_env() {
+    // Unique names: the environment is shared by every test thread
+    let var = "MICROSHARD_TEST_SHARD_ID";
+    std::env::set_var(var, " 42\n");
+    let shard = ShardId::from_env(var).unwrap();
+    assert_eq!((shard.get(), u32::from(shard)), (42, 42));
+    assert_eq!(shard.to_string(), "42");
+    std::env::set_var(var, "4294967295");
+    assert_eq!(ShardId::from_env(var).unwrap().get(), u32::MAX);
+
+    std::env::set_var(var, "shard-7");
+    let err = ShardId::from_env(var).unwrap_err();
+    assert_eq!(
+        err,
+        ShardIdError::Invalid {
+            var: var.to_string(),
+            value: "shard-7".to_string()
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "MICROSHARD_TEST_SHARD_ID=\"shard-7\" is not a shard ID (expected an integer 0..=4294967295)"
+    );
+    std::env::set_var(var, "-1");
+    assert!(matches!(
+        ShardId::from_env(var),
+        Err(ShardIdError::Invalid { .. })
+    ));
+
+    std::env::set_var(var, "4294967296");
+    let err = ShardId::from_env(var).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "MICROSHARD_TEST_SHARD_ID=4294967296 is out of range (expected 0..=4294967295)"
+    );
+
+    std::env::remove_var(var);
+    let err = ShardId::from_env(var).unwrap_err();
+    assert_eq!(err, ShardIdError::Missing(var.to_string()));
+    assert_eq!(
+        err.to_string(),
+        "environment variable MICROSHARD_TEST_SHARD_ID is not set"
+    );
+
+    assert_eq!(ShardId::new(7).map(ShardId::get), Ok(7));
+}
+
+#[test]
+fn test_shard_lease() {
+    use shard::ShardLease;
+    use std::io::ErrorKind;
+
+    let dir = std::env::temp_dir().join(format!("microshard-lease-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let a = ShardLease::acquire(&dir, 5..=6).unwrap();
+    let b = ShardLease::acquire(&dir, 5..=6).unwrap();
+    assert_eq!((a.shard_id(), b.shard_id()), (5, 6));
+    assert_eq!(a.shard().get(), 5);
+    assert_eq!(a.path(), dir.join("shard-5.lock"));
+    let pid = std::fs::read_to_string(a.path()).unwrap();
+    assert_eq!(pid.trim(), std::process::id().to_string());
+
+    let err = ShardLease::acquire(&dir, 5..=6).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::ResourceBusy);
+
+    // Dropping a lease frees its shard for the next claimant
+    drop(a);
+    assert_eq!(ShardLease::acquire(&dir, 5..=6).unwrap().shard_id(), 5);
+
+    #[allow(clippy::reversed_empty_ranges)]
+    let empty = 6..=5;
+    let err = ShardLease::acquire(&dir, empty).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    drop(b);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_partition_for() {
+    // Reference vector shared with the other ports: hash = 0x9b74bd9f93a6e0f9
+    let id: MicroShardUUID = "01234567-89ab-8def-8123-456789abcdef".parse().unwrap();
+    assert_eq!(id.partition_for(1), 0);
+    assert_eq!(id.partition_for(12), (0x9b74bd9f93a6e0f9u64 % 12) as u32);
+    assert_eq!(id.partition_for(1000), 225);
+    assert_eq!(
+        id.partition_for(u32::MAX),
+        (0x9b74bd9f93a6e0f9u64 % u32::MAX as u64) as u32
+    );
+
+    // IDs from one shard at one instant still spread over every partition
+    let mut counts = [0u32; 8];
+    for _ in 0..8_000 {
+        let id = MicroShardUUID::from_micros(1_700_000_000_000_000, 42).unwrap();
+        counts[id.partition_for(8) as usize] += 1;
+    }
+    assert!(
+        counts.iter().all(|&c| (800..1200).contains(&c)),
+        "{:?}",
+        counts
+    );
+}
+
+#[test]
+#[should_panic(expected = "num_partitions must be non-zero")]
+fn test_partition_for_zero_panics() {
+    MicroShardUUID::from_micros(0, 0).unwrap().partition_for(0);
+}
+
+#[test]
+fn test_jump_hash() {
+    // Reference vectors (same key derivation as partition_for)
+    let id: MicroShardUUID = "01234567-89ab-8def-8123-456789abcdef".parse().unwrap();
+    let buckets = [1, 2, 10, 1000, u32::MAX].map(|n| id.jump_hash(n));
+    assert_eq!(buckets, [0, 0, 8, 15, 3_502_946_146]);
+
+    // Growing 10 -> 11 buckets: IDs either stay put or move to the new bucket
+    let ids: Vec<_> = (0..11_000)
+        .map(|_| MicroShardUUID::from_micros(1_700_000_000_000_000, 7).unwrap())
+        .collect();
+    let mut moved = 0;
+    for id in &ids {
+        let (before, after) = (id.jump_hash(10), id.jump_hash(11));
+        assert!(before < 10 && after < 11);
+        if before != after {
+            assert_eq!(after, 10);
+            moved += 1;
+        }
+    }
+    assert!((700..1300).contains(&moved), "{}", moved);
+}
+
+#[test]
+#[should_panic(expected = "buckets must be non-zero")]
+fn test_jump_hash_zero_panics() {
+    MicroShardUUID::from_micros(0, 0).unwrap().jump_hash(0);
+}
+
+#[test]
+fn test_shard_routing_table() {
+    use microshard_uuid::routing::{Route, RoutingError, ShardRoutingTable};
+
+    let table = ShardRoutingTable::new(vec![
+        Route::new(2048, u32::MAX, "us"),
+        Route::new(0, 1023, "eu"),
+        Route::new(1024, 2047, "ap"),
+    ])
+    .unwrap();
+    assert_eq!(
+        table.routes().iter().map(|r| r.first).collect::<Vec<_>>(),
+        [0, 1024, 2048]
+    );
+    for (shard, dest) in [
+        (0, "eu"),
+        (1023, "eu"),
+        (1024, "ap"),
+        (2047, "ap"),
+        (2048, "us"),
+        (u32::MAX, "us"),
+    ] {
+        assert_eq!(*table.route_shard(shard), dest);
+    }
+    let id = MicroShardUUID::from_micros(1_700_000_000_000_000, 1500).unwrap();
+    assert_eq!(*table.route(&id), "ap");
+    assert!(table.routes()[1].contains(1500));
+
+    // Validation reports the first problem in shard order
+    let err = |routes: Vec<Route<&str>>| ShardRoutingTable::new(routes).unwrap_err();
+    assert_eq!(err(vec![]), RoutingError::Empty);
+    assert_eq!(
+        err(vec![Route::new(0, u32::MAX, "a"), Route::new(9, 5, "b")]),
+        RoutingError::InvertedRange { first: 9, last: 5 }
+    );
+    assert_eq!(
+        err(vec![Route::new(0, 100, "a"), Route::new(50, u32::MAX, "b")]),
+        RoutingError::Overlap {
+            first: 50,
+            last: 100
+        }
+    );
+    assert_eq!(
+        err(vec![
+            Route::new(0, 100, "a"),
+            Route::new(150, u32::MAX, "b")
+        ]),
+        RoutingError::Gap {
+            first: 101,
+            last: 149
+        }
+    );
+    assert_eq!(
+        err(vec![Route::new(10, u32::MAX, "a")]),
+        RoutingError::Gap { first: 0, last: 9 }
+    );
+    let gap = err(vec![Route::new(0, 1023, "a")]);
+    assert_eq!(
+        gap,
+        RoutingError::Gap {
+            first: 1024,
+            last: u32::MAX
+        }
+    );
+    assert_eq!(gap.to_string(), "Shards 1024..=4294967295 have no route");
+    assert_eq!(
+        RoutingError::Overlap {
+            first: 50,
+            last: 100
+        }
+        .to_string(),
+        "Shards 50..=100 are routed more than once"
+    );
+}
+
+#[test]
+fn test_time_buckets() {
+    // 2024-02-29T23:59:59.999999Z, a leap day at the end of an hour
+    let id = MicroShardUUID::from_micros(1_709_251_199_999_999, 3).unwrap();
+    assert_eq!(id.time_bucket(Bucket::Hour), 1_709_251_199 / 3600);
+    assert_eq!(id.time_bucket(Bucket::Day), 19_782);
+    assert_eq!(id.time_bucket(Bucket::Month), 54 * 12 + 1);
+    assert_eq!(
+        id.time_bucket_label(Bucket::Hour).to_string(),
+        "2024-02-29T23"
+    );
+    assert_eq!(id.time_bucket_label(Bucket::Day).to_string(), "2024-02-29");
+    assert_eq!(id.time_bucket_label(Bucket::Month).to_string(), "2024-02");
+
+    // One microsecond later every bucket rolls over
+    let next = MicroShardUUID::from_micros(1_709_251_200_000_000, 3).unwrap();
+    for bucket in [Bucket::Hour, Bucket::Day, Bucket::Month] {
+        assert_eq!(next.time_bucket(bucket), id.time_bucket(bucket) + 1);
+    }
+    assert_eq!(
+        next.time_bucket_label(Bucket::Hour).to_string(),
+        "2024-03-01T00"
+    );
+
+    let epoch = MicroShardUUID::from_micros(0, 0).unwrap();
+    for bucket in [Bucket::Hour, Bucket::Day, Bucket::Month] {
+        assert_eq!(epoch.time_bucket(bucket), 0);
+    }
+    assert_eq!(
+        epoch.time_bucket_label(Bucket::Month).to_string(),
+        "1970-01"
+    );
+}
+
+#[test]
+fn test_truncate_to() {
+    let t = 1_709_251_199_999_999; // 2024-02-29T23:59:59.999999Z
+    let id = MicroShardUUID::from_micros(t, 77).unwrap();
+    for (unit, floored) in [
+        (TimeUnit::Millisecond, 1_709_251_199_999_000),
+        (TimeUnit::Second, 1_709_251_199_000_000),
+        (TimeUnit::Minute, 1_709_251_140_000_000),
+        (TimeUnit::Hour, 1_709_247_600_000_000),
+        (TimeUnit::Day, 1_709_164_800_000_000),
+    ] {
+        let key = id.truncate_to(unit);
+        assert_eq!(key.timestamp_micros(), floored);
+        assert_eq!((key.shard_id(), key.random()), (77, 0));
+        assert!(key <= id);
+        assert_eq!(key.truncate_to(unit), key);
+        assert_eq!(
+            unit.micros(),
+            match unit {
+                TimeUnit::Millisecond => 1_000,
+                TimeUnit::Second => 1_000_000,
+                TimeUnit::Minute => 60_000_000,
+                TimeUnit::Hour => 3_600_000_000,
+                TimeUnit::Day => 86_400_000_000,
+            }
+        );
+    }
+
+    // Same unit and shard: same key; different shard: different key
+    let other = MicroShardUUID::from_micros(t - 500_000, 77).unwrap();
+    assert_eq!(
+        other.truncate_to(TimeUnit::Second),
+        id.truncate_to(TimeUnit::Second)
+    );
+    let elsewhere = MicroShardUUID::from_micros(t, 78).unwrap();
+    assert_ne!(
+        elsewhere.truncate_to(TimeUnit::Second),
+        id.truncate_to(TimeUnit::Second)
+    );
+}
+
+#[test]
+fn test_successor_predecessor() {
+    let max_random = (1u64 << 36) - 1;
+    let max_time = (1u64 << 54) - 1;
+    let step = |micros, shard, random| MicroShardUUID::from_parts(micros, shard, random).unwrap();
+
+    // Plain increment, then carries across each field boundary
+    for (from, to) in [
+        (step(5, 7, 9), step(5, 7, 10)),
+        (step(5, 7, max_random), step(5, 8, 0)),
+        (step(5, u32::MAX, max_random), step(6, 0, 0)),
+        // time_low (6 bits) rolling into time_high
+        (step(63, u32::MAX, max_random), step(64, 0, 0)),
+    ] {
+        assert_eq!(from.successor(), Some(to));
+        assert_eq!(to.predecessor(), Some(from));
+        assert!(from < to);
+        assert_eq!(MicroShardUUID::from_u128(to.as_u128()), Ok(to));
+    }
+
+    // Carrying out of the low 26 shard bits crosses the variant bits, where
+    // a naive `u128 + 1` lands on an invalid value
+    let below = step(5, 0x03FF_FFFF, max_random);
+    assert!(MicroShardUUID::from_u128(below.as_u128() + 1).is_err());
+    assert_eq!(below.successor(), Some(step(5, 0x0400_0000, 0)));
+    let below = step(5, u32::MAX, max_random);
+    assert!(MicroShardUUID::from_u128(below.as_u128() + 1).is_err());
+
+    let largest = step(max_time, u32::MAX, max_random);
+    assert_eq!(largest.successor(), None);
+    assert_eq!(step(0, 0, 0).predecessor(), None);
+    assert_eq!(largest.predecessor().unwrap().successor(), Some(largest));
+
+    // Round trips on random IDs
+    for _ in 0..100 {
+        let id = MicroShardUUID::generate(42).unwrap();
+        assert!(id.successor().unwrap() > id);
+        assert_eq!(id.successor().unwrap().predecessor(), Some(id));
+    }
+}
+
+#[test]
+fn test_time_range() {
+    use microshard_uuid::search::TimeRange;
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::ops::RangeBounds;
+
+    let mut index = BTreeMap::new();
+    for t in [10, 20, 20, 30, 40] {
+        for shard in [0, u32::MAX] {
+            index.insert(MicroShardUUID::from_micros(t, shard).unwrap(), t);
+        }
+    }
+    let times = |range: TimeRange| -> Vec<u64> {
+        let set: BTreeSet<_> = index.range(range).map(|(_, t)| *t).collect();
+        set.into_iter().collect()
+    };
+    assert_eq!(times(TimeRange::new(20..40)), [20, 30]);
+    assert_eq!(times(TimeRange::new(20..=40)), [20, 30, 40]);
+    assert_eq!(
+        times(TimeRange::new((
+            std::ops::Bound::Excluded(20),
+            std::ops::Bound::Unbounded
+        ))),
+        [30, 40]
+    );
+    assert_eq!(times(TimeRange::new(..30)), [10, 20]);
+    assert_eq!(times(TimeRange::new(..)), [10, 20, 30, 40]);
+    assert_eq!(index.range(TimeRange::new(20..40)).count(), 6);
+
+    // Empty and inverted intervals are empty ranges, not panics
+    #[allow(clippy::reversed_empty_ranges)]
+    for range in [
+        TimeRange::new(20..20),
+        TimeRange::new(40..10),
+        TimeRange::new(..0),
+        TimeRange::new(u64::MAX..),
+    ] {
+        assert!(range.is_empty());
+        assert_eq!(range.bounds(), None);
+        assert_eq!(index.range(range).count(), 0);
+    }
+
+    // Bounds and clamping
+    let range = TimeRange::new(5..u64::MAX);
+    let (lo, hi) = range.bounds().unwrap();
+    assert_eq!(lo, MicroShardUUID::min_for_micros(5).unwrap());
+    assert_eq!(hi.successor(), None);
+    assert!(range.contains(&MicroShardUUID::from_micros(5, 9).unwrap()));
+    assert!(!range.contains(&MicroShardUUID::from_micros(4, 9).unwrap()));
+
+    // Inclusive pairs for BETWEEN predicates
+    let (lo, hi) = MicroShardUUID::range_for_micros(20..40);
+    assert_eq!(lo, MicroShardUUID::min_for_micros(20).unwrap());
+    assert_eq!(hi, MicroShardUUID::max_for_micros(39).unwrap());
+    #[allow(clippy::reversed_empty_ranges)]
+    let (lo, hi) = MicroShardUUID::range_for_micros(40..20);
+    assert!(lo > hi);
+    let (lo, hi) = MicroShardUUID::range_for_micros(0..0);
+    assert!(lo > hi);
+}
+
+#[test]
+fn test_cursor_tokens() {
+    let id: MicroShardUUID = "01234567-89ab-8def-8123-456789abcdef".parse().unwrap();
+
+    // Fixed vectors (independently computed with Python's base64 and hmac)
+    let token = Cursor::after(id).encode();
+    assert_eq!(token, "AQABI0VniauN74EjRWeJq83v");
+    assert_eq!(
+        Cursor::decode(&token),
+        Ok(Cursor::new(id, Direction::Forward))
+    );
+    let signed = Cursor::after(id).encode_signed(b"secret");
+    assert_eq!(signed, "AQLahccTRI_1DNhF_ZwayqDPeShG-pw5Ckai7Z-vhiuYoA");
+    // The last 16 bytes hold the ID, encrypted rather than in the clear
+    assert_ne!(&signed[24..], id.to_base64url());
+    // Keys longer than a SHA-256 block are hashed first
+    let long_key = [b'k'; 100];
+    let back = Cursor::before(id).encode_signed(&long_key);
+    assert_eq!(back, "AQOokU4twJ3IdbICmhgU9A-HmxkhahMjyoECzvJTCpsu2Q");
+    assert_eq!(
+        Cursor::decode_signed(&back, &long_key),
+        Ok(Cursor::new(id, Direction::Backward))
+    );
+
+    // Round trips are URL-safe
+    for _ in 0..50 {
+        let cursor = Cursor::before(MicroShardUUID::generate(9).unwrap());
+        let (plain, signed) = (cursor.encode(), cursor.encode_signed(b"k"));
+        assert_eq!((plain.len(), signed.len()), (24, 46));
+        assert!(signed
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_'));
+        assert_eq!(Cursor::decode(&plain), Ok(cursor));
+        assert_eq!(Cursor::decode_signed(&signed, b"k"), Ok(cursor));
+        assert_ne!(&signed[24..], cursor.id.to_base64url());
+    }
+
+    // Signatures: wrong key, tampering, and mixing signed/unsigned decoding
+    assert_eq!(
+        Cursor::decode_signed(&signed, b"other"),
+        Err(MicroShardError::InvalidSignature)
+    );
+    let mut tampered = signed.clone().into_bytes();
+    tampered[10] = if tampered[10] == b'A' { b'B' } else { b'A' };
+    let tampered = String::from_utf8(tampered).unwrap();
+    assert_eq!(
+        Cursor::decode_signed(&tampered, b"secret"),
+        Err(MicroShardError::InvalidSignature)
+    );
+    // Flipping ciphertext bits must not yield a different valid cursor
+    let mut flipped = signed.clone().into_bytes();
+    flipped[40] = if flipped[40] == b'A' { b'B' } else { b'A' };
+    assert_eq!(
+        Cursor::decode_signed(&String::from_utf8(flipped).unwrap(), b"secret"),
+        Err(MicroShardError::InvalidSignature)
+    );
+    assert_eq!(
+        Cursor::decode(&signed),
+        Err(MicroShardError::InvalidSignature)
+    );
+    assert_eq!(
+        Cursor::decode_signed(&token, b"secret"),
+        Err(MicroShardError::InvalidSignature)
+    );
+    assert_eq!(
+        MicroShardError::InvalidSignature.to_string(),
+        "Missing or invalid signature"
+    );
+
+    // Malformed tokens
+    for bad in [
+        "",
+        "A",
+        "AQABI0VniauN74EjRWeJq83",
+        "AQABI0VniauN74EjRWeJq83+",
+        "AgABI0VniauN74EjRWeJq83v",
+    ] {
+        assert_eq!(
+            Cursor::decode(bad),
+            Err(MicroShardError::InvalidEncoding),
+            "{}",
+            bad
+        );
+    }
+    // A well-formed token around a non-MicroShard UUID (v4)
+    assert!(matches!(
+        Cursor::decode("AQBVDoQA4ptB1KcWRGZVRAAA"),
+        Err(MicroShardError::InvalidVersion(4))
+    ));
+}
@@ -0,0 +1,133 @@
+// File: tests/local_time.rs
+// Requires the OS TZ database (e.g. the `tzdata` package).
+#![cfg(all(feature = "local-time", feature = "gen", feature = "parse"))]
+
+use microshard_uuid::local_time::TimeZone;
+use microshard_uuid::{MicroShardError, MicroShardUUID};
+
+#[test]
+fn test_fixed_offset_zone() {
+    let uuid = MicroShardUUID::from_iso("2024-05-01T10:00:00.123456Z", 1).unwrap();
+    assert_eq!(
+        uuid.to_local_string("Asia/Kolkata").unwrap(),
+        "2024-05-01T15:30:00.123456+05:30"
+    );
+    assert_eq!(
+        uuid.to_local_string("UTC").unwrap(),
+        "2024-05-01T10:00:00.123456+00:00"
+    );
+}
+
+#[test]
+fn test_dst_transitions() {
+    let tz = TimeZone::load("America/New_York").unwrap();
+
+    let winter = MicroShardUUID::from_iso("2024-01-15T12:00:00Z", 1).unwrap();
+    let summer = MicroShardUUID::from_iso("2024-07-15T12:00:00Z", 1).unwrap();
+    assert_eq!(
+        winter.to_local_string_in(&tz),
+        "2024-01-15T07:00:00.000000-05:00"
+    );
+    assert_eq!(
+        summer.to_local_string_in(&tz),
+        "2024-07-15T08:00:00.000000-04:00"
+    );
+
+    // Far future: resolved through the POSIX rule in the TZif footer
+    let future = MicroShardUUID::from_iso("2300-07-04T12:00:00Z", 1).unwrap();
+    assert_eq!(
+        future.to_local_string_in(&tz),
+        "2300-07-04T08:00:00.000000-04:00"
+    );
+
+    // Southern hemisphere: DST spans the year boundary
+    let sydney = MicroShardUUID::from_iso("2030-01-01T00:00:00Z", 1).unwrap();
+    assert_eq!(
+        sydney.to_local_string("Australia/Sydney").unwrap(),
+        "2030-01-01T11:00:00.000000+11:00"
+    );
+}
+
+#[test]
+fn test_local_date_before_epoch() {
+    let epoch = MicroShardUUID::from_micros(0, 1).unwrap();
+    assert_eq!(
+        epoch.to_local_string("America/New_York").unwrap(),
+        "1969-12-31T19:00:00.000000-05:00"
+    );
+}
+
+#[test]
+fn test_invalid_zone_names() {
+    let uuid = MicroShardUUID::generate(1).unwrap();
+    for name in ["", "Not/AZone", "../etc/passwd", "/etc/localtime"] {
+        assert_eq!(
+            uuid.to_local_string(name),
+            Err(MicroShardError::InvalidTimeZone)
+        );
+    }
+    assert!(TimeZone::from_tzif("Bad", b"not a tzif file").is_err());
+}
+
+/// A minimal version 2 TZif file (a single UTC type) with `footer`.
+fn tzif_with_footer(footer: &str) -> Vec<u8> {
+    let mut block = Vec::new();
+    block.extend_from_slice(b"TZif2");
+    block.extend_from_slice(&[0; 15]);
+    // isutcnt, isstdcnt, leapcnt, timecnt, typecnt, charcnt
+    for count in [0u32, 0, 0, 0, 1, 4] {
+        block.extend_from_slice(&count.to_be_bytes());
+    }
+    block.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+    block.extend_from_slice(b"UTC\0");
+
+    let mut data = block.repeat(2);
+    data.extend_from_slice(format!("\n{}\n", footer).as_bytes());
+    data
+}
+
+#[test]
+fn test_tzif_footer_bounds() {
+    let tz = TimeZone::from_tzif("Fixed", &tzif_with_footer("<+0530>-5:30")).unwrap();
+    assert_eq!(tz.offset_at(0), 19_800);
+    let tz =
+        TimeZone::from_tzif("Late", &tzif_with_footer("AAA3BBB,M3.2.0/167,M11.1.0/-167")).unwrap();
+    assert_eq!(tz.offset_at(0), -3 * 3600);
+
+    // Out-of-range or overflowing fields are rejected, not a panic
+    for footer in [
+        "AAA9999999",
+        "AAA4294967295",
+        "AAA25",
+        "AAA5:60",
+        "AAA5BBB,M3.2.0/168,M11.1.0",
+    ] {
+        assert_eq!(
+            TimeZone::from_tzif("Bad", &tzif_with_footer(footer)),
+            Err(MicroShardError::InvalidTimeZone),
+            "{}",
+            footer
+        );
+    }
+}
+
+#[test]
+fn test_tzif_truncated_counts() {
+    // Header counts far beyond the data are rejected before any allocation
+    for counts in [
+        [0u32, 0, 0, 0x7FFF_FFFF, 1, 0],
+        [0, 0, 0, 0, 0x7FFF_FFFF, 0],
+        [u32::MAX; 6],
+    ] {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"TZif\0");
+        data.extend_from_slice(&[0; 15]);
+        for count in counts {
+            data.extend_from_slice(&count.to_be_bytes());
+        }
+        assert_eq!(
+            TimeZone::from_tzif("Huge", &data),
+            Err(MicroShardError::InvalidTimeZone)
+        );
+    }
+}
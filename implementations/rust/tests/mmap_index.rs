@@ -0,0 +1,57 @@
+// File: tests/mmap_index.rs
+#![cfg(all(feature = "mmap-index", feature = "gen"))]
+
+use microshard_uuid::index::{IndexReader, IndexWriter};
+use microshard_uuid::MicroShardUUID;
+use std::path::PathBuf;
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("microshard-{}-{}.idx", name, std::process::id()))
+}
+
+#[test]
+fn test_index_roundtrip_and_lookup() {
+    let path = temp_path("roundtrip");
+    let base = 1_700_000_000_000_000;
+    let mut ids: Vec<_> = (0..1_000)
+        .map(|i| MicroShardUUID::from_micros(base + i * 10, (i % 7) as u32).unwrap())
+        .collect();
+    let absent = MicroShardUUID::from_micros(base + 5, 1).unwrap();
+
+    assert_eq!(IndexWriter::write_all(&path, &mut ids).unwrap(), 1_000);
+
+    let reader = IndexReader::open(&path).unwrap();
+    assert_eq!(reader.len(), 1_000);
+    assert!(ids.iter().all(|id| reader.contains(id)));
+    assert!(!reader.contains(&absent));
+    assert_eq!(reader.iter().collect::<Vec<_>>(), ids);
+
+    // Range scan by boundary IDs
+    let lo = ids[100];
+    let hi = ids[199];
+    assert_eq!(reader.range(lo..=hi).collect::<Vec<_>>(), &ids[100..200]);
+    assert_eq!(reader.range(lo..hi).count(), 99);
+    assert_eq!(reader.range(hi..lo).count(), 0);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_index_rejects_bad_input() {
+    let path = temp_path("bad");
+    let a = MicroShardUUID::from_micros(2, 1).unwrap();
+    let b = MicroShardUUID::from_micros(1, 1).unwrap();
+
+    let mut writer = IndexWriter::create(&path).unwrap();
+    writer.push(a).unwrap();
+    assert!(writer.push(b).is_err());
+    assert!(writer.push(a).is_err());
+    writer.finish().unwrap();
+
+    // Truncated file fails validation
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::write(&path, &bytes[..bytes.len() - 1]).unwrap();
+    assert!(IndexReader::open(&path).is_err());
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,83 @@
+// File: tests/persistent.rs
+#![cfg(feature = "gen")]
+
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use microshard_uuid::{test_support, PersistentGenerator, DEFAULT_RESERVE_AHEAD};
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("microshard-{}-{}.state", name, std::process::id()))
+}
+
+#[test]
+fn test_resumes_above_high_water_mark() {
+    let path = temp_path("resume");
+    let _ = std::fs::remove_file(&path);
+    let t = 1_765_503_300_000_000;
+    let _guard = test_support::freeze_time(t);
+
+    let mut ids = PersistentGenerator::open(&path, 7).unwrap();
+    assert_eq!(ids.high_water_mark(), 0);
+    let first: Vec<_> = (0..5).map(|_| ids.next().unwrap()).collect();
+    assert!(first.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(first[0].shard_id(), 7);
+    let window = DEFAULT_RESERVE_AHEAD.as_micros() as u64;
+    assert_eq!(ids.high_water_mark(), t + window);
+    drop(ids);
+
+    // "Restart" with the clock stepped back: nothing at or below the mark
+    let _guard = test_support::freeze_time(t - 60_000_000);
+    let mut ids = PersistentGenerator::open(&path, 7).unwrap();
+    assert_eq!(ids.high_water_mark(), t + window);
+    let resumed = ids.next().unwrap();
+    assert_eq!(resumed.timestamp_micros(), t + window);
+    assert!(resumed > *first.last().unwrap());
+    assert!(ids.high_water_mark() > resumed.timestamp_micros());
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_checkpoints_once_per_window() {
+    let path = temp_path("window");
+    let _ = std::fs::remove_file(&path);
+    let t = 1_765_503_300_000_000;
+    let _guard = test_support::freeze_time(t);
+
+    let mut ids = PersistentGenerator::open(&path, 1)
+        .unwrap()
+        .with_reserve_ahead(Duration::from_millis(10));
+    ids.next().unwrap();
+    assert_eq!(ids.high_water_mark(), t + 10_000);
+
+    // Inside the window the mark (and the file) stays put
+    test_support::advance(Duration::from_millis(5));
+    ids.next().unwrap();
+    assert_eq!(ids.high_water_mark(), t + 10_000);
+    assert_eq!(
+        std::fs::read(&path).unwrap()[..8],
+        (t + 10_000).to_le_bytes()
+    );
+
+    // Reaching the mark reserves a new window before the ID is returned
+    test_support::advance(Duration::from_millis(5));
+    let id = ids.next().unwrap();
+    assert_eq!(id.timestamp_micros(), t + 10_000);
+    assert_eq!(ids.high_water_mark(), t + 20_000);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_rejects_bad_state() {
+    let path = temp_path("bad");
+    std::fs::write(&path, b"not a state file").unwrap();
+    let err = PersistentGenerator::open(&path, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    std::fs::write(&path, b"short").unwrap();
+    let err = PersistentGenerator::open(&path, 1).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    std::fs::remove_file(&path).unwrap();
+}
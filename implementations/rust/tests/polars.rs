@@ -0,0 +1,64 @@
+// File: tests/polars.rs
+#![cfg(feature = "polars")]
+
+use microshard_uuid::{polars as ms, MicroShardUUID};
+use polars_core::prelude::*;
+
+#[test]
+fn test_polars_round_trip() {
+    let a = MicroShardUUID::from_parts(1_714_557_600_123_456, 7, 42).unwrap();
+    let b = MicroShardUUID::from_parts(1_714_557_600_123_457, u32::MAX, 0xF_FFFF_FFFF).unwrap();
+
+    let col = ms::to_series("id", &[a, b]);
+    assert_eq!(col.name().as_str(), "id");
+    assert_eq!(col.dtype(), &DataType::Binary);
+    assert_eq!(col.binary().unwrap().get(1), Some(&b.as_bytes()[..]));
+    assert_eq!(ms::from_series(&col).unwrap(), vec![Some(a), Some(b)]);
+
+    let col = ms::to_nullable_series("id", &[None, Some(b)]);
+    assert_eq!(col.null_count(), 1);
+    assert_eq!(ms::from_series(&col).unwrap(), vec![None, Some(b)]);
+}
+
+#[test]
+fn test_polars_extractors() {
+    let a = MicroShardUUID::from_parts(1_714_557_600_123_456, 7, 42).unwrap();
+    let b = MicroShardUUID::from_parts(1_893_456_000_000_000, u32::MAX, 0xF_FFFF_FFFF).unwrap();
+    let col = ms::to_nullable_series("id", &[Some(a), None, Some(b)]);
+
+    let ts = ms::timestamp_micros(&col).unwrap();
+    assert_eq!(ts.name().as_str(), "id");
+    assert_eq!(
+        ts.dtype(),
+        &DataType::Datetime(TimeUnit::Microseconds, Some(TimeZone::UTC))
+    );
+    let micros = ts.datetime().unwrap().physical();
+    assert_eq!(
+        micros.iter().collect::<Vec<_>>(),
+        vec![
+            Some(1_714_557_600_123_456),
+            None,
+            Some(1_893_456_000_000_000)
+        ]
+    );
+
+    let shards = ms::shard_id(&col).unwrap();
+    assert_eq!(
+        shards.u32().unwrap().iter().collect::<Vec<_>>(),
+        vec![Some(7), None, Some(u32::MAX)]
+    );
+}
+
+#[test]
+fn test_polars_rejects_bad_values() {
+    // A v4 UUID is rejected, not silently decoded
+    let v4 = Series::new("id".into(), [&[0x40u8; 16][..]]);
+    assert!(ms::shard_id(&v4).is_err());
+
+    let short = Series::new("id".into(), [&[0u8; 8][..]]);
+    assert!(ms::from_series(&short).is_err());
+
+    // Not a Binary column
+    let ints = Series::new("id".into(), [1i64, 2]);
+    assert!(ms::timestamp_micros(&ints).is_err());
+}
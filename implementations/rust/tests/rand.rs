@@ -0,0 +1,64 @@
+// File: tests/rand.rs
+#![cfg(feature = "rand")]
+
+use microshard_uuid::{test_support, MicroShardGenerator, MicroShardUUID};
+use rand_core::RngCore;
+
+/// Deterministic test RNG: returns 0, 1, 2, ... from `next_u64`.
+struct CountingRng(u64);
+
+impl RngCore for CountingRng {
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let v = self.0;
+        self.0 = self.0.wrapping_add(1);
+        v
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        for chunk in dst.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+}
+
+#[test]
+fn test_generate_with_rng() {
+    let micros = 1_765_503_300_123_456;
+    let _guard = test_support::freeze_time(micros);
+
+    let mut rng = CountingRng(u64::MAX);
+    let a = MicroShardUUID::generate_with_rng(5, &mut rng).unwrap();
+    let b = MicroShardUUID::generate_with_rng(5, &mut rng).unwrap();
+    assert_eq!(a.random(), (1 << 36) - 1); // truncated to 36 bits
+    assert_eq!(b.random(), 0);
+    assert_eq!(a.timestamp_micros(), micros);
+    assert_eq!(a.shard_id(), 5);
+}
+
+#[test]
+fn test_generator_with_rng_is_reproducible() {
+    let run = || {
+        let mut ids = MicroShardGenerator::with_rng(9, CountingRng(100)).unwrap();
+        (0..4)
+            .map(|i| ids.next_at(1_000 + i).unwrap())
+            .collect::<Vec<_>>()
+    };
+    let first = run();
+    assert_eq!(first, run());
+    assert_eq!(first[3].random(), 103);
+
+    // Custom RNG combined with monotonic mode keeps the low 24 bits from the RNG
+    let mut ids = MicroShardGenerator::with_rng(9, CountingRng(7))
+        .unwrap()
+        .into_monotonic();
+    assert!(ids.is_monotonic());
+    let x = ids.next_at(50).unwrap();
+    let y = ids.next_at(50).unwrap();
+    assert!(x < y);
+    assert_eq!(y.random(), (1 << 24) | 8);
+}
@@ -0,0 +1,61 @@
+// File: tests/redis.rs
+#![cfg(feature = "redis")]
+
+mod common;
+
+use common::sample;
+use microshard_uuid::redis::{Storage, STORAGE};
+use microshard_uuid::MicroShardUUID;
+use redis::{FromRedisValue, ToRedisArgs, Value};
+
+#[test]
+fn test_redis_args_wire_format() {
+    let id = sample();
+    let expected = match STORAGE {
+        Storage::Bytes => id.as_bytes().to_vec(),
+        Storage::Text => id.to_string().into_bytes(),
+    };
+    assert_eq!(id.to_redis_args(), vec![expected.clone()]);
+
+    // As a key inside a packed command
+    let packed = redis::cmd("SET")
+        .arg(id)
+        .arg("created")
+        .get_packed_command();
+    let mut wire = format!("*3\r\n$3\r\nSET\r\n${}\r\n", expected.len()).into_bytes();
+    wire.extend_from_slice(&expected);
+    wire.extend_from_slice(b"\r\n$7\r\ncreated\r\n");
+    assert_eq!(packed, wire);
+}
+
+#[test]
+fn test_redis_from_value() {
+    let id = sample();
+    let bytes = Value::BulkString(id.as_bytes().to_vec());
+    let text = Value::BulkString(id.to_string().into_bytes());
+    let simple = Value::SimpleString(id.to_string());
+    for v in [bytes.clone(), text, simple] {
+        assert_eq!(MicroShardUUID::from_redis_value(v).unwrap(), id);
+    }
+
+    // Missing keys and collections go through redis' generic impls
+    assert_eq!(
+        Option::<MicroShardUUID>::from_redis_value(Value::Nil).unwrap(),
+        None
+    );
+    let members = Value::Array(vec![bytes.clone(), bytes]);
+    assert_eq!(
+        Vec::<MicroShardUUID>::from_redis_value(members).unwrap(),
+        vec![id, id]
+    );
+
+    for bad in [
+        Value::Nil,
+        Value::Int(42),
+        Value::BulkString(vec![1, 2]),
+        Value::BulkString(vec![0; 16]),
+        Value::SimpleString("not-a-uuid".to_owned()),
+    ] {
+        assert!(MicroShardUUID::from_redis_value(bad).is_err());
+    }
+}
@@ -0,0 +1,82 @@
+// File: tests/scylla.rs
+#![cfg(feature = "scylla")]
+
+mod common;
+
+use common::sample;
+use microshard_uuid::{MicroShardError, MicroShardUUID};
+use scylla_cql_core::deserialize::value::DeserializeValue;
+use scylla_cql_core::deserialize::FrameSlice;
+use scylla_cql_core::frame::response::result::{ColumnType, NativeType};
+use scylla_cql_core::serialize::value::SerializeValue;
+use scylla_cql_core::serialize::writers::CellWriter;
+use scylla_cql_core::value::CqlTimeuuid;
+
+const UUID: ColumnType<'static> = ColumnType::Native(NativeType::Uuid);
+
+fn serialize(id: &MicroShardUUID, typ: &ColumnType) -> Option<Vec<u8>> {
+    let mut buf = Vec::new();
+    let written = id
+        .serialize(typ, CellWriter::new_without_size(&mut buf))
+        .is_ok();
+    written.then_some(buf)
+}
+
+#[test]
+fn test_scylla_uuid_round_trip() {
+    let id = sample();
+    let bytes = serialize(&id, &UUID).unwrap();
+    assert_eq!(bytes, id.as_bytes());
+
+    assert!(<MicroShardUUID as DeserializeValue>::type_check(&UUID).is_ok());
+    let back = MicroShardUUID::deserialize(&UUID, Some(FrameSlice::new_borrowed(&bytes))).unwrap();
+    assert_eq!(back, id);
+
+    // Other column types, NULL, wrong lengths and non-MicroShard UUIDs fail
+    let text = ColumnType::Native(NativeType::Text);
+    let timeuuid = ColumnType::Native(NativeType::Timeuuid);
+    assert!(serialize(&id, &text).is_none());
+    assert!(serialize(&id, &timeuuid).is_none());
+    assert!(<MicroShardUUID as DeserializeValue>::type_check(&timeuuid).is_err());
+    assert!(MicroShardUUID::deserialize(&UUID, None).is_err());
+    assert!(MicroShardUUID::deserialize(&UUID, Some(FrameSlice::new_borrowed(&[1, 2]))).is_err());
+    let v4 = 0x0123_4567_89ab_4def_8123_4567_89ab_cdef_u128.to_be_bytes();
+    assert!(MicroShardUUID::deserialize(&UUID, Some(FrameSlice::new_borrowed(&v4))).is_err());
+}
+
+#[test]
+fn test_scylla_timeuuid_order() {
+    let id = sample();
+    let timeuuid = CqlTimeuuid::from(id);
+    assert_eq!(timeuuid.as_u128(), id.to_timeuuid());
+    assert_eq!(
+        MicroShardUUID::try_from(timeuuid),
+        MicroShardUUID::from_timeuuid(id.to_timeuuid())
+    );
+    assert_eq!(
+        MicroShardUUID::try_from(CqlTimeuuid::from_u128(id.as_u128())),
+        Err(MicroShardError::InvalidVersion(8))
+    );
+
+    // CqlTimeuuid compares like the database (timestamp, then signed bytes),
+    // so converted IDs cluster in the same order, across shards and in the
+    // same microsecond
+    let micros = 1_765_503_300_123_456;
+    let mut ids = Vec::new();
+    for (dt, shard, random) in [
+        (0, 0, 0),
+        (0, 0, 1 << 6),
+        (0, 0x7F, 0),
+        (0, 0x80, 0),
+        (0, 0x8000_0000, 0),
+        (0, u32::MAX, (1 << 36) - 1),
+        (1, 0, 0),
+        (1, 0xFF, 1 << 35),
+        (1_000_000, 3, 0),
+    ] {
+        ids.push(MicroShardUUID::from_parts(micros + dt, shard, random).unwrap());
+    }
+    let timeuuids: Vec<_> = ids.iter().map(|&id| CqlTimeuuid::from(id)).collect();
+    assert!(ids.windows(2).all(|w| w[0] < w[1]));
+    assert!(timeuuids.windows(2).all(|w| w[0] < w[1]));
+}
@@ -0,0 +1,50 @@
+// File: tests/sea_orm.rs
+#![cfg(feature = "sea-orm")]
+
+mod common;
+
+use common::sample;
+use microshard_uuid::MicroShardUUID;
+use sea_orm::prelude::Uuid;
+use sea_orm::sea_query::{ColumnType, Expr, ExprTrait, Nullable, Query, Value, ValueType};
+use sea_orm::{DbBackend, DbErr, TryFromU64};
+
+#[test]
+fn test_sea_orm_value_round_trip() {
+    let id = sample();
+    let value = Value::from(id);
+    assert_eq!(value, Value::Uuid(Some(Uuid::from_bytes(id.as_bytes()))));
+    assert_eq!(<MicroShardUUID as ValueType>::try_from(value).unwrap(), id);
+
+    // Options map to NULL, and to the same column type
+    assert_eq!(Value::from(None::<MicroShardUUID>), Value::Uuid(None));
+    assert_eq!(MicroShardUUID::null(), Value::Uuid(None));
+    assert_eq!(MicroShardUUID::column_type(), ColumnType::Uuid);
+    assert_eq!(
+        <Option<MicroShardUUID> as ValueType>::try_from(Value::Uuid(None)).unwrap(),
+        None
+    );
+
+    // Other UUIDs and other value types are rejected
+    let v4 = Uuid::from_u128(0x0123_4567_89ab_4def_8123_4567_89ab_cdef);
+    assert!(<MicroShardUUID as ValueType>::try_from(Value::Uuid(Some(v4))).is_err());
+    assert!(<MicroShardUUID as ValueType>::try_from(Value::from("x")).is_err());
+
+    assert!(matches!(
+        MicroShardUUID::try_from_u64(1),
+        Err(DbErr::ConvertFromU64("MicroShardUUID"))
+    ));
+}
+
+#[test]
+fn test_sea_orm_query_binds_uuid() {
+    let id = sample();
+    let query = Query::select()
+        .column("name")
+        .from("events")
+        .and_where(Expr::col("id").gt(id))
+        .to_owned();
+    let stmt = DbBackend::Postgres.build(&query);
+    assert_eq!(stmt.sql, r#"SELECT "name" FROM "events" WHERE "id" > $1"#);
+    assert_eq!(stmt.values.unwrap().0, vec![Value::from(id)]);
+}
@@ -0,0 +1,25 @@
+// File: tests/secure_rng.rs
+#![cfg(feature = "secure-rng")]
+
+use std::collections::HashSet;
+
+use microshard_uuid::{test_support, MicroShardGenerator, MicroShardUUID, UniqueGenerator};
+
+#[test]
+fn test_secure_rng_generation_paths() {
+    let _guard = test_support::freeze_time(1_765_503_300_123_456);
+
+    let mut ids: Vec<MicroShardUUID> = (0..1_000)
+        .map(|_| MicroShardUUID::generate(3).unwrap())
+        .collect();
+    let mut generator = MicroShardGenerator::new(3).unwrap();
+    ids.extend((0..1_000).map(|_| generator.next().unwrap()));
+    ids.push(UniqueGenerator::new(3).unwrap().next().unwrap());
+
+    let random_fields: HashSet<u64> = ids.iter().map(|id| id.random()).collect();
+    assert_eq!(random_fields.len(), ids.len());
+    assert!(ids.iter().all(|id| id.shard_id() == 3));
+    assert!(ids
+        .iter()
+        .all(|id| MicroShardUUID::from_u128(id.as_u128()) == Ok(*id)));
+}
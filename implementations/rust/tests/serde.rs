@@ -0,0 +1,66 @@
+// File: tests/serde.rs
+#![cfg(all(feature = "serde", feature = "gen"))]
+
+use microshard_uuid::any::AnyUuid;
+use microshard_uuid::MicroShardUUID;
+
+#[test]
+fn test_serde_json_string_form() {
+    let uuid = MicroShardUUID::from_micros(1_765_503_300_123_456, 12345).unwrap();
+    let json = serde_json::to_string(&uuid).unwrap();
+    assert_eq!(json, format!("\"{}\"", uuid));
+    assert_eq!(serde_json::from_str::<MicroShardUUID>(&json).unwrap(), uuid);
+
+    // Non-MicroShard strings are rejected for the strict type...
+    let v4 = "\"550e8400-e29b-41d4-a716-446655440000\"";
+    assert!(serde_json::from_str::<MicroShardUUID>(v4).is_err());
+
+    // ...but round-trip through AnyUuid
+    let any: AnyUuid = serde_json::from_str(v4).unwrap();
+    assert!(matches!(any, AnyUuid::V4(_)));
+    assert_eq!(serde_json::to_string(&any).unwrap(), v4);
+
+    let mixed: Vec<AnyUuid> = serde_json::from_str(&format!("[{}, \"{}\"]", v4, uuid)).unwrap();
+    assert_eq!(mixed[1].as_microshard(), Some(&uuid));
+}
+
+#[test]
+fn test_routing_table_from_config() {
+    use microshard_uuid::routing::{Route, ShardRoutingTable};
+
+    let config = r#"[
+        { "first": 1024, "last": 4294967295, "destination": "pg-us" },
+        { "destination": "pg-eu", "first": 0, "last": 1023 }
+    ]"#;
+    let table: ShardRoutingTable<String> = serde_json::from_str(config).unwrap();
+    assert_eq!(table.route_shard(7), "pg-eu");
+    assert_eq!(table.route_shard(1024), "pg-us");
+
+    // Round-trips as the sorted route list
+    let json = serde_json::to_string(&table).unwrap();
+    assert_eq!(
+        json,
+        r#"[{"first":0,"last":1023,"destination":"pg-eu"},{"first":1024,"last":4294967295,"destination":"pg-us"}]"#
+    );
+    assert_eq!(
+        serde_json::from_str::<ShardRoutingTable<String>>(&json).unwrap(),
+        table
+    );
+    let route: Route<u8> = serde_json::from_str("[0, 9, 3]").unwrap();
+    assert_eq!(route, Route::new(0, 9, 3));
+
+    // Invalid tables and routes are rejected while loading
+    let gap = r#"[{ "first": 0, "last": 1023, "destination": "pg-eu" }]"#;
+    let err = serde_json::from_str::<ShardRoutingTable<String>>(gap).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Shards 1024..=4294967295 have no route"),
+        "{}",
+        err
+    );
+    let missing = r#"[{ "first": 0, "destination": "pg-eu" }]"#;
+    let err = serde_json::from_str::<ShardRoutingTable<String>>(missing).unwrap_err();
+    assert!(err.to_string().contains("missing field `last`"), "{}", err);
+    let unknown = r#"[{ "first": 0, "last": 1, "dest": "x" }]"#;
+    assert!(serde_json::from_str::<ShardRoutingTable<String>>(unknown).is_err());
+}
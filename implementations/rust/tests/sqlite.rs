@@ -0,0 +1,161 @@
+// File: tests/sqlite.rs
+#![cfg(all(feature = "rusqlite", feature = "gen"))]
+
+use microshard_uuid::{sqlite, MicroShardUUID};
+use rusqlite::Connection;
+
+fn setup() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    sqlite::register_functions(&conn).unwrap();
+    conn.execute_batch("CREATE TABLE events (id BLOB PRIMARY KEY)")
+        .unwrap();
+    conn
+}
+
+#[test]
+fn test_sqlite_extract_functions() {
+    let conn = setup();
+    let uuid = MicroShardUUID::from_iso("2024-05-01T10:00:00.123456Z", 4242).unwrap();
+    conn.execute("INSERT INTO events VALUES (?1)", [uuid.as_bytes().to_vec()])
+        .unwrap();
+
+    let (ts, shard): (i64, i64) = conn
+        .query_row(
+            "SELECT microshard_time(id), microshard_shard(id) FROM events",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!(ts as u64, uuid.timestamp_micros());
+    assert_eq!(shard, 4242);
+
+    let null: Option<i64> = conn
+        .query_row("SELECT microshard_time(NULL)", [], |r| r.get(0))
+        .unwrap();
+    assert_eq!(null, None);
+
+    // Malformed blobs surface as SQL errors
+    assert!(conn
+        .query_row("SELECT microshard_shard(X'0102')", [], |r| r
+            .get::<_, i64>(0))
+        .is_err());
+}
+
+#[test]
+fn test_sqlite_range_function() {
+    let conn = setup();
+    for iso in [
+        "2024-04-30T23:59:59.999999Z",
+        "2024-05-01T00:00:00Z",
+        "2024-05-15T12:00:00Z",
+        "2024-06-01T00:00:00Z",
+    ] {
+        // Stored in this build's format, which the range bounds match
+        let id = MicroShardUUID::from_iso(iso, 1).unwrap();
+        conn.execute("INSERT INTO events VALUES (?1)", [id])
+            .unwrap();
+    }
+
+    let count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM events \
+             WHERE microshard_range(id, '2024-05-01T00:00:00Z', '2024-06-01T00:00:00Z')",
+            [],
+            |r| r.get(0),
+        )
+        .unwrap();
+    assert_eq!(count, 2);
+
+    // Index-friendly form: same rows, answered by a search on the key
+    let by_bounds = "SELECT COUNT(*) FROM events \
+        WHERE id >= microshard_range_start('2024-05-01T00:00:00Z') \
+          AND id < microshard_range_end('2024-06-01T00:00:00Z')";
+    let count: i64 = conn.query_row(by_bounds, [], |r| r.get(0)).unwrap();
+    assert_eq!(count, 2);
+    let plan: String = conn
+        .query_row(&format!("EXPLAIN QUERY PLAN {}", by_bounds), [], |r| {
+            r.get(3)
+        })
+        .unwrap();
+    assert!(plan.starts_with("SEARCH"), "{}", plan);
+
+    let start: MicroShardUUID = conn
+        .query_row(
+            "SELECT microshard_range_start('2024-05-01T00:00:00Z')",
+            [],
+            |r| r.get(0),
+        )
+        .unwrap();
+    assert_eq!(
+        start,
+        MicroShardUUID::min_for_micros(1_714_521_600_000_000).unwrap()
+    );
+    assert!(conn
+        .query_row("SELECT microshard_range_end('yesterday')", [], |r| {
+            r.get::<_, MicroShardUUID>(0)
+        })
+        .is_err());
+}
+
+#[test]
+fn test_sqlite_to_sql_from_sql() {
+    use microshard_uuid::sqlite::{Storage, STORAGE};
+
+    let conn = setup();
+    let first = MicroShardUUID::from_iso("2024-05-01T10:00:00Z", 7).unwrap();
+    let second = MicroShardUUID::from_iso("2024-05-01T10:00:01Z", 3).unwrap();
+    for id in [second, first] {
+        conn.execute("INSERT INTO events VALUES (?1)", [id])
+            .unwrap();
+    }
+
+    // Either storage keeps creation order under ORDER BY
+    let mut stmt = conn.prepare("SELECT id FROM events ORDER BY id").unwrap();
+    let ids: Vec<MicroShardUUID> = stmt
+        .query_map([], |r| r.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(ids, vec![first, second]);
+
+    let (kind, len): (String, i64) = conn
+        .query_row(
+            "SELECT typeof(id), length(id) FROM events LIMIT 1",
+            [],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )
+        .unwrap();
+    match STORAGE {
+        Storage::Blob => assert_eq!((kind.as_str(), len), ("blob", 16)),
+        Storage::Text => assert_eq!((kind.as_str(), len), ("text", 36)),
+    }
+    let found: i64 = conn
+        .query_row("SELECT COUNT(*) FROM events WHERE id = ?1", [first], |r| {
+            r.get(0)
+        })
+        .unwrap();
+    assert_eq!(found, 1);
+
+    // Reads accept both formats whatever the build writes
+    let blob: MicroShardUUID = conn
+        .query_row("SELECT ?1", [first.as_bytes().to_vec()], |r| r.get(0))
+        .unwrap();
+    let text: MicroShardUUID = conn
+        .query_row("SELECT ?1", [first.to_string()], |r| r.get(0))
+        .unwrap();
+    assert_eq!((blob, text), (first, first));
+    let shard: i64 = conn
+        .query_row("SELECT microshard_shard(?1)", [first.to_string()], |r| {
+            r.get(0)
+        })
+        .unwrap();
+    assert_eq!(shard, 7);
+
+    for bad in ["SELECT X'0102'", "SELECT 'not-a-uuid'", "SELECT 42"] {
+        assert!(conn
+            .query_row(bad, [], |r| r.get::<_, MicroShardUUID>(0))
+            .is_err());
+    }
+    let null: Option<MicroShardUUID> = conn.query_row("SELECT NULL", [], |r| r.get(0)).unwrap();
+    assert_eq!(null, None);
+}
@@ -0,0 +1,105 @@
+// File: tests/sqlx.rs
+#![cfg(any(
+    feature = "sqlx-postgres",
+    feature = "sqlx-mysql",
+    feature = "sqlx-sqlite"
+))]
+
+mod common;
+
+use common::sample;
+use microshard_uuid::MicroShardUUID;
+
+#[cfg(feature = "sqlx-postgres")]
+#[test]
+fn test_postgres_encodes_uuid_bytes() {
+    use sqlx_core::encode::{Encode, IsNull};
+    use sqlx_core::types::Type;
+    use sqlx_postgres::types::Oid;
+    use sqlx_postgres::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, Postgres};
+
+    assert_eq!(
+        <MicroShardUUID as Type<Postgres>>::type_info(),
+        PgTypeInfo::with_oid(Oid(2950))
+    );
+    assert_eq!(
+        MicroShardUUID::array_type_info(),
+        PgTypeInfo::with_oid(Oid(2951))
+    );
+
+    let mut buf = PgArgumentBuffer::default();
+    let is_null = <MicroShardUUID as Encode<Postgres>>::encode_by_ref(&sample(), &mut buf);
+    assert!(matches!(is_null.unwrap(), IsNull::No));
+    assert_eq!(buf[..], sample().as_bytes());
+}
+
+#[cfg(feature = "sqlx-mysql")]
+#[test]
+fn test_mysql_encodes_binary16() {
+    use sqlx_core::encode::{Encode, IsNull};
+    use sqlx_core::types::Type;
+    use sqlx_mysql::MySql;
+
+    assert_eq!(
+        <MicroShardUUID as Type<MySql>>::type_info(),
+        <[u8] as Type<MySql>>::type_info()
+    );
+
+    // Length-encoded: one length byte, then the 16 raw bytes
+    let mut buf = Vec::new();
+    let is_null = <MicroShardUUID as Encode<MySql>>::encode_by_ref(&sample(), &mut buf);
+    assert!(matches!(is_null.unwrap(), IsNull::No));
+    assert_eq!(buf[0], 16);
+    assert_eq!(buf[1..], sample().as_bytes());
+}
+
+#[cfg(feature = "sqlx-sqlite")]
+#[test]
+fn test_sqlite_blob_round_trip() {
+    use futures_executor::block_on;
+    use sqlx_core::connection::Connection;
+    use sqlx_core::query::query;
+    use sqlx_core::query_scalar::query_scalar;
+    use sqlx_sqlite::{Sqlite, SqliteConnection};
+
+    block_on(async {
+        let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+        query("CREATE TABLE events (id BLOB PRIMARY KEY)")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        let id = sample();
+        query::<Sqlite>("INSERT INTO events (id) VALUES (?)")
+            .bind(id)
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        let (kind, len): (String, i64) =
+            sqlx_core::query_as::query_as("SELECT typeof(id), length(id) FROM events")
+                .fetch_one(&mut conn)
+                .await
+                .unwrap();
+        assert_eq!((kind.as_str(), len), ("blob", 16));
+
+        let back: MicroShardUUID = query_scalar("SELECT id FROM events")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(back, id);
+
+        // Columns filled with canonical strings decode too
+        let text: MicroShardUUID = query_scalar("SELECT '01234567-89ab-8def-8123-456789abcdef'")
+            .fetch_one(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(text, id);
+
+        // Other blobs are rejected rather than misread
+        let err = query_scalar::<Sqlite, MicroShardUUID>("SELECT x'0102'")
+            .fetch_one(&mut conn)
+            .await;
+        assert!(err.is_err());
+    });
+}